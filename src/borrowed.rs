@@ -0,0 +1,78 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::Json;
+
+// a parsed document that borrows from its input: numbers always point into
+// the text, strings and keys only need an allocation when they contain
+// escapes. object members keep their input order (see JsonRef::parse)
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum JsonRef<'a> {
+    Null,
+    Bool(bool),
+    Number(&'a str),
+    String(Cow<'a,str>),
+    Array(Vec<JsonRef<'a>>),
+    Object(Vec<(Cow<'a,str>,JsonRef<'a>)>)
+}
+
+impl<'a> JsonRef<'a> {
+    pub fn as_number(&self)->Option<&'a str> {
+        match self {
+            JsonRef::Number(num)=>Some(num),
+            _=>None
+        }
+    }
+    pub fn as_string(&self)->Option<&str> {
+        match self {
+            JsonRef::String(str)=>Some(str),
+            _=>None
+        }
+    }
+    pub fn as_bool(&self)->Option<&bool> {
+        match self {
+            JsonRef::Bool(b)=>Some(b),
+            _=>None
+        }
+    }
+    pub fn as_null(&self)->Option<()> {
+        match self {
+            JsonRef::Null=>Some(()),
+            _=>None
+        }
+    }
+    pub fn as_array(&self)->Option<&Vec<JsonRef<'a>>> {
+        match self {
+            JsonRef::Array(vec)=>Some(vec),
+            _=>None
+        }
+    }
+    pub fn as_object(&self)->Option<&Vec<(Cow<'a,str>,JsonRef<'a>)>> {
+        match self {
+            JsonRef::Object(members)=>Some(members),
+            _=>None
+        }
+    }
+
+    // like Json, the last of several members with the same key wins
+    pub fn get(&self,key:&str)->Option<&JsonRef<'a>> {
+        self.as_object()?.iter().rev().find(|(k,_)|k == key).map(|(_,value)|value)
+    }
+
+    pub fn to_owned(&self)->Json {
+        match self {
+            JsonRef::Null=>Json::Null,
+            JsonRef::Bool(b)=>Json::Bool(*b),
+            JsonRef::Number(num)=>Json::Number(String::from(*num)),
+            JsonRef::String(str)=>Json::String(String::from(str.as_ref())),
+            JsonRef::Array(vec)=>Json::Array(vec.iter().map(JsonRef::to_owned).collect()),
+            JsonRef::Object(members)=>{
+                let mut map = HashMap::with_capacity(members.len());
+                for (key,value) in members {
+                    map.insert(String::from(key.as_ref()), value.to_owned());
+                }
+                Json::Object(map)
+            }
+        }
+    }
+}
@@ -0,0 +1,715 @@
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::canonical::format_double;
+use crate::cbor::{base64,BASE64_ALPHABET};
+use crate::number::Decimal;
+use crate::pointer::escape_token;
+
+// decoding errors carry the byte offset of the offending element.
+// encoding errors carry the json pointer of the value that cannot be
+// written: numbers that overflow a double, extended json with invalid
+// contents and keys holding a nul character
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum BsonErr {
+    NotADocument,
+    UnexpectedEnd,
+    InvalidLength(usize),
+    InvalidType(usize),
+    InvalidUtf8(usize),
+    InvalidValue(usize),
+    TrailingBytes(usize),
+    // documents and arrays nested more than 100 levels deep
+    TooDeep(usize),
+    InvalidNumber(String),
+    InvalidExtendedJson(String),
+    InvalidKey(String)
+}
+
+const DOUBLE : u8 = 0x01;
+const STRING : u8 = 0x02;
+const DOCUMENT : u8 = 0x03;
+const ARRAY : u8 = 0x04;
+const BINARY : u8 = 0x05;
+const UNDEFINED : u8 = 0x06;
+const OBJECT_ID : u8 = 0x07;
+const BOOLEAN : u8 = 0x08;
+const DATE : u8 = 0x09;
+const NULL : u8 = 0x0a;
+const REGEX : u8 = 0x0b;
+const DB_POINTER : u8 = 0x0c;
+const CODE : u8 = 0x0d;
+const SYMBOL : u8 = 0x0e;
+const CODE_WITH_SCOPE : u8 = 0x0f;
+const INT32 : u8 = 0x10;
+const TIMESTAMP : u8 = 0x11;
+const INT64 : u8 = 0x12;
+const DECIMAL128 : u8 = 0x13;
+const MIN_KEY : u8 = 0xff;
+const MAX_KEY : u8 = 0x7f;
+
+const MS_PER_DAY : i64 = 86_400_000;
+
+// days since 1970-01-01 of a proleptic gregorian date
+fn days_from_civil(year:i64,month:i64,day:i64)->i64 {
+    let year = if month <= 2 {year - 1} else {year};
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days:i64)->(i64,i64,i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 {mp + 3} else {mp - 9};
+    let year = yoe + era * 400 + if month <= 2 {1} else {0};
+    (year,month,day)
+}
+
+// milliseconds since the epoch as YYYY-MM-DDTHH:MM:SS[.mmm]Z
+fn format_date(ms:i64)->String {
+    let (year,month,day) = civil_from_days(ms.div_euclid(MS_PER_DAY));
+    let time = ms.rem_euclid(MS_PER_DAY);
+    let mut text = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,month,day,time / 3_600_000,time / 60_000 % 60,time / 1000 % 60);
+    if time % 1000 != 0 {
+        text.push_str(&format!(".{:03}",time % 1000));
+    }
+    text.push('Z');
+    text
+}
+
+fn parse_fixed(text:&str,range:std::ops::Range<usize>)->Option<i64> {
+    let part = text.get(range)?;
+    if !part.bytes().all(|b|b.is_ascii_digit()) {
+        return None;
+    }
+    part.parse().ok()
+}
+
+// YYYY-MM-DDTHH:MM:SS, an optional fraction and Z or an offset
+fn parse_date(text:&str)->Option<i64> {
+    let bytes = text.as_bytes();
+    if bytes.len() < 20 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' || bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+    let (year,month,day) = (parse_fixed(text, 0..4)?,parse_fixed(text, 5..7)?,parse_fixed(text, 8..10)?);
+    let (hour,minute,second) = (parse_fixed(text, 11..13)?,parse_fixed(text, 14..16)?,parse_fixed(text, 17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let mut rest = &text[19..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        let digits = format!("{:0<3}",&fraction[..len.min(3)]);
+        millis = digits.parse::<i64>().ok()?;
+        rest = &fraction[len..];
+    }
+    let offset = match rest {
+        "Z"=>0,
+        _=>{
+            let sign = match rest.as_bytes().first() {
+                Some(b'+')=>1,
+                Some(b'-')=>-1,
+                _=>return None
+            };
+            let digits = rest[1..].replace(':', "");
+            if digits.len() != 4 {
+                return None;
+            }
+            sign * (parse_fixed(&digits, 0..2)? * 60 + parse_fixed(&digits, 2..4)?)
+        }
+    };
+    let days = days_from_civil(year, month, day);
+    Some(days * MS_PER_DAY + ((hour * 60 + minute - offset) * 60 + second) * 1000 + millis)
+}
+
+fn base64_decode(text:&str)->Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut bytes = vec![];
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let value = BASE64_ALPHABET.iter().position(|a|*a == c)? as u32;
+        buffer = buffer << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    if bits >= 6 {
+        return None;
+    }
+    Some(bytes)
+}
+
+const DECIMAL128_BIAS : i64 = 6176;
+const DECIMAL128_MAX_EXPONENT : i64 = 6111;
+const DECIMAL128_MAX_COEFFICIENT : u128 = 9_999_999_999_999_999_999_999_999_999_999_999;
+
+// the 16 little-endian bytes of a decimal128 in its binary integer
+// decimal encoding, or None when the text needs rounding to fit
+fn encode_decimal128(text:&str)->Option<[u8;16]> {
+    let (negative,unsigned) = match text.strip_prefix('-') {
+        Some(rest)=>(true,rest),
+        None=>(false,text.strip_prefix('+').unwrap_or(text))
+    };
+    let sign = if negative {1u64 << 63} else {0};
+    let special = match unsigned.to_ascii_lowercase().as_str() {
+        "infinity" | "inf"=>Some(sign | 0x7800_0000_0000_0000),
+        "nan" if !negative=>Some(0x7c00_0000_0000_0000),
+        _=>None
+    };
+    if let Some(high) = special {
+        let mut bytes = [0u8;16];
+        bytes[8..].copy_from_slice(&high.to_le_bytes());
+        return Some(bytes);
+    }
+    let (mantissa,exponent) = match unsigned.find(['e','E']) {
+        Some(i)=>(&unsigned[..i],unsigned[i + 1..].parse::<i64>().ok()?),
+        None=>(unsigned,0)
+    };
+    let (int,frac) = mantissa.split_once('.').unwrap_or((mantissa,""));
+    if int.is_empty() && frac.is_empty() || !int.bytes().chain(frac.bytes()).all(|b|b.is_ascii_digit()) {
+        return None;
+    }
+    let digits = format!("{}{}",int,frac);
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > 34 {
+        return None;
+    }
+    let mut coefficient : u128 = if digits.is_empty() {0} else {digits.parse().ok()?};
+    let mut exponent = exponent.checked_sub(frac.len() as i64)?;
+    while exponent > DECIMAL128_MAX_EXPONENT && coefficient != 0 && coefficient * 10 <= DECIMAL128_MAX_COEFFICIENT {
+        coefficient *= 10;
+        exponent -= 1;
+    }
+    while exponent < -DECIMAL128_BIAS && coefficient != 0 && coefficient.is_multiple_of(10) {
+        coefficient /= 10;
+        exponent += 1;
+    }
+    if coefficient == 0 {
+        exponent = exponent.clamp(-DECIMAL128_BIAS, DECIMAL128_MAX_EXPONENT);
+    }
+    if !(-DECIMAL128_BIAS..=DECIMAL128_MAX_EXPONENT).contains(&exponent) {
+        return None;
+    }
+    let high = sign | ((exponent + DECIMAL128_BIAS) as u64) << 49 | (coefficient >> 64) as u64;
+    let mut bytes = [0u8;16];
+    bytes[..8].copy_from_slice(&(coefficient as u64).to_le_bytes());
+    bytes[8..].copy_from_slice(&high.to_le_bytes());
+    Some(bytes)
+}
+
+// the string form of the decimal128 specification
+fn format_decimal128(bytes:&[u8])->String {
+    let mut low = [0u8;8];
+    let mut high = [0u8;8];
+    low.copy_from_slice(&bytes[..8]);
+    high.copy_from_slice(&bytes[8..16]);
+    let low = u64::from_le_bytes(low);
+    let high = u64::from_le_bytes(high);
+    let sign = if high >> 63 == 1 {"-"} else {""};
+    let (exponent,coefficient) = if (high >> 61) & 3 == 3 {
+        match (high >> 58) & 0x1f {
+            0x1e=>return format!("{}Infinity",sign),
+            0x1f=>return String::from("NaN"),
+            // a coefficient this large is out of range and reads as zero
+            _=>(((high >> 47) & 0x3fff) as i64,0)
+        }
+    }
+    else {
+        let coefficient = ((high & 0x1_ffff_ffff_ffff) as u128) << 64 | low as u128;
+        (((high >> 49) & 0x3fff) as i64,if coefficient > DECIMAL128_MAX_COEFFICIENT {0} else {coefficient})
+    };
+    let exponent = exponent - DECIMAL128_BIAS;
+    let digits = coefficient.to_string();
+    let n = digits.len() as i64;
+    let adjusted = exponent + n - 1;
+    let text = if exponent <= 0 && adjusted >= -6 {
+        if exponent == 0 {
+            digits
+        }
+        else if n > -exponent {
+            let point = (n + exponent) as usize;
+            format!("{}.{}",&digits[..point],&digits[point..])
+        }
+        else {
+            format!("0.{}{}","0".repeat((-exponent - n) as usize),digits)
+        }
+    }
+    else {
+        let mut text = String::from(&digits[..1]);
+        if n > 1 {
+            text.push('.');
+            text.push_str(&digits[1..]);
+        }
+        format!("{}E{}{}",text,if adjusted < 0 {"-"} else {"+"},adjusted.abs())
+    };
+    format!("{}{}",sign,text)
+}
+
+fn write_cstring(out:&mut Vec<u8>,str:&str,path:&str)->Result<(),BsonErr> {
+    if str.contains('\0') {
+        return Err(BsonErr::InvalidKey(String::from(path)));
+    }
+    out.extend_from_slice(str.as_bytes());
+    out.push(0);
+    Ok(())
+}
+
+fn write_string(out:&mut Vec<u8>,str:&str) {
+    out.extend_from_slice(&(str.len() as i32 + 1).to_le_bytes());
+    out.extend_from_slice(str.as_bytes());
+    out.push(0);
+}
+
+fn text(json:Option<&Json>)->Option<&str> {
+    match json {
+        Some(Json::String(str))=>Some(str),
+        _=>None
+    }
+}
+
+fn uint32(json:Option<&Json>)->Option<u32> {
+    match json {
+        Some(Json::Number(num))=>num.parse().ok(),
+        _=>None
+    }
+}
+
+fn object_id(json:Option<&Json>)->Option<Vec<u8>> {
+    let id = text(json)?;
+    if id.len() != 24 {
+        return None;
+    }
+    hex::decode(id).ok()
+}
+
+// the type and contents of an element written as extended json, None when
+// the object is not extended json, or Err when it is but holds bad values
+fn write_extended(map:&HashMap<String,Json>,out:&mut Vec<u8>,path:&mut String)->Result<Option<u8>,BsonErr> {
+    let mut keys : Vec<&str> = map.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    let invalid = ||BsonErr::InvalidExtendedJson(path.clone());
+    let kind = match keys.as_slice() {
+        ["$oid"]=>{
+            out.extend(object_id(map.get("$oid")).ok_or_else(invalid)?);
+            OBJECT_ID
+        },
+        ["$date"]=>{
+            let ms = match map.get("$date") {
+                Some(Json::String(date))=>parse_date(date),
+                Some(Json::Object(inner)) if inner.len() == 1=>text(inner.get("$numberLong")).and_then(|n|n.parse().ok()),
+                Some(Json::Number(num))=>num.parse().ok(),
+                _=>None
+            };
+            out.extend_from_slice(&ms.ok_or_else(invalid)?.to_le_bytes());
+            DATE
+        },
+        ["$binary"]=>{
+            let binary = match map.get("$binary") {
+                Some(Json::Object(binary)) if binary.len() == 2=>binary,
+                _=>return Err(invalid())
+            };
+            let data = text(binary.get("base64")).and_then(base64_decode).ok_or_else(invalid)?;
+            let subtype = text(binary.get("subType")).filter(|s|s.len() <= 2).and_then(|s|u8::from_str_radix(s, 16).ok()).ok_or_else(invalid)?;
+            out.extend_from_slice(&(data.len() as i32).to_le_bytes());
+            out.push(subtype);
+            out.extend(data);
+            BINARY
+        },
+        ["$numberInt"]=>{
+            let value : i32 = text(map.get("$numberInt")).and_then(|n|n.parse().ok()).ok_or_else(invalid)?;
+            out.extend_from_slice(&value.to_le_bytes());
+            INT32
+        },
+        ["$numberLong"]=>{
+            let value : i64 = text(map.get("$numberLong")).and_then(|n|n.parse().ok()).ok_or_else(invalid)?;
+            out.extend_from_slice(&value.to_le_bytes());
+            INT64
+        },
+        ["$numberDouble"]=>{
+            let value : f64 = match text(map.get("$numberDouble")).ok_or_else(invalid)? {
+                "Infinity"=>f64::INFINITY,
+                "-Infinity"=>f64::NEG_INFINITY,
+                "NaN"=>f64::NAN,
+                num=>Decimal::parse(num).and(num.parse().ok()).ok_or_else(invalid)?
+            };
+            out.extend_from_slice(&value.to_le_bytes());
+            DOUBLE
+        },
+        ["$numberDecimal"]=>{
+            out.extend(text(map.get("$numberDecimal")).and_then(encode_decimal128).ok_or_else(invalid)?);
+            DECIMAL128
+        },
+        ["$timestamp"]=>{
+            let (t,i) = match map.get("$timestamp") {
+                Some(Json::Object(ts)) if ts.len() == 2=>(uint32(ts.get("t")),uint32(ts.get("i"))),
+                _=>(None,None)
+            };
+            out.extend_from_slice(&i.ok_or_else(invalid)?.to_le_bytes());
+            out.extend_from_slice(&t.ok_or_else(invalid)?.to_le_bytes());
+            TIMESTAMP
+        },
+        ["$regularExpression"]=>{
+            let (pattern,options) = match map.get("$regularExpression") {
+                Some(Json::Object(regex)) if regex.len() == 2=>(text(regex.get("pattern")),text(regex.get("options"))),
+                _=>(None,None)
+            };
+            write_cstring(out, pattern.ok_or_else(invalid)?, path).map_err(|_|invalid())?;
+            // options are stored in alphabetical order
+            let mut options : Vec<char> = options.ok_or_else(invalid)?.chars().collect();
+            options.sort_unstable();
+            write_cstring(out, &options.into_iter().collect::<String>(), path).map_err(|_|invalid())?;
+            REGEX
+        },
+        ["$dbPointer"]=>{
+            let (reference,id) = match map.get("$dbPointer") {
+                Some(Json::Object(pointer)) if pointer.len() == 2=>(text(pointer.get("$ref")),match pointer.get("$id") {
+                    Some(Json::Object(id)) if id.len() == 1=>object_id(id.get("$oid")),
+                    _=>None
+                }),
+                _=>(None,None)
+            };
+            write_string(out, reference.ok_or_else(invalid)?);
+            out.extend(id.ok_or_else(invalid)?);
+            DB_POINTER
+        },
+        ["$code"]=>{
+            write_string(out, text(map.get("$code")).ok_or_else(invalid)?);
+            CODE
+        },
+        ["$code","$scope"]=>{
+            let code = text(map.get("$code")).ok_or_else(invalid)?;
+            let scope = match map.get("$scope") {
+                Some(Json::Object(scope))=>scope,
+                _=>return Err(invalid())
+            };
+            let start = out.len();
+            out.extend_from_slice(&[0;4]);
+            write_string(out, code);
+            let len = path.len();
+            path.push_str("/$scope");
+            write_document(sorted_members(scope), out, path)?;
+            path.truncate(len);
+            let size = (out.len() - start) as i32;
+            out[start..start + 4].copy_from_slice(&size.to_le_bytes());
+            CODE_WITH_SCOPE
+        },
+        ["$symbol"]=>{
+            write_string(out, text(map.get("$symbol")).ok_or_else(invalid)?);
+            SYMBOL
+        },
+        ["$undefined"] if map.get("$undefined") == Some(&Json::Bool(true))=>UNDEFINED,
+        ["$minKey"] if uint32(map.get("$minKey")) == Some(1)=>MIN_KEY,
+        ["$maxKey"] if uint32(map.get("$maxKey")) == Some(1)=>MAX_KEY,
+        _=>return Ok(None)
+    };
+    Ok(Some(kind))
+}
+
+// integers go to int32 or int64 when they fit, every other number to a
+// double
+fn write_number(out:&mut Vec<u8>,num:&str)->Option<u8> {
+    Decimal::parse(num)?;
+    if !num.contains(['.','e','E']) && num != "-0" {
+        if let Ok(value) = num.parse::<i32>() {
+            out.extend_from_slice(&value.to_le_bytes());
+            return Some(INT32);
+        }
+        if let Ok(value) = num.parse::<i64>() {
+            out.extend_from_slice(&value.to_le_bytes());
+            return Some(INT64);
+        }
+    }
+    let value : f64 = num.parse().ok()?;
+    if !value.is_finite() {
+        return None;
+    }
+    out.extend_from_slice(&value.to_le_bytes());
+    Some(DOUBLE)
+}
+
+fn write_element(key:&str,json:&Json,out:&mut Vec<u8>,path:&mut String)->Result<(),BsonErr> {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&escape_token(key));
+    let type_pos = out.len();
+    out.push(0);
+    write_cstring(out, key, path)?;
+    let kind = match json {
+        Json::Null=>NULL,
+        Json::Bool(b)=>{
+            out.push(*b as u8);
+            BOOLEAN
+        },
+        Json::Number(num)=>write_number(out, num).ok_or_else(||BsonErr::InvalidNumber(path.clone()))?,
+        Json::String(str)=>{
+            write_string(out, str);
+            STRING
+        },
+        Json::Array(vec)=>{
+            write_document(vec.iter().enumerate().map(|(i,item)|(i.to_string(),item)).collect(), out, path)?;
+            ARRAY
+        },
+        Json::Object(map)=>match write_extended(map, out, path)? {
+            Some(kind)=>kind,
+            None=>{
+                write_document(sorted_members(map), out, path)?;
+                DOCUMENT
+            }
+        }
+    };
+    out[type_pos] = kind;
+    path.truncate(len);
+    Ok(())
+}
+
+// objects are written with their keys in sorted order
+fn sorted_members(map:&HashMap<String,Json>)->Vec<(String,&Json)> {
+    let mut members : Vec<(String,&Json)> = map.iter().map(|(key,value)|(key.clone(),value)).collect();
+    members.sort_by(|a,b|a.0.cmp(&b.0));
+    members
+}
+
+fn write_document(members:Vec<(String,&Json)>,out:&mut Vec<u8>,path:&mut String)->Result<(),BsonErr> {
+    let start = out.len();
+    out.extend_from_slice(&[0;4]);
+    for (key,value) in members {
+        write_element(&key, value, out, path)?;
+    }
+    out.push(0);
+    let size = (out.len() - start) as i32;
+    out[start..start + 4].copy_from_slice(&size.to_le_bytes());
+    Ok(())
+}
+
+fn extended(key:&str,value:Json)->Json {
+    let mut map = HashMap::new();
+    map.insert(String::from(key), value);
+    Json::Object(map)
+}
+
+fn extended_pair(key:&str,first:(&str,Json),second:(&str,Json))->Json {
+    let mut map = HashMap::new();
+    map.insert(String::from(first.0), first.1);
+    map.insert(String::from(second.0), second.1);
+    extended(key, Json::Object(map))
+}
+
+// nesting allowed when decoding, so hostile input cannot exhaust the
+// stack. MongoDB refuses documents nested deeper than this too
+const MAX_DEPTH : usize = 100;
+
+struct BsonReader<'a> {
+    bytes:&'a [u8],
+    pos:usize,
+    depth:usize
+}
+
+impl<'a> BsonReader<'a> {
+    fn take(&mut self,len:usize)->Result<&'a [u8],BsonErr> {
+        if len > self.bytes.len() - self.pos {
+            return Err(BsonErr::UnexpectedEnd);
+        }
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+    fn array<const N:usize>(&mut self)->Result<[u8;N],BsonErr> {
+        let mut bytes = [0u8;N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+    fn i32(&mut self)->Result<i32,BsonErr> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+    fn u32(&mut self)->Result<u32,BsonErr> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    fn i64(&mut self)->Result<i64,BsonErr> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+    fn cstring(&mut self)->Result<String,BsonErr> {
+        let start = self.pos;
+        let len = self.bytes[start..].iter().position(|b|*b == 0).ok_or(BsonErr::UnexpectedEnd)?;
+        let bytes = self.take(len + 1)?;
+        String::from_utf8(bytes[..len].to_vec()).map_err(|_|BsonErr::InvalidUtf8(start))
+    }
+    fn string(&mut self)->Result<String,BsonErr> {
+        let start = self.pos;
+        let len = self.i32()?;
+        if len < 1 {
+            return Err(BsonErr::InvalidLength(start));
+        }
+        let bytes = self.take(len as usize)?;
+        if bytes[bytes.len() - 1] != 0 {
+            return Err(BsonErr::InvalidLength(start));
+        }
+        String::from_utf8(bytes[..bytes.len() - 1].to_vec()).map_err(|_|BsonErr::InvalidUtf8(start))
+    }
+    fn object_id(&mut self)->Result<Json,BsonErr> {
+        Ok(extended("$oid", Json::String(hex::encode(self.take(12)?))))
+    }
+    // the members of a document, in order
+    fn document(&mut self)->Result<Vec<(String,Json)>,BsonErr> {
+        if self.depth == MAX_DEPTH {
+            return Err(BsonErr::TooDeep(self.pos));
+        }
+        self.depth += 1;
+        let result = self.members();
+        self.depth -= 1;
+        result
+    }
+    fn members(&mut self)->Result<Vec<(String,Json)>,BsonErr> {
+        let start = self.pos;
+        let len = self.i32()?;
+        if len < 5 || len as usize > self.bytes.len() - start {
+            return Err(BsonErr::InvalidLength(start));
+        }
+        let end = start + len as usize;
+        let mut members = vec![];
+        loop {
+            let element = self.pos;
+            if element >= end {
+                return Err(BsonErr::InvalidLength(start));
+            }
+            let kind = self.take(1)?[0];
+            if kind == 0 {
+                break;
+            }
+            let key = self.cstring()?;
+            members.push((key,self.element(kind, element)?));
+        }
+        if self.pos != end {
+            return Err(BsonErr::InvalidLength(start));
+        }
+        Ok(members)
+    }
+    fn element(&mut self,kind:u8,start:usize)->Result<Json,BsonErr> {
+        let json = match kind {
+            DOUBLE=>{
+                let value = f64::from_le_bytes(self.array()?);
+                match format_double(value) {
+                    // an integral double keeps a fraction so that it is
+                    // written back as a double
+                    Some(num) if num.contains(['.','e']) =>Json::Number(num),
+                    Some(num) if value == 0.0 && value.is_sign_negative()=>Json::Number(format!("-{}.0",num)),
+                    Some(num)=>Json::Number(format!("{}.0",num)),
+                    None if value.is_nan()=>extended("$numberDouble", Json::from("NaN")),
+                    None if value > 0.0=>extended("$numberDouble", Json::from("Infinity")),
+                    None=>extended("$numberDouble", Json::from("-Infinity"))
+                }
+            },
+            STRING=>Json::String(self.string()?),
+            DOCUMENT=>Json::Object(self.document()?.into_iter().collect()),
+            ARRAY=>Json::Array(self.document()?.into_iter().map(|(_,value)|value).collect()),
+            BINARY=>{
+                let len = self.i32()?;
+                if len < 0 {
+                    return Err(BsonErr::InvalidLength(start));
+                }
+                let subtype = self.take(1)?[0];
+                let data = self.take(len as usize)?;
+                extended_pair("$binary",
+                    ("base64",Json::String(base64(data, BASE64_ALPHABET, true))),
+                    ("subType",Json::String(format!("{:02x}",subtype))))
+            },
+            UNDEFINED=>extended("$undefined", Json::Bool(true)),
+            OBJECT_ID=>self.object_id()?,
+            BOOLEAN=>match self.take(1)?[0] {
+                0=>Json::Bool(false),
+                1=>Json::Bool(true),
+                _=>return Err(BsonErr::InvalidValue(start))
+            },
+            DATE=>{
+                let ms = self.i64()?;
+                // relaxed form only for years 1970 through 9999
+                if (0..253_402_300_800_000).contains(&ms) {
+                    extended("$date", Json::String(format_date(ms)))
+                }
+                else {
+                    extended("$date", extended("$numberLong", Json::String(ms.to_string())))
+                }
+            },
+            NULL=>Json::Null,
+            REGEX=>{
+                let pattern = self.cstring()?;
+                let options = self.cstring()?;
+                extended_pair("$regularExpression",("pattern",Json::String(pattern)),("options",Json::String(options)))
+            },
+            DB_POINTER=>{
+                let reference = self.string()?;
+                let id = self.object_id()?;
+                extended_pair("$dbPointer",("$ref",Json::String(reference)),("$id",id))
+            },
+            CODE=>extended("$code", Json::String(self.string()?)),
+            SYMBOL=>extended("$symbol", Json::String(self.string()?)),
+            CODE_WITH_SCOPE=>{
+                let begin = self.pos;
+                let len = self.i32()?;
+                let code = self.string()?;
+                let scope = Json::Object(self.document()?.into_iter().collect());
+                if len < 0 || self.pos - begin != len as usize {
+                    return Err(BsonErr::InvalidLength(start));
+                }
+                let mut map = HashMap::new();
+                map.insert(String::from("$code"), Json::String(code));
+                map.insert(String::from("$scope"), scope);
+                Json::Object(map)
+            },
+            INT32=>Json::from(self.i32()?),
+            TIMESTAMP=>{
+                let i = self.u32()?;
+                let t = self.u32()?;
+                extended_pair("$timestamp",("t",Json::from(t)),("i",Json::from(i)))
+            },
+            INT64=>Json::from(self.i64()?),
+            DECIMAL128=>extended("$numberDecimal", Json::String(format_decimal128(self.take(16)?))),
+            MIN_KEY=>extended("$minKey", Json::from(1)),
+            MAX_KEY=>extended("$maxKey", Json::from(1)),
+            _=>return Err(BsonErr::InvalidType(start))
+        };
+        Ok(json)
+    }
+}
+
+impl Json {
+    // writes an object as a BSON document. objects in the extended json v2
+    // format, like {"$oid": ...} or {"$date": ...}, become the BSON types
+    // they describe
+    pub fn to_bson(&self)->Result<Vec<u8>,BsonErr> {
+        let map = match self {
+            Json::Object(map)=>map,
+            _=>return Err(BsonErr::NotADocument)
+        };
+        let mut out = vec![];
+        write_document(sorted_members(map), &mut out, &mut String::new())?;
+        Ok(out)
+    }
+    // reads a BSON document, giving the types json lacks in relaxed
+    // extended json v2. doubles keep a fraction, so they convert back to
+    // doubles rather than integers
+    pub fn from_bson(bytes:&[u8])->Result<Json,BsonErr> {
+        let mut reader = BsonReader{bytes,pos:0,depth:0};
+        let json = Json::Object(reader.document()?.into_iter().collect());
+        if reader.pos != bytes.len() {
+            return Err(BsonErr::TrailingBytes(reader.pos));
+        }
+        Ok(json)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::Json;
+use crate::number::Decimal;
+use crate::pointer::escape_token;
+use crate::serializer::write_string;
+
+// carries the json pointer of a number that is not a finite double
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum CanonicalErr {
+    InvalidNumber(String)
+}
+
+fn push_string(out:&mut String,str:&str) {
+    // writing to a String cannot fail
+    let _ = write_string(out, str);
+}
+
+fn increment(digits:&str)->String {
+    let mut bytes = digits.as_bytes().to_vec();
+    for byte in bytes.iter_mut().rev() {
+        if *byte == b'9' {
+            *byte = b'0';
+        }
+        else {
+            *byte += 1;
+            return String::from_utf8(bytes).unwrap_or_default();
+        }
+    }
+    format!("1{}",String::from_utf8(bytes).unwrap_or_default())
+}
+
+// shortest digits that read back as the same double. when the double lies
+// exactly halfway between two such candidates ECMAScript takes the even
+// one, while rust's formatting may round up
+fn shortest_digits(value:f64)->Option<Decimal> {
+    let shortest = Decimal::parse(&format!("{:e}",value))?;
+    let exact = Decimal::parse(&format!("{:.800e}",value))?;
+    let k = shortest.digits.len();
+    if exact.digits.len() != k + 1 || !exact.digits.ends_with('5') {
+        return Some(shortest);
+    }
+    let lower = &exact.digits[..k];
+    for candidate in [String::from(lower),increment(lower)].iter() {
+        let even = matches!(candidate.bytes().last(),Some(b'0') | Some(b'2') | Some(b'4') | Some(b'6') | Some(b'8'));
+        let text = format!("{}e{}",candidate,exact.exponent + 1);
+        if even && text.parse::<f64>().ok() == Some(value) {
+            return Decimal::parse(&text);
+        }
+    }
+    Some(shortest)
+}
+
+// shortest round-trip digits of a double laid out like ECMAScript's
+// Number::toString, or None for NaN and the infinities
+pub(crate) fn format_double(value:f64)->Option<String> {
+    if !value.is_finite() {
+        return None;
+    }
+    let mut decimal = shortest_digits(value.abs())?;
+    decimal.negative = value < 0.0;
+    Some(decimal.to_es_string())
+}
+
+fn canonical_number(num:&str)->Option<String> {
+    Decimal::parse(num)?;
+    format_double(num.parse().ok()?)
+}
+
+fn write_canonical(json:&Json,out:&mut String,path:&mut String)->Result<(),CanonicalErr> {
+    match json {
+        Json::Null=>out.push_str("null"),
+        Json::Bool(b)=>out.push_str(if *b {"true"} else {"false"}),
+        Json::Number(num)=>match canonical_number(num) {
+            Some(num)=>out.push_str(&num),
+            None=>return Err(CanonicalErr::InvalidNumber(path.clone()))
+        },
+        Json::String(str)=>push_string(out, str),
+        Json::Array(vec)=>{
+            out.push('[');
+            for (i,item) in vec.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let len = path.len();
+                path.push_str(&format!("/{}",i));
+                write_canonical(item, out, path)?;
+                path.truncate(len);
+            }
+            out.push(']');
+        },
+        Json::Object(map)=>{
+            // sorted by utf-16 code units, which differs from the order of
+            // rust strings for characters beyond the basic multilingual plane
+            let mut keys : Vec<&String> = map.keys().collect();
+            keys.sort_by(|a,b|a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i,key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                push_string(out, key);
+                out.push(':');
+                let len = path.len();
+                path.push('/');
+                path.push_str(&escape_token(key));
+                write_canonical(&map[key], out, path)?;
+                path.truncate(len);
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+impl Json {
+    // serializes following the JSON Canonicalization Scheme (RFC 8785),
+    // so equal documents always give the same bytes
+    pub fn to_canonical_string(&self)->Result<String,CanonicalErr> {
+        let mut out = String::new();
+        write_canonical(self, &mut out, &mut String::new())?;
+        Ok(out)
+    }
+}
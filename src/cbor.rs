@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::canonical::format_double;
+use crate::number::Decimal;
+use crate::pointer::escape_token;
+
+// decoding errors carry the byte offset of the offending item. encoding
+// fails only on numbers that are not json numbers, with their json pointer
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum CborErr {
+    UnexpectedEnd,
+    InvalidHeader(usize),
+    InvalidUtf8(usize),
+    InvalidTag(usize),
+    TrailingBytes(usize),
+    // items nested more than 256 levels deep
+    TooDeep(usize),
+    InvalidNumber(String)
+}
+
+const UNSIGNED : u8 = 0;
+const NEGATIVE : u8 = 1;
+const BYTES : u8 = 2;
+const TEXT : u8 = 3;
+const ARRAY : u8 = 4;
+const MAP : u8 = 5;
+const TAG : u8 = 6;
+const SIMPLE : u8 = 7;
+
+const POSITIVE_BIGNUM : u64 = 2;
+const NEGATIVE_BIGNUM : u64 = 3;
+const DECIMAL_FRACTION : u64 = 4;
+const BASE64URL : u64 = 21;
+const BASE64 : u64 = 22;
+const BASE16 : u64 = 23;
+
+const INDEFINITE : u8 = 31;
+const BREAK : u8 = 0xff;
+
+// big-endian magnitude of a string of decimal digits
+fn digits_to_bytes(digits:&str)->Vec<u8> {
+    let mut bytes : Vec<u8> = vec![];
+    for digit in digits.bytes() {
+        let mut carry = (digit - b'0') as u32;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes
+}
+
+fn bytes_to_digits(bytes:&[u8])->String {
+    let mut bytes : Vec<u8> = bytes.iter().copied().skip_while(|b|*b == 0).collect();
+    let mut digits = vec![];
+    while !bytes.is_empty() {
+        let mut remainder = 0u32;
+        for byte in bytes.iter_mut() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        let leading = bytes.iter().take_while(|b|**b == 0).count();
+        bytes.drain(..leading);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+// adds or subtracts one from a big-endian magnitude
+fn step(bytes:&mut Vec<u8>,up:bool) {
+    for byte in bytes.iter_mut().rev() {
+        let (value,overflow) = if up {byte.overflowing_add(1)} else {byte.overflowing_sub(1)};
+        *byte = value;
+        if !overflow {
+            return;
+        }
+    }
+    if up {
+        bytes.insert(0, 1);
+    }
+}
+
+fn half_to_f64(half:u16)->f64 {
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f64;
+    let value = match exponent {
+        0=>mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0=>f64::INFINITY,
+        31=>f64::NAN,
+        _=>(mantissa + 1024.0) * 2f64.powi(exponent - 25)
+    };
+    if half & 0x8000 != 0 {-value} else {value}
+}
+
+// the half precision bits of a double, when it converts without loss
+fn f64_to_half(value:f64)->Option<u16> {
+    let single = value as f32;
+    if single as f64 != value {
+        return None;
+    }
+    let bits = single.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = bits & 0x7f_ffff;
+    if value == 0.0 {
+        return Some(sign);
+    }
+    if (-14..=15).contains(&exponent) {
+        if mantissa & 0x1fff != 0 {
+            return None;
+        }
+        return Some(sign | ((exponent + 15) as u16) << 10 | (mantissa >> 13) as u16);
+    }
+    if (-24..-14).contains(&exponent) {
+        let full = mantissa | 0x80_0000;
+        let shift = (-exponent - 1) as u32;
+        if full & ((1 << shift) - 1) != 0 {
+            return None;
+        }
+        return Some(sign | (full >> shift) as u16);
+    }
+    None
+}
+
+fn write_head(out:&mut Vec<u8>,major:u8,value:u64) {
+    let major = major << 5;
+    if value < 24 {
+        out.push(major | value as u8);
+    }
+    else if value <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(value as u8);
+    }
+    else if value <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(value as u16).to_be_bytes());
+    }
+    else if value <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(value as u32).to_be_bytes());
+    }
+    else {
+        out.push(major | 27);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+// an integer of any size: major type 0 or 1 when it fits in 64 bits, a
+// bignum tag otherwise
+fn write_integer(out:&mut Vec<u8>,negative:bool,digits:&str) {
+    let mut bytes = digits_to_bytes(digits);
+    if negative {
+        step(&mut bytes, false);
+    }
+    let skip = bytes.iter().take_while(|b|**b == 0).count();
+    let bytes = &bytes[skip..];
+    if bytes.len() <= 8 {
+        let value = bytes.iter().fold(0u64,|value,byte|value << 8 | *byte as u64);
+        write_head(out, if negative {NEGATIVE} else {UNSIGNED}, value);
+    }
+    else {
+        write_head(out, TAG, if negative {NEGATIVE_BIGNUM} else {POSITIVE_BIGNUM});
+        write_head(out, BYTES, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    }
+}
+
+fn write_float(out:&mut Vec<u8>,value:f64) {
+    if let Some(half) = f64_to_half(value) {
+        out.push(SIMPLE << 5 | 25);
+        out.extend_from_slice(&half.to_be_bytes());
+    }
+    else if value as f32 as f64 == value {
+        out.push(SIMPLE << 5 | 26);
+        out.extend_from_slice(&(value as f32).to_be_bytes());
+    }
+    else {
+        out.push(SIMPLE << 5 | 27);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+// integers stay integers and numbers a double spells the same way become
+// floats. anything else is a decimal fraction, which keeps the exact value
+// and, without an exponent, the number of fraction digits too
+fn write_number(out:&mut Vec<u8>,num:&str)->Option<()> {
+    let decimal = Decimal::parse(num)?;
+    let negative = num.starts_with('-');
+    let unsigned = num.trim_start_matches('-');
+    if !num.contains(['.','e','E']) && num != "-0" {
+        write_integer(out, negative, unsigned);
+        return Some(());
+    }
+    if let Ok(value) = num.parse::<f64>() {
+        if format_double(value).as_deref() == Some(num) || (value == 0.0 && num == "-0") {
+            write_float(out, value);
+            return Some(());
+        }
+    }
+    let (digits,exponent) = match unsigned.split_once('.') {
+        Some((int,frac)) if !frac.contains(['e','E'])=>{
+            (format!("{}{}",int,frac).trim_start_matches('0').to_string(),-(frac.len() as i64))
+        },
+        _=>(decimal.digits,decimal.exponent)
+    };
+    write_head(out, TAG, DECIMAL_FRACTION);
+    write_head(out, ARRAY, 2);
+    if exponent < 0 {
+        write_head(out, NEGATIVE, (-1 - exponent) as u64);
+    }
+    else {
+        write_head(out, UNSIGNED, exponent as u64);
+    }
+    let digits = if digits.is_empty() {"0"} else {&digits};
+    write_integer(out, negative && digits != "0", digits);
+    Some(())
+}
+
+fn write_cbor(json:&Json,out:&mut Vec<u8>,path:&mut String)->Result<(),CborErr> {
+    match json {
+        Json::Null=>out.push(SIMPLE << 5 | 22),
+        Json::Bool(b)=>out.push(SIMPLE << 5 | if *b {21} else {20}),
+        Json::Number(num)=>{
+            if write_number(out, num).is_none() {
+                return Err(CborErr::InvalidNumber(path.clone()));
+            }
+        },
+        Json::String(str)=>{
+            write_head(out, TEXT, str.len() as u64);
+            out.extend_from_slice(str.as_bytes());
+        },
+        Json::Array(vec)=>{
+            write_head(out, ARRAY, vec.len() as u64);
+            for (i,item) in vec.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("/{}",i));
+                write_cbor(item, out, path)?;
+                path.truncate(len);
+            }
+        },
+        Json::Object(map)=>{
+            // the deterministic order of RFC 8949: shorter keys first,
+            // then bytewise
+            let mut keys : Vec<&String> = map.keys().collect();
+            keys.sort_by(|a,b|a.len().cmp(&b.len()).then_with(||a.cmp(b)));
+            write_head(out, MAP, keys.len() as u64);
+            for key in keys {
+                write_head(out, TEXT, key.len() as u64);
+                out.extend_from_slice(key.as_bytes());
+                let len = path.len();
+                path.push('/');
+                path.push_str(&escape_token(key));
+                write_cbor(&map[key], out, path)?;
+                path.truncate(len);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn base64(bytes:&[u8],alphabet:&[u8],pad:bool)->String {
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32,|value,(i,byte)|value | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(alphabet[(value >> (18 - 6 * i) & 0x3f) as usize] as char);
+            }
+            else if pad {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+pub(crate) const BASE64_ALPHABET : &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+pub(crate) const BASE64URL_ALPHABET : &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// how byte strings turn into json strings, following the expected
+// conversion tags of RFC 8949 section 3.4.5.2
+#[derive(Clone,Copy)]
+enum Encoding {
+    Base64Url,
+    Base64,
+    Base16
+}
+
+enum Head {
+    Break,
+    // major type, additional information and argument
+    Item(u8,u8,Option<u64>)
+}
+
+// nesting allowed when decoding, so hostile input cannot exhaust the stack
+const MAX_DEPTH : usize = 256;
+
+struct CborReader<'a> {
+    bytes:&'a [u8],
+    pos:usize,
+    depth:usize
+}
+
+impl<'a> CborReader<'a> {
+    fn new(bytes:&'a [u8])->CborReader<'a> {
+        CborReader{bytes,pos:0,depth:0}
+    }
+    fn take(&mut self,len:u64)->Result<&'a [u8],CborErr> {
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(CborErr::UnexpectedEnd);
+        }
+        let slice = &self.bytes[self.pos..self.pos + len as usize];
+        self.pos += len as usize;
+        Ok(slice)
+    }
+    fn uint(&mut self,len:u64)->Result<u64,CborErr> {
+        Ok(self.take(len)?.iter().fold(0u64,|value,byte|value << 8 | *byte as u64))
+    }
+    // indefinite lengths have no argument
+    fn head(&mut self)->Result<Head,CborErr> {
+        let start = self.pos;
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        if initial == BREAK {
+            return Ok(Head::Break);
+        }
+        let argument = match info {
+            0..=23=>info as u64,
+            24=>self.uint(1)?,
+            25=>self.uint(2)?,
+            26=>self.uint(4)?,
+            27=>self.uint(8)?,
+            INDEFINITE if matches!(major,BYTES | TEXT | ARRAY | MAP)=>return Ok(Head::Item(major,info,None)),
+            _=>return Err(CborErr::InvalidHeader(start))
+        };
+        // simple values below 32 only have the one byte form
+        if major == SIMPLE && info == 24 && argument < 32 {
+            return Err(CborErr::InvalidHeader(start));
+        }
+        Ok(Head::Item(major,info,Some(argument)))
+    }
+    // the contents of a definite or chunked string
+    fn string(&mut self,major:u8,len:Option<u64>)->Result<Vec<u8>,CborErr> {
+        if let Some(len) = len {
+            return Ok(self.take(len)?.to_vec());
+        }
+        let mut bytes = vec![];
+        loop {
+            let start = self.pos;
+            match self.head()? {
+                Head::Break=>return Ok(bytes),
+                Head::Item(chunk,_,Some(len)) if chunk == major=>{
+                    let chunk = self.take(len)?;
+                    if major == TEXT && std::str::from_utf8(chunk).is_err() {
+                        return Err(CborErr::InvalidUtf8(start));
+                    }
+                    bytes.extend_from_slice(chunk);
+                },
+                _=>return Err(CborErr::InvalidHeader(start))
+            }
+        }
+    }
+    fn float(&self,info:u8,bits:u64)->Json {
+        let value = match info {
+            25=>half_to_f64(bits as u16),
+            26=>f32::from_bits(bits as u32) as f64,
+            _=>f64::from_bits(bits)
+        };
+        if value == 0.0 && value.is_sign_negative() {
+            return Json::Number(String::from("-0"));
+        }
+        format_double(value).map(Json::Number).unwrap_or(Json::Null)
+    }
+    // an item, or None for a break code
+    fn next(&mut self,encoding:Encoding)->Result<Option<Json>,CborErr> {
+        if self.depth == MAX_DEPTH {
+            return Err(CborErr::TooDeep(self.pos));
+        }
+        self.depth += 1;
+        let result = self.nested(encoding);
+        self.depth -= 1;
+        result
+    }
+    fn nested(&mut self,encoding:Encoding)->Result<Option<Json>,CborErr> {
+        let start = self.pos;
+        let (major,info,argument) = match self.head()? {
+            Head::Break=>return Ok(None),
+            Head::Item(major,info,argument)=>(major,info,argument)
+        };
+        let json = match (major,argument) {
+            (UNSIGNED,Some(n))=>Json::Number(n.to_string()),
+            (NEGATIVE,Some(n))=>Json::Number((-1 - n as i128).to_string()),
+            (BYTES,len)=>{
+                let bytes = self.string(BYTES, len)?;
+                Json::String(match encoding {
+                    Encoding::Base64Url=>base64(&bytes, BASE64URL_ALPHABET, false),
+                    Encoding::Base64=>base64(&bytes, BASE64_ALPHABET, true),
+                    Encoding::Base16=>hex::encode(&bytes)
+                })
+            },
+            (TEXT,len)=>match String::from_utf8(self.string(TEXT, len)?) {
+                Ok(str)=>Json::String(str),
+                Err(_)=>return Err(CborErr::InvalidUtf8(start))
+            },
+            (ARRAY,len)=>{
+                let mut vec = vec![];
+                while len.is_none_or(|len|(vec.len() as u64) < len) {
+                    match self.next(encoding)? {
+                        Some(item)=>vec.push(item),
+                        None if len.is_none()=>break,
+                        None=>return Err(CborErr::InvalidHeader(self.pos - 1))
+                    }
+                }
+                Json::Array(vec)
+            },
+            (MAP,len)=>{
+                let mut map = HashMap::new();
+                let mut count = 0;
+                while len.is_none_or(|len|count < len) {
+                    let key_start = self.pos;
+                    let key = match self.next(encoding)? {
+                        Some(Json::String(key))=>key,
+                        Some(key)=>{
+                            let mut text = String::new();
+                            // writing to a String cannot fail
+                            let _ = key.write_json(&mut text);
+                            text
+                        },
+                        None if len.is_none()=>break,
+                        None=>return Err(CborErr::InvalidHeader(key_start))
+                    };
+                    map.insert(key, self.item(encoding)?);
+                    count += 1;
+                }
+                Json::Object(map)
+            },
+            (TAG,Some(tag))=>self.tagged(tag, start, encoding)?,
+            (SIMPLE,Some(n)) if info >= 25=>self.float(info, n),
+            (SIMPLE,Some(20))=>Json::Bool(false),
+            (SIMPLE,Some(21))=>Json::Bool(true),
+            // null, undefined and unassigned simple values
+            (SIMPLE,Some(_))=>Json::Null,
+            _=>return Err(CborErr::InvalidHeader(start))
+        };
+        Ok(Some(json))
+    }
+    fn item(&mut self,encoding:Encoding)->Result<Json,CborErr> {
+        let start = self.pos;
+        self.next(encoding)?.ok_or(CborErr::InvalidHeader(start))
+    }
+    fn tagged(&mut self,tag:u64,start:usize,encoding:Encoding)->Result<Json,CborErr> {
+        match tag {
+            POSITIVE_BIGNUM | NEGATIVE_BIGNUM=>{
+                let mut bytes = match self.head()? {
+                    Head::Item(BYTES,_,len)=>self.string(BYTES, len)?,
+                    _=>return Err(CborErr::InvalidTag(start))
+                };
+                if tag == NEGATIVE_BIGNUM {
+                    step(&mut bytes, true);
+                    return Ok(Json::Number(format!("-{}",bytes_to_digits(&bytes))));
+                }
+                Ok(Json::Number(bytes_to_digits(&bytes)))
+            },
+            DECIMAL_FRACTION=>{
+                let parts = match self.item(encoding)? {
+                    Json::Array(parts) if parts.len() == 2=>parts,
+                    _=>return Err(CborErr::InvalidTag(start))
+                };
+                let integer = |json:&Json|match json {
+                    Json::Number(num) if !num.contains(['.','e','E']) && num != "-0"=>Some(num.clone()),
+                    _=>None
+                };
+                let exponent = integer(&parts[0]).and_then(|e|e.parse::<i64>().ok());
+                match (exponent,integer(&parts[1])) {
+                    (Some(exponent),Some(mantissa))=>decimal_fraction(&mantissa, exponent).map(Json::Number).ok_or(CborErr::InvalidTag(start)),
+                    _=>Err(CborErr::InvalidTag(start))
+                }
+            },
+            BASE64URL=>self.item(Encoding::Base64Url),
+            BASE64=>self.item(Encoding::Base64),
+            BASE16=>self.item(Encoding::Base16),
+            // other tags only add meaning to the enclosed item
+            _=>self.item(encoding)
+        }
+    }
+}
+
+// mantissa * 10^exponent, with a decimal point when the exponent is a
+// modest negative number and an exponent otherwise. None when the
+// exponent has no negation in an i64
+fn decimal_fraction(mantissa:&str,exponent:i64)->Option<String> {
+    let (sign,digits) = match mantissa.strip_prefix('-') {
+        Some(digits)=>("-",digits),
+        None=>("",mantissa)
+    };
+    let scale = exponent.checked_neg()?;
+    if exponent == 0 {
+        return Some(String::from(mantissa));
+    }
+    if exponent > 0 || scale > digits.len() as i64 + 20 {
+        return Some(format!("{}e{}",mantissa,exponent));
+    }
+    let scale = scale as usize;
+    let digits = format!("{}{}","0".repeat((scale + 1).saturating_sub(digits.len())),digits);
+    let point = digits.len() - scale;
+    Some(format!("{}{}.{}",sign,&digits[..point],&digits[point..]))
+}
+
+impl Json {
+    // encodes as CBOR (RFC 8949) with preferred serialization and map keys
+    // in deterministic order
+    pub fn to_cbor(&self)->Result<Vec<u8>,CborErr> {
+        let mut out = vec![];
+        write_cbor(self, &mut out, &mut String::new())?;
+        Ok(out)
+    }
+    // decodes one CBOR item as described in RFC 8949 section 6.1: byte
+    // strings become base64url text, NaN, infinities, undefined and other
+    // simple values become null, and keys that are not text are written
+    // out as json
+    pub fn from_cbor(bytes:&[u8])->Result<Json,CborErr> {
+        let mut reader = CborReader::new(bytes);
+        let json = reader.item(Encoding::Base64Url)?;
+        if reader.pos != bytes.len() {
+            return Err(CborErr::TrailingBytes(reader.pos));
+        }
+        Ok(json)
+    }
+}
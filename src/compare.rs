@@ -0,0 +1,100 @@
+use crate::Json;
+use crate::pointer::escape_token;
+
+// paths are json pointers, numbers are compared by value
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum Difference {
+    Added{path:String,value:Json},
+    Removed{path:String,value:Json},
+    Changed{path:String,left:Json,right:Json}
+}
+
+const RED : &str = "\x1b[31m";
+const GREEN : &str = "\x1b[32m";
+const RESET : &str = "\x1b[0m";
+
+fn compare_into(left:&Json,right:&Json,path:&str,out:&mut Vec<Difference>) {
+    match (left,right) {
+        (Json::Object(a),Json::Object(b))=>{
+            let mut keys : Vec<&String> = a.keys().chain(b.keys().filter(|key|!a.contains_key(*key))).collect();
+            keys.sort();
+            for key in keys {
+                let at = format!("{}/{}",path,escape_token(key));
+                match (a.get(key),b.get(key)) {
+                    (Some(x),Some(y))=>compare_into(x, y, &at, out),
+                    (Some(x),None)=>out.push(Difference::Removed{path:at,value:x.clone()}),
+                    (None,Some(y))=>out.push(Difference::Added{path:at,value:y.clone()}),
+                    (None,None)=>{}
+                }
+            }
+        },
+        (Json::Array(a),Json::Array(b))=>{
+            for i in 0..a.len().max(b.len()) {
+                let at = format!("{}/{}",path,i);
+                match (a.get(i),b.get(i)) {
+                    (Some(x),Some(y))=>compare_into(x, y, &at, out),
+                    (Some(x),None)=>out.push(Difference::Removed{path:at,value:x.clone()}),
+                    (None,Some(y))=>out.push(Difference::Added{path:at,value:y.clone()}),
+                    (None,None)=>{}
+                }
+            }
+        },
+        _=>{
+            if !left.semantic_eq(right) {
+                out.push(Difference::Changed{
+                    path:String::from(path),
+                    left:left.clone(),
+                    right:right.clone()
+                });
+            }
+        }
+    }
+}
+
+pub fn compare(left:&Json,right:&Json)->Vec<Difference> {
+    let mut out = vec![];
+    compare_into(left, right, "", &mut out);
+    out
+}
+
+fn push_line(text:&mut String,sign:char,path:&str,value:&Json,colored:bool) {
+    let path = if path.is_empty() { "(root)" } else { path };
+    if colored {
+        text.push_str(if sign == '-' { RED } else { GREEN });
+    }
+    text.push_str(&format!("{} {}: {}",sign,path,value));
+    if colored {
+        text.push_str(RESET);
+    }
+    text.push('\n');
+}
+
+// renders differences in unified style, removed values as `-` lines and
+// added ones as `+` lines, optionally colored red and green
+pub fn render_diff(differences:&[Difference],colored:bool)->String {
+    let mut text = String::new();
+    for difference in differences {
+        match difference {
+            Difference::Added{path,value}=>push_line(&mut text, '+', path, value, colored),
+            Difference::Removed{path,value}=>push_line(&mut text, '-', path, value, colored),
+            Difference::Changed{path,left,right}=>{
+                push_line(&mut text, '-', path, left, colored);
+                push_line(&mut text, '+', path, right, colored);
+            }
+        }
+    }
+    text
+}
+
+#[macro_export]
+macro_rules! assert_json_eq {
+    ($left:expr,$right:expr $(,)?) => {{
+        let differences = $crate::compare(&$left, &$right);
+        if !differences.is_empty() {
+            panic!(
+                "assertion failed: json values differ (- left, + right)\n{}",
+                $crate::render_diff(&differences, false)
+            );
+        }
+    }};
+}
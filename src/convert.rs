@@ -0,0 +1,94 @@
+use std::collections::{BTreeMap,HashMap};
+
+use crate::Json;
+
+impl From<bool> for Json {
+    fn from(b:bool)->Json {
+        Json::Bool(b)
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n:$t)->Json {
+                    Json::Number(n.to_string())
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8,i16,i32,i64,i128,isize,u8,u16,u32,u64,u128,usize);
+
+// NaN and the infinities have no json form and become null
+macro_rules! from_float {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n:$t)->Json {
+                    if n.is_finite() {
+                        Json::Number(n.to_string())
+                    }
+                    else {
+                        Json::Null
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_float!(f32,f64);
+
+impl From<String> for Json {
+    fn from(str:String)->Json {
+        Json::String(str)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(str:&str)->Json {
+        Json::String(String::from(str))
+    }
+}
+
+impl From<&String> for Json {
+    fn from(str:&String)->Json {
+        Json::String(str.clone())
+    }
+}
+
+impl<T:Into<Json>> From<Vec<T>> for Json {
+    fn from(vec:Vec<T>)->Json {
+        Json::Array(vec.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T:Clone + Into<Json>> From<&[T]> for Json {
+    fn from(slice:&[T])->Json {
+        Json::Array(slice.iter().cloned().map(Into::into).collect())
+    }
+}
+
+impl<T:Into<Json>> From<Option<T>> for Json {
+    fn from(option:Option<T>)->Json {
+        match option {
+            Some(value)=>value.into(),
+            None=>Json::Null
+        }
+    }
+}
+
+impl<K:Into<String>,V:Into<Json>> From<HashMap<K,V>> for Json {
+    fn from(map:HashMap<K,V>)->Json {
+        Json::Object(map.into_iter().map(|(k,v)|(k.into(),v.into())).collect())
+    }
+}
+
+impl<K:Into<String>,V:Into<Json>> From<BTreeMap<K,V>> for Json {
+    fn from(map:BTreeMap<K,V>)->Json {
+        Json::Object(map.into_iter().map(|(k,v)|(k.into(),v.into())).collect())
+    }
+}
@@ -0,0 +1,280 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::number::Decimal;
+use crate::flatten::{self,FlattenOptions,Node};
+
+// rows are the records of the text counted from 1, the header included.
+// NotAnObject carries the index of the element that is not an object
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum CsvErr {
+    NotAnArray,
+    NotAnObject(usize),
+    UnterminatedQuote(usize),
+    UnexpectedQuote(usize),
+    FieldCount(usize),
+    ConflictingColumn(String)
+}
+
+// how arrays inside the objects turn into cells
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum CsvArrays {
+    // one column per element: tags.0, tags.1, ...
+    Index,
+    // the whole array as json text in one cell
+    Json,
+    // the elements joined with the separator in one cell. this does not
+    // read back into an array
+    Join(String)
+}
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct CsvOptions {
+    pub delimiter:char,
+    pub arrays:CsvArrays,
+    // when reading, turns cells holding numbers, true, false or null into
+    // those values and empty cells into null
+    pub infer_types:bool
+}
+
+impl Default for CsvOptions {
+    fn default()->CsvOptions {
+        CsvOptions{
+            delimiter:',',
+            arrays:CsvArrays::Index,
+            infer_types:false
+        }
+    }
+}
+
+fn json_text(json:&Json)->String {
+    let mut out = String::new();
+    // writing to a String cannot fail
+    let _ = json.write_json(&mut out);
+    out
+}
+
+fn cell(json:&Json)->String {
+    match json {
+        Json::Null=>String::new(),
+        Json::String(str)=>str.clone(),
+        Json::Number(num)=>num.clone(),
+        other=>json_text(other)
+    }
+}
+
+// arrays that are not indexed turn into single cells before flattening
+fn arrays_to_cells(json:&Json,options:&CsvOptions)->Json {
+    match json {
+        Json::Object(map)=>Json::Object(map.iter().map(|(key,value)|(key.clone(),arrays_to_cells(value, options))).collect()),
+        Json::Array(vec) if !vec.is_empty()=>match &options.arrays {
+            CsvArrays::Index=>json.clone(),
+            CsvArrays::Json=>Json::String(json_text(json)),
+            CsvArrays::Join(separator)=>{
+                let cells : Vec<String> = vec.iter().map(cell).collect();
+                Json::String(cells.join(separator))
+            }
+        },
+        other=>other.clone()
+    }
+}
+
+// RFC 4180: fields holding the delimiter, a quote or a line break are
+// quoted, and quotes inside them doubled
+fn write_field(out:&mut String,field:&str,delimiter:char) {
+    if field.contains([delimiter,'"','\r','\n']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    }
+    else {
+        out.push_str(field);
+    }
+}
+
+fn write_record(out:&mut String,fields:&[&str],delimiter:char) {
+    for (i,field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(delimiter);
+        }
+        write_field(out, field, delimiter);
+    }
+    out.push_str("\r\n");
+}
+
+// splits text into records of fields, following RFC 4180 but accepting
+// bare line feeds too
+fn read_records(text:&str,delimiter:char)->Result<Vec<Vec<String>>,CsvErr> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut row = 1;
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+    let mut quote_row = 0;
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"')=>{
+                    chars.next();
+                    field.push('"');
+                },
+                '"'=>{
+                    quoted = false;
+                    if !matches!(chars.peek(),None | Some('\r') | Some('\n')) && chars.peek() != Some(&delimiter) {
+                        return Err(CsvErr::UnexpectedQuote(row));
+                    }
+                },
+                _=>field.push(c)
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty()=>{
+                quoted = true;
+                quote_row = row;
+            },
+            '"'=>return Err(CsvErr::UnexpectedQuote(row)),
+            '\r' if chars.peek() == Some(&'\n')=>{},
+            '\n'=>{
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                row += 1;
+            },
+            c if c == delimiter=>record.push(std::mem::take(&mut field)),
+            _=>field.push(c)
+        }
+    }
+    if quoted {
+        return Err(CsvErr::UnterminatedQuote(quote_row));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn infer(cell:&str)->Json {
+    match cell {
+        ""| "null"=>Json::Null,
+        "true"=>Json::Bool(true),
+        "false"=>Json::Bool(false),
+        _ if Decimal::parse(cell).is_some()=>Json::Number(String::from(cell)),
+        _=>Json::String(String::from(cell))
+    }
+}
+
+fn value(cell:&str,options:&CsvOptions)->Json {
+    if options.arrays == CsvArrays::Json && cell.starts_with('[') {
+        if let Ok(json) = Json::parse(cell) {
+            return json;
+        }
+    }
+    if options.infer_types {
+        match cell {
+            "[]"=>Json::Array(vec![]),
+            "{}"=>Json::Object(HashMap::new()),
+            _=>infer(cell)
+        }
+    }
+    else {
+        Json::String(String::from(cell))
+    }
+}
+
+impl Json {
+    pub fn to_csv(&self)->Result<String,CsvErr> {
+        self.to_csv_with(&CsvOptions::default())
+    }
+    // writes an array of objects as CSV with a header row. nested keys
+    // become column names as flatten makes them, columns appear in the
+    // order they are first met and missing values are left empty
+    pub fn to_csv_with(&self,options:&CsvOptions)->Result<String,CsvErr> {
+        let vec = match self {
+            Json::Array(vec)=>vec,
+            _=>return Err(CsvErr::NotAnArray)
+        };
+        let mut columns : Vec<String> = vec![];
+        let mut index : HashMap<String,usize> = HashMap::new();
+        let mut rows = vec![];
+        for (i,item) in vec.iter().enumerate() {
+            if item.as_object().is_none() {
+                return Err(CsvErr::NotAnObject(i));
+            }
+            let item = match options.arrays {
+                CsvArrays::Index=>Cow::Borrowed(item),
+                _=>Cow::Owned(arrays_to_cells(item, options))
+            };
+            let cells : Vec<(String,String)> = flatten::leaves(&item, &FlattenOptions::default())
+                .into_iter()
+                .map(|(column,value)|(column,cell(value)))
+                .collect();
+            for (column,_) in cells.iter() {
+                if !index.contains_key(column) {
+                    index.insert(column.clone(), columns.len());
+                    columns.push(column.clone());
+                }
+            }
+            rows.push(cells);
+        }
+        let mut out = String::new();
+        let header : Vec<&str> = columns.iter().map(String::as_str).collect();
+        write_record(&mut out, &header, options.delimiter);
+        for cells in rows {
+            let mut record = vec![""; columns.len()];
+            for (column,value) in cells.iter() {
+                record[index[column]] = value;
+            }
+            write_record(&mut out, &record, options.delimiter);
+        }
+        Ok(out)
+    }
+    pub fn from_csv(text:&str)->Result<Json,CsvErr> {
+        Json::from_csv_with(text, &CsvOptions::default())
+    }
+    // reads CSV with a header row into an array of objects, one per row.
+    // column names are unflattened into nested objects
+    pub fn from_csv_with(text:&str,options:&CsvOptions)->Result<Json,CsvErr> {
+        let mut records = read_records(text, options.delimiter)?.into_iter();
+        let header = match records.next() {
+            Some(header)=>header,
+            None=>return Ok(Json::Array(vec![]))
+        };
+        // indices stay below the number of columns, which no array written
+        // by to_csv reaches
+        let paths : Vec<_> = header.iter()
+            .map(|column|flatten::parse(column, header.len() - 1, &FlattenOptions::default()))
+            .collect();
+        let mut rows = vec![];
+        for (i,record) in records.enumerate() {
+            // blank lines hold no row
+            if record.len() == 1 && record[0].is_empty() && header.len() > 1 {
+                continue;
+            }
+            if record.len() != header.len() {
+                return Err(CsvErr::FieldCount(i + 2));
+            }
+            let mut row = Node::Object(HashMap::new());
+            let (filled,empty) : (Vec<_>,Vec<_>) = header.iter()
+                .zip(paths.iter())
+                .zip(record.iter())
+                .partition(|(_,cell)|!cell.is_empty());
+            for ((column,path),cell) in filled {
+                if path.as_ref().and_then(|path|flatten::insert(&mut row, path, value(cell, options))).is_none() {
+                    return Err(CsvErr::ConflictingColumn(column.clone()));
+                }
+            }
+            // an empty cell never conflicts, it only fills in a column
+            // another row needed
+            for ((_,path),cell) in empty {
+                if let Some(path) = path {
+                    let _ = flatten::insert(&mut row, path, value(cell, options));
+                }
+            }
+            rows.push(flatten::to_json(row));
+        }
+        Ok(Json::Array(rows))
+    }
+}
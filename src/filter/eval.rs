@@ -0,0 +1,393 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::canonical::format_double;
+use crate::filter::*;
+use crate::index::type_name;
+use crate::number::Decimal;
+
+fn truthy(json:&Json)->bool {
+    !matches!(json,Json::Null | Json::Bool(false))
+}
+
+// object members in key order, so that outputs do not depend on the
+// iteration order of the map
+fn sorted_members(map:&HashMap<String,Json>)->Vec<(&String,&Json)> {
+    let mut members : Vec<(&String,&Json)> = map.iter().collect();
+    members.sort_by(|a,b|a.0.cmp(b.0));
+    members
+}
+
+fn values(json:&Json)->Result<Vec<Json>,FilterErr> {
+    match json {
+        Json::Array(vec)=>Ok(vec.clone()),
+        Json::Object(map)=>Ok(sorted_members(map).into_iter().map(|(_,value)|value.clone()).collect()),
+        _=>Err(FilterErr::CannotIterate(String::from(type_name(json))))
+    }
+}
+
+fn to_f64(json:&Json)->Option<f64> {
+    match json {
+        Json::Number(num)=>{
+            Decimal::parse(num)?;
+            num.parse().ok()
+        },
+        _=>None
+    }
+}
+
+fn number(value:f64)->Result<Json,FilterErr> {
+    format_double(value).map(Json::Number).ok_or(FilterErr::NumberOutOfRange)
+}
+
+// like Json's own order, except that numbers compare by value alone and
+// objects by their sorted keys first, then by their values
+fn compare(a:&Json,b:&Json)->Ordering {
+    match (a,b) {
+        (Json::Number(x),Json::Number(y))=>match (Decimal::parse(x),Decimal::parse(y)) {
+            (Some(x),Some(y))=>x.cmp(&y),
+            _=>a.cmp(b)
+        },
+        (Json::Array(x),Json::Array(y))=>{
+            for (x,y) in x.iter().zip(y.iter()) {
+                let order = compare(x, y);
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            x.len().cmp(&y.len())
+        },
+        (Json::Object(x),Json::Object(y))=>{
+            let x = sorted_members(x);
+            let y = sorted_members(y);
+            let keys = x.iter().map(|m|m.0).cmp(y.iter().map(|m|m.0));
+            if keys != Ordering::Equal {
+                return keys;
+            }
+            for ((_,x),(_,y)) in x.iter().zip(y.iter()) {
+                let order = compare(x, y);
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            Ordering::Equal
+        },
+        _=>a.cmp(b)
+    }
+}
+
+fn symbol(op:BinaryOp)->&'static str {
+    match op {
+        BinaryOp::Add=>"+",
+        BinaryOp::Sub=>"-",
+        BinaryOp::Mul=>"*",
+        BinaryOp::Div=>"/",
+        BinaryOp::Mod=>"%",
+        BinaryOp::Eq=>"==",
+        BinaryOp::Ne=>"!=",
+        BinaryOp::Lt=>"<",
+        BinaryOp::Le=>"<=",
+        BinaryOp::Gt=>">",
+        BinaryOp::Ge=>">="
+    }
+}
+
+fn arithmetic(op:BinaryOp,left:&Json,right:&Json)->Result<Json,FilterErr> {
+    let invalid = ||FilterErr::InvalidOperands(
+        String::from(symbol(op)),String::from(type_name(left)),String::from(type_name(right))
+    );
+    if let (Some(x),Some(y)) = (to_f64(left),to_f64(right)) {
+        return match op {
+            BinaryOp::Add=>number(x + y),
+            BinaryOp::Sub=>number(x - y),
+            BinaryOp::Mul=>number(x * y),
+            BinaryOp::Div if y == 0.0=>Err(FilterErr::DivisionByZero),
+            BinaryOp::Div=>number(x / y),
+            BinaryOp::Mod if y.trunc() == 0.0=>Err(FilterErr::DivisionByZero),
+            BinaryOp::Mod=>number(x.trunc() % y.trunc()),
+            _=>Err(invalid())
+        };
+    }
+    match (op,left,right) {
+        (BinaryOp::Add,Json::Null,other) | (BinaryOp::Add,other,Json::Null)=>Ok(other.clone()),
+        (BinaryOp::Add,Json::String(x),Json::String(y))=>Ok(Json::String(format!("{}{}",x,y))),
+        (BinaryOp::Add,Json::Array(x),Json::Array(y))=>{
+            let mut vec = x.clone();
+            vec.extend(y.iter().cloned());
+            Ok(Json::Array(vec))
+        },
+        (BinaryOp::Add,Json::Object(x),Json::Object(y))=>{
+            let mut map = x.clone();
+            map.extend(y.iter().map(|(k,v)|(k.clone(),v.clone())));
+            Ok(Json::Object(map))
+        },
+        (BinaryOp::Sub,Json::Array(x),Json::Array(y))=>{
+            let vec = x.iter().filter(|item|!y.iter().any(|other|compare(item, other) == Ordering::Equal)).cloned().collect();
+            Ok(Json::Array(vec))
+        },
+        _=>Err(invalid())
+    }
+}
+
+fn binary(op:BinaryOp,left:&Json,right:&Json)->Result<Json,FilterErr> {
+    let order = compare(left, right);
+    let result = match op {
+        BinaryOp::Eq=>order == Ordering::Equal,
+        BinaryOp::Ne=>order != Ordering::Equal,
+        BinaryOp::Lt=>order == Ordering::Less,
+        BinaryOp::Le=>order != Ordering::Greater,
+        BinaryOp::Gt=>order == Ordering::Greater,
+        BinaryOp::Ge=>order != Ordering::Less,
+        _=>return arithmetic(op, left, right)
+    };
+    Ok(Json::Bool(result))
+}
+
+// negative positions count from the end, fractions are dropped
+fn position(index:f64,len:usize)->i64 {
+    let index = index.floor() as i64;
+    if index < 0 {
+        index + len as i64
+    }
+    else {
+        index
+    }
+}
+
+fn index(target:&Json,index:&Json)->Result<Json,FilterErr> {
+    match (target,index) {
+        (Json::Object(map),Json::String(key))=>Ok(map.get(key).cloned().unwrap_or(Json::Null)),
+        (Json::Array(vec),Json::Number(_))=>{
+            let i = to_f64(index).map(|i|position(i, vec.len())).unwrap_or(-1);
+            if i < 0 {
+                return Ok(Json::Null);
+            }
+            Ok(vec.get(i as usize).cloned().unwrap_or(Json::Null))
+        },
+        (Json::Null,Json::String(_)) | (Json::Null,Json::Number(_))=>Ok(Json::Null),
+        _=>Err(FilterErr::CannotIndex(String::from(type_name(target)),String::from(type_name(index))))
+    }
+}
+
+fn slice_bound(bound:&Json,len:usize,default:usize)->Result<usize,FilterErr> {
+    match bound {
+        Json::Null=>Ok(default),
+        Json::Number(_)=>{
+            let i = to_f64(bound).map(|i|position(i, len)).unwrap_or(0);
+            Ok(i.max(0).min(len as i64) as usize)
+        },
+        _=>Err(FilterErr::CannotIndex(String::from("slice"),String::from(type_name(bound))))
+    }
+}
+
+fn slice(target:&Json,from:&Json,to:&Json)->Result<Json,FilterErr> {
+    match target {
+        Json::Null=>Ok(Json::Null),
+        Json::Array(vec)=>{
+            let start = slice_bound(from, vec.len(), 0)?;
+            let end = slice_bound(to, vec.len(), vec.len())?.max(start);
+            Ok(Json::Array(vec[start..end].to_vec()))
+        },
+        Json::String(str)=>{
+            let chars : Vec<char> = str.chars().collect();
+            let start = slice_bound(from, chars.len(), 0)?;
+            let end = slice_bound(to, chars.len(), chars.len())?.max(start);
+            Ok(Json::String(chars[start..end].iter().collect()))
+        },
+        _=>Err(FilterErr::CannotIndex(String::from(type_name(target)),String::from("slice")))
+    }
+}
+
+fn recurse(json:&Json,out:&mut Vec<Json>) {
+    out.push(json.clone());
+    match json {
+        Json::Array(vec)=>vec.iter().for_each(|item|recurse(item, out)),
+        Json::Object(map)=>sorted_members(map).into_iter().for_each(|(_,value)|recurse(value, out)),
+        _=>{}
+    }
+}
+
+fn invalid_argument(name:&str,input:&Json)->FilterErr {
+    FilterErr::InvalidArgument(String::from(name),String::from(type_name(input)))
+}
+
+fn call(name:&str,args:&[Expr],input:&Json)->Result<Vec<Json>,FilterErr> {
+    let output = match name {
+        "length"=>match input {
+            Json::Null=>Json::from(0),
+            Json::Number(_)=>number(to_f64(input).map(f64::abs).unwrap_or(0.0))?,
+            Json::String(str)=>Json::from(str.chars().count()),
+            Json::Array(vec)=>Json::from(vec.len()),
+            Json::Object(map)=>Json::from(map.len()),
+            Json::Bool(_)=>return Err(invalid_argument(name, input))
+        },
+        "keys"=>match input {
+            Json::Object(map)=>Json::Array(sorted_members(map).into_iter().map(|(key,_)|Json::String(key.clone())).collect()),
+            Json::Array(vec)=>Json::Array((0..vec.len()).map(Json::from).collect()),
+            _=>return Err(invalid_argument(name, input))
+        },
+        "map"=>{
+            let mut vec = vec![];
+            for item in values(input)? {
+                vec.extend(eval(&args[0], &item)?);
+            }
+            Json::Array(vec)
+        },
+        "select"=>{
+            let mut out = vec![];
+            for condition in eval(&args[0], input)? {
+                if truthy(&condition) {
+                    out.push(input.clone());
+                }
+            }
+            return Ok(out);
+        },
+        "not"=>Json::Bool(!truthy(input)),
+        "type"=>Json::from(type_name(input)),
+        "empty"=>return Ok(vec![]),
+        "add"=>{
+            let mut sum = Json::Null;
+            for item in values(input)? {
+                sum = arithmetic(BinaryOp::Add, &sum, &item)?;
+            }
+            sum
+        },
+        "sort"=>match input {
+            Json::Array(vec)=>{
+                let mut vec = vec.clone();
+                vec.sort_by(compare);
+                Json::Array(vec)
+            },
+            _=>return Err(invalid_argument(name, input))
+        },
+        _=>return Err(FilterErr::UnknownFunction(format!("{}/{}",name,args.len())))
+    };
+    Ok(vec![output])
+}
+
+// every combination of the outputs of two filters run on the same input
+fn product<F:Fn(&Json,&Json)->Result<Json,FilterErr>>(left:&Expr,right:&Expr,input:&Json,f:F)->Result<Vec<Json>,FilterErr> {
+    let rights = eval(right, input)?;
+    let mut out = vec![];
+    for l in eval(left, input)? {
+        for r in rights.iter() {
+            out.push(f(&l, r)?);
+        }
+    }
+    Ok(out)
+}
+
+fn object(entries:&[(Expr,Expr)],input:&Json)->Result<Vec<Json>,FilterErr> {
+    let mut objects = vec![HashMap::new()];
+    for (key,value) in entries {
+        let keys = eval(key, input)?;
+        let values = eval(value, input)?;
+        let mut next = vec![];
+        for object in objects.iter() {
+            for key in keys.iter() {
+                let key = match key {
+                    Json::String(key)=>key,
+                    _=>return Err(FilterErr::InvalidKey(String::from(type_name(key))))
+                };
+                for value in values.iter() {
+                    let mut object : HashMap<String,Json> = object.clone();
+                    object.insert(key.clone(), value.clone());
+                    next.push(object);
+                }
+            }
+        }
+        objects = next;
+    }
+    Ok(objects.into_iter().map(Json::Object).collect())
+}
+
+pub fn eval(expr:&Expr,input:&Json)->Result<Vec<Json>,FilterErr> {
+    match expr {
+        Expr::Identity=>Ok(vec![input.clone()]),
+        Expr::Recurse=>{
+            let mut out = vec![];
+            recurse(input, &mut out);
+            Ok(out)
+        },
+        Expr::Literal(json)=>Ok(vec![json.clone()]),
+        Expr::Index(target,i)=>product(target, i, input, index),
+        Expr::Slice(target,from,to)=>{
+            let null = Expr::Literal(Json::Null);
+            let from = from.as_deref().unwrap_or(&null);
+            let to = to.as_deref().unwrap_or(&null);
+            let mut out = vec![];
+            for t in eval(target, input)? {
+                for f in eval(from, input)? {
+                    for e in eval(to, input)? {
+                        out.push(slice(&t, &f, &e)?);
+                    }
+                }
+            }
+            Ok(out)
+        },
+        Expr::Iterate(target)=>{
+            let mut out = vec![];
+            for t in eval(target, input)? {
+                out.extend(values(&t)?);
+            }
+            Ok(out)
+        },
+        Expr::Try(inner)=>Ok(eval(inner, input).unwrap_or_default()),
+        Expr::Array(None)=>Ok(vec![Json::Array(vec![])]),
+        Expr::Array(Some(inner))=>Ok(vec![Json::Array(eval(inner, input)?)]),
+        Expr::Object(entries)=>object(entries, input),
+        Expr::Pipe(left,right)=>{
+            let mut out = vec![];
+            for l in eval(left, input)? {
+                out.extend(eval(right, &l)?);
+            }
+            Ok(out)
+        },
+        Expr::Comma(left,right)=>{
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            Ok(out)
+        },
+        Expr::Neg(inner)=>{
+            let mut out = vec![];
+            for value in eval(inner, input)? {
+                match to_f64(&value) {
+                    Some(x)=>out.push(number(-x)?),
+                    None=>return Err(FilterErr::InvalidOperands(
+                        String::from("-"),String::new(),String::from(type_name(&value))
+                    ))
+                }
+            }
+            Ok(out)
+        },
+        Expr::Binary(op,left,right)=>product(left, right, input, |l,r|binary(*op, l, r)),
+        Expr::And(left,right)=>{
+            let mut out = vec![];
+            for l in eval(left, input)? {
+                if !truthy(&l) {
+                    out.push(Json::Bool(false));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Json::Bool(truthy(&r)));
+                }
+            }
+            Ok(out)
+        },
+        Expr::Or(left,right)=>{
+            let mut out = vec![];
+            for l in eval(left, input)? {
+                if truthy(&l) {
+                    out.push(Json::Bool(true));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Json::Bool(truthy(&r)));
+                }
+            }
+            Ok(out)
+        },
+        Expr::Call(name,args)=>call(name, args, input)
+    }
+}
@@ -0,0 +1,96 @@
+mod parser;
+mod eval;
+
+use crate::Json;
+use crate::lexer::LexErr;
+
+// positions are char indices in the filter text. the errors after
+// UnknownFunction come up while applying a filter, and carry type names
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum FilterErr {
+    UnexpectedChar(usize),
+    UnexpectedEnd,
+    InvalidNumber(usize),
+    InvalidEscape(usize),
+    // name/arity, like jq
+    UnknownFunction(String),
+    // value type, index type
+    CannotIndex(String,String),
+    CannotIterate(String),
+    // operator, left type, right type
+    InvalidOperands(String,String,String),
+    // function, input type
+    InvalidArgument(String,String),
+    InvalidKey(String),
+    DivisionByZero,
+    NumberOutOfRange
+}
+
+impl From<LexErr> for FilterErr {
+    fn from(err:LexErr)->FilterErr {
+        match err {
+            LexErr::UnexpectedChar(pos)=>FilterErr::UnexpectedChar(pos),
+            LexErr::UnexpectedEnd=>FilterErr::UnexpectedEnd,
+            LexErr::InvalidEscape(pos)=>FilterErr::InvalidEscape(pos)
+        }
+    }
+}
+
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub(crate) enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Expr {
+    Identity,
+    Recurse,
+    Literal(Json),
+    // the index is evaluated against the same input as the target
+    Index(Box<Expr>,Box<Expr>),
+    Slice(Box<Expr>,Option<Box<Expr>>,Option<Box<Expr>>),
+    Iterate(Box<Expr>),
+    Try(Box<Expr>),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(Expr,Expr)>),
+    Pipe(Box<Expr>,Box<Expr>),
+    Comma(Box<Expr>,Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinaryOp,Box<Expr>,Box<Expr>),
+    And(Box<Expr>,Box<Expr>),
+    Or(Box<Expr>,Box<Expr>),
+    Call(String,Vec<Expr>)
+}
+
+// a compiled jq-style filter. applying it gives every output it produces,
+// in order, for one input
+#[derive(Debug,PartialEq,Clone)]
+pub struct Filter {
+    expr:Expr
+}
+
+impl Filter {
+    pub fn parse(text:&str)->Result<Filter,FilterErr> {
+        let expr = parser::FilterParser::new(text).parse()?;
+        Ok(Filter{expr})
+    }
+    pub fn apply(&self,input:&Json)->Result<Vec<Json>,FilterErr> {
+        eval::eval(&self.expr, input)
+    }
+}
+
+impl Json {
+    pub fn filter(&self,filter:&str)->Result<Vec<Json>,FilterErr> {
+        Filter::parse(filter)?.apply(self)
+    }
+}
@@ -0,0 +1,374 @@
+use crate::Json;
+use crate::lexer::Lexer;
+use crate::number::Decimal;
+use crate::filter::*;
+
+// builtin functions and the number of arguments each takes
+const FUNCTIONS : [(&str,usize);9] = [
+    ("length",0),
+    ("keys",0),
+    ("map",1),
+    ("select",1),
+    ("not",0),
+    ("type",0),
+    ("empty",0),
+    ("add",0),
+    ("sort",0)
+];
+
+const COMPARE_OPS : [(&str,BinaryOp);6] = [
+    ("==",BinaryOp::Eq),
+    ("!=",BinaryOp::Ne),
+    ("<=",BinaryOp::Le),
+    (">=",BinaryOp::Ge),
+    ("<",BinaryOp::Lt),
+    (">",BinaryOp::Gt)
+];
+
+fn is_name_first(c:char)->bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_name_char(c:char)->bool {
+    is_name_first(c) || c.is_ascii_digit()
+}
+
+fn field(target:Expr,name:String)->Expr {
+    Expr::Index(Box::new(target),Box::new(Expr::Literal(Json::String(name))))
+}
+
+pub struct FilterParser {
+    lexer:Lexer
+}
+
+impl FilterParser {
+    pub fn new(text:&str)->FilterParser {
+        FilterParser{
+            lexer:Lexer::new(text)
+        }
+    }
+
+    fn expect(&mut self,c:char)->Result<(),FilterErr> {
+        self.lexer.skip_space();
+        Ok(self.lexer.expect(c)?)
+    }
+
+    // a keyword must not run on into a longer name
+    fn eat_keyword(&mut self,word:&str)->bool {
+        let len = word.chars().count();
+        let follows = self.lexer.peek_at(len).map(is_name_char).unwrap_or(false);
+        !follows && self.lexer.eat(word)
+    }
+
+    pub fn parse(&mut self)->Result<Expr,FilterErr> {
+        let expr = self.parse_pipe()?;
+        self.lexer.skip_space();
+        if self.lexer.peek().is_some() {
+            return Err(self.lexer.unexpected().into());
+        }
+        Ok(expr)
+    }
+
+    fn parse_pipe(&mut self)->Result<Expr,FilterErr> {
+        let left = self.parse_comma()?;
+        self.lexer.skip_space();
+        if self.lexer.eat("|") {
+            let right = self.parse_pipe()?;
+            return Ok(Expr::Pipe(Box::new(left),Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn parse_comma(&mut self)->Result<Expr,FilterErr> {
+        let mut left = self.parse_or()?;
+        loop {
+            self.lexer.skip_space();
+            if !self.lexer.eat(",") {
+                return Ok(left);
+            }
+            let right = self.parse_or()?;
+            left = Expr::Comma(Box::new(left),Box::new(right));
+        }
+    }
+
+    fn parse_or(&mut self)->Result<Expr,FilterErr> {
+        let mut left = self.parse_and()?;
+        loop {
+            self.lexer.skip_space();
+            if !self.eat_keyword("or") {
+                return Ok(left);
+            }
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left),Box::new(right));
+        }
+    }
+
+    fn parse_and(&mut self)->Result<Expr,FilterErr> {
+        let mut left = self.parse_compare()?;
+        loop {
+            self.lexer.skip_space();
+            if !self.eat_keyword("and") {
+                return Ok(left);
+            }
+            let right = self.parse_compare()?;
+            left = Expr::And(Box::new(left),Box::new(right));
+        }
+    }
+
+    // comparisons do not chain
+    fn parse_compare(&mut self)->Result<Expr,FilterErr> {
+        let left = self.parse_additive()?;
+        self.lexer.skip_space();
+        for (text,op) in COMPARE_OPS.iter() {
+            if self.lexer.eat(text) {
+                let right = self.parse_additive()?;
+                return Ok(Expr::Binary(*op,Box::new(left),Box::new(right)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self)->Result<Expr,FilterErr> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            self.lexer.skip_space();
+            let op = match self.lexer.peek() {
+                Some('+')=>BinaryOp::Add,
+                Some('-')=>BinaryOp::Sub,
+                _=>return Ok(left)
+            };
+            self.lexer.pos += 1;
+            let right = self.parse_multiplicative()?;
+            left = Expr::Binary(op,Box::new(left),Box::new(right));
+        }
+    }
+
+    fn parse_multiplicative(&mut self)->Result<Expr,FilterErr> {
+        let mut left = self.parse_unary()?;
+        loop {
+            self.lexer.skip_space();
+            let op = match self.lexer.peek() {
+                Some('*')=>BinaryOp::Mul,
+                Some('/')=>BinaryOp::Div,
+                Some('%')=>BinaryOp::Mod,
+                _=>return Ok(left)
+            };
+            self.lexer.pos += 1;
+            let right = self.parse_unary()?;
+            left = Expr::Binary(op,Box::new(left),Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self)->Result<Expr,FilterErr> {
+        self.lexer.skip_space();
+        if self.lexer.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.parse_unary()?)));
+        }
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self)->Result<Expr,FilterErr> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            match (self.lexer.peek(),self.lexer.peek_at(1)) {
+                (Some('.'),Some('['))=>{
+                    self.lexer.pos += 1;
+                    expr = self.parse_bracket(expr)?;
+                },
+                (Some('.'),Some(c)) if is_name_first(c) || c == '"'=>{
+                    self.lexer.pos += 1;
+                    expr = self.parse_field(expr)?;
+                },
+                (Some('['),_)=>expr = self.parse_bracket(expr)?,
+                (Some('?'),_)=>{
+                    self.lexer.pos += 1;
+                    expr = Expr::Try(Box::new(expr));
+                },
+                _=>return Ok(expr)
+            }
+        }
+    }
+
+    // the dot is already consumed
+    fn parse_field(&mut self,target:Expr)->Result<Expr,FilterErr> {
+        if self.lexer.peek() == Some('"') {
+            let name = self.lexer.string(true)?;
+            Ok(field(target, name))
+        }
+        else {
+            Ok(field(target, self.parse_name()))
+        }
+    }
+
+    // [] iterates, [i] indexes and [from:to] slices
+    fn parse_bracket(&mut self,target:Expr)->Result<Expr,FilterErr> {
+        self.expect('[')?;
+        self.lexer.skip_space();
+        if self.lexer.eat("]") {
+            return Ok(Expr::Iterate(Box::new(target)));
+        }
+        let from = if self.lexer.peek() == Some(':') {
+            None
+        }
+        else {
+            Some(Box::new(self.parse_pipe()?))
+        };
+        self.lexer.skip_space();
+        if !self.lexer.eat(":") {
+            self.expect(']')?;
+            return match from {
+                Some(index)=>Ok(Expr::Index(Box::new(target),index)),
+                None=>Err(self.lexer.unexpected().into())
+            };
+        }
+        self.lexer.skip_space();
+        let to = if self.lexer.peek() == Some(']') {
+            None
+        }
+        else {
+            Some(Box::new(self.parse_pipe()?))
+        };
+        self.expect(']')?;
+        Ok(Expr::Slice(Box::new(target),from,to))
+    }
+
+    fn parse_primary(&mut self)->Result<Expr,FilterErr> {
+        self.lexer.skip_space();
+        match self.lexer.peek() {
+            Some('.')=>{
+                self.lexer.pos += 1;
+                match self.lexer.peek() {
+                    Some('.')=>{
+                        self.lexer.pos += 1;
+                        Ok(Expr::Recurse)
+                    },
+                    Some('[')=>self.parse_bracket(Expr::Identity),
+                    Some(c) if is_name_first(c) || c == '"'=>self.parse_field(Expr::Identity),
+                    _=>Ok(Expr::Identity)
+                }
+            },
+            Some('"')=>Ok(Expr::Literal(Json::String(self.lexer.string(true)?))),
+            Some(c) if c.is_ascii_digit()=>Ok(Expr::Literal(self.parse_number()?)),
+            Some('(')=>{
+                self.lexer.pos += 1;
+                let expr = self.parse_pipe()?;
+                self.expect(')')?;
+                Ok(expr)
+            },
+            Some('[')=>{
+                self.lexer.pos += 1;
+                self.lexer.skip_space();
+                if self.lexer.eat("]") {
+                    return Ok(Expr::Array(None));
+                }
+                let expr = self.parse_pipe()?;
+                self.expect(']')?;
+                Ok(Expr::Array(Some(Box::new(expr))))
+            },
+            Some('{')=>self.parse_object(),
+            Some(c) if is_name_first(c)=>self.parse_call(),
+            _=>Err(self.lexer.unexpected().into())
+        }
+    }
+
+    fn parse_name(&mut self)->String {
+        let mut name = String::new();
+        while let Some(c) = self.lexer.peek() {
+            if !is_name_char(c) {
+                break;
+            }
+            name.push(c);
+            self.lexer.pos += 1;
+        }
+        name
+    }
+
+    fn parse_call(&mut self)->Result<Expr,FilterErr> {
+        let name = self.parse_name();
+        match name.as_str() {
+            "true"=>return Ok(Expr::Literal(Json::Bool(true))),
+            "false"=>return Ok(Expr::Literal(Json::Bool(false))),
+            "null"=>return Ok(Expr::Literal(Json::Null)),
+            _=>{}
+        }
+        let mut args = vec![];
+        self.lexer.skip_space();
+        if self.lexer.eat("(") {
+            loop {
+                args.push(self.parse_pipe()?);
+                self.lexer.skip_space();
+                if !self.lexer.eat(";") {
+                    break;
+                }
+            }
+            self.expect(')')?;
+        }
+        if !FUNCTIONS.contains(&(name.as_str(),args.len())) {
+            return Err(FilterErr::UnknownFunction(format!("{}/{}",name,args.len())));
+        }
+        Ok(Expr::Call(name,args))
+    }
+
+    // {a: f, "b": g, (h): i, c} where c is short for c: .c
+    fn parse_object(&mut self)->Result<Expr,FilterErr> {
+        self.lexer.pos += 1;
+        let mut entries = vec![];
+        self.lexer.skip_space();
+        if self.lexer.eat("}") {
+            return Ok(Expr::Object(entries));
+        }
+        loop {
+            self.lexer.skip_space();
+            let (key,name) = match self.lexer.peek() {
+                Some('"')=>{
+                    let name = self.lexer.string(true)?;
+                    (Expr::Literal(Json::String(name.clone())),Some(name))
+                },
+                Some('(')=>{
+                    self.lexer.pos += 1;
+                    let key = self.parse_pipe()?;
+                    self.expect(')')?;
+                    (key,None)
+                },
+                Some(c) if is_name_first(c)=>{
+                    let name = self.parse_name();
+                    (Expr::Literal(Json::String(name.clone())),Some(name))
+                },
+                _=>return Err(self.lexer.unexpected().into())
+            };
+            self.lexer.skip_space();
+            let value = if self.lexer.eat(":") {
+                self.parse_or()?
+            }
+            else {
+                match name {
+                    Some(name)=>field(Expr::Identity, name),
+                    None=>return Err(self.lexer.unexpected().into())
+                }
+            };
+            entries.push((key,value));
+            self.lexer.skip_space();
+            if self.lexer.eat("}") {
+                return Ok(Expr::Object(entries));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn parse_number(&mut self)->Result<Json,FilterErr> {
+        let start = self.lexer.pos;
+        let mut text = String::new();
+        while let Some(c) = self.lexer.peek() {
+            let sign = (c == '+' || c == '-') && text.ends_with(['e','E']);
+            if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || sign) {
+                break;
+            }
+            text.push(c);
+            self.lexer.pos += 1;
+        }
+        match Decimal::parse(&text) {
+            Some(_)=>Ok(Json::Number(text)),
+            None=>Err(FilterErr::InvalidNumber(start))
+        }
+    }
+}
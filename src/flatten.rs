@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap,HashMap};
+
+use crate::Json;
+
+// how array elements appear in flattened keys
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum IndexNotation {
+    // a.0.b, object keys made of digits are escaped as a.\0
+    Dot,
+    // a[0].b
+    Bracket
+}
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct FlattenOptions {
+    pub separator:String,
+    pub index:IndexNotation
+}
+
+impl Default for FlattenOptions {
+    fn default()->FlattenOptions {
+        FlattenOptions{
+            separator:String::from("."),
+            index:IndexNotation::Dot
+        }
+    }
+}
+
+// InvalidKey and Conflict carry the flattened key at fault
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum FlattenErr {
+    NotAnObject,
+    InvalidKey(String),
+    Conflict(String)
+}
+
+fn is_index(segment:&str)->bool {
+    !segment.is_empty() && segment.bytes().all(|b|b.is_ascii_digit()) && (segment == "0" || !segment.starts_with('0'))
+}
+
+// a backslash goes before backslashes, characters of the separator and,
+// with brackets, the bracket characters
+fn escape(key:&str,options:&FlattenOptions)->String {
+    let bracket = options.index == IndexNotation::Bracket;
+    let mut out = String::new();
+    if !bracket && is_index(key) {
+        out.push('\\');
+    }
+    for c in key.chars() {
+        if c == '\\' || options.separator.contains(c) || bracket && (c == '[' || c == ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// objects are visited in key order, so values with the same keys give the
+// same leaves in the same order
+fn walk<'a>(json:&'a Json,path:Option<&str>,options:&FlattenOptions,out:&mut Vec<(String,&'a Json)>) {
+    let child = |segment:&str|match path {
+        Some(path)=>format!("{}{}{}",path,options.separator,segment),
+        None=>String::from(segment)
+    };
+    match json {
+        Json::Object(map) if !map.is_empty()=>{
+            let mut keys : Vec<&String> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                walk(&map[key], Some(&child(&escape(key, options))), options, out);
+            }
+        },
+        Json::Array(vec) if !vec.is_empty()=>{
+            for (i,item) in vec.iter().enumerate() {
+                let key = match options.index {
+                    IndexNotation::Dot=>child(&i.to_string()),
+                    IndexNotation::Bracket=>format!("{}[{}]",path.unwrap_or(""),i)
+                };
+                walk(item, Some(&key), options, out);
+            }
+        },
+        _=>out.push((String::from(path.unwrap_or("")),json))
+    }
+}
+
+// the flattened keys of json with their leaves, as flatten_with makes them
+pub(crate) fn leaves<'a>(json:&'a Json,options:&FlattenOptions)->Vec<(String,&'a Json)> {
+    let mut out = vec![];
+    walk(json, None, options, &mut out);
+    out
+}
+
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize)
+}
+
+// an index larger than limit is refused
+fn index(text:&str,limit:usize)->Option<Segment> {
+    text.parse().ok().filter(|i|*i <= limit).map(Segment::Index)
+}
+
+fn segment(text:String,escaped:bool,limit:usize,options:&FlattenOptions)->Option<Segment> {
+    if options.index == IndexNotation::Dot && !escaped && is_index(&text) {
+        index(&text, limit)
+    }
+    else {
+        Some(Segment::Key(text))
+    }
+}
+
+pub(crate) fn parse(key:&str,limit:usize,options:&FlattenOptions)->Option<Vec<Segment>> {
+    let separator = options.separator.as_str();
+    let bracket = options.index == IndexNotation::Bracket;
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut escaped = false;
+    // false right after an index, where only a separator or another
+    // index may follow
+    let mut open = true;
+    let mut rest = key;
+    while let Some(c) = rest.chars().next() {
+        if c == '\\' {
+            let next = rest[1..].chars().next()?;
+            if !open {
+                return None;
+            }
+            text.push(next);
+            escaped = true;
+            rest = &rest[1 + next.len_utf8()..];
+        }
+        else if !separator.is_empty() && rest.starts_with(separator) {
+            if open {
+                segments.push(segment(std::mem::take(&mut text), escaped, limit, options)?);
+            }
+            open = true;
+            escaped = false;
+            rest = &rest[separator.len()..];
+        }
+        else if bracket && c == '[' {
+            // a leading index has no key before it
+            if open && rest.len() < key.len() {
+                segments.push(Segment::Key(std::mem::take(&mut text)));
+            }
+            let end = rest.find(']')?;
+            if !is_index(&rest[1..end]) {
+                return None;
+            }
+            segments.push(index(&rest[1..end], limit)?);
+            open = false;
+            escaped = false;
+            rest = &rest[end + 1..];
+        }
+        else {
+            if !open {
+                return None;
+            }
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if open {
+        segments.push(segment(text, escaped, limit, options)?);
+    }
+    Some(segments)
+}
+
+// the tree being rebuilt, with arrays kept sparse until every key is in
+pub(crate) enum Node {
+    Leaf(Json),
+    Object(HashMap<String,Node>),
+    Array(BTreeMap<usize,Node>)
+}
+
+fn container(segment:&Segment)->Node {
+    match segment {
+        Segment::Key(_)=>Node::Object(HashMap::new()),
+        Segment::Index(_)=>Node::Array(BTreeMap::new())
+    }
+}
+
+// None when the path runs into a value already set, or into a container
+// of the other kind. nothing is changed then
+pub(crate) fn insert(node:&mut Node,segments:&[Segment],value:Json)->Option<()> {
+    let (first,rest) = segments.split_first()?;
+    let fresh = ||rest.first().map_or(Node::Leaf(Json::Null), container);
+    let (slot,vacant) = match (node,first) {
+        (Node::Object(map),Segment::Key(key))=>{
+            let vacant = !map.contains_key(key);
+            (map.entry(key.clone()).or_insert_with(fresh),vacant)
+        },
+        (Node::Array(map),Segment::Index(i))=>{
+            let vacant = !map.contains_key(i);
+            (map.entry(*i).or_insert_with(fresh),vacant)
+        },
+        _=>return None
+    };
+    if rest.is_empty() {
+        if !vacant {
+            return None;
+        }
+        *slot = Node::Leaf(value);
+        return Some(());
+    }
+    insert(slot, rest, value)
+}
+
+// missing array elements become null. indices are bounded when the keys
+// are parsed, so the gaps cannot outgrow the input
+pub(crate) fn to_json(node:Node)->Json {
+    match node {
+        Node::Leaf(json)=>json,
+        Node::Object(map)=>Json::Object(map.into_iter().map(|(key,node)|(key,to_json(node))).collect()),
+        Node::Array(map)=>{
+            let mut vec = vec![];
+            for (i,node) in map {
+                vec.resize(i, Json::Null);
+                vec.push(to_json(node));
+            }
+            Json::Array(vec)
+        }
+    }
+}
+
+impl Json {
+    pub fn flatten(&self)->Json {
+        self.flatten_with(&FlattenOptions::default())
+    }
+    // one key per leaf, joining the keys and indices on the way to it.
+    // empty objects and arrays are leaves, and values other than objects
+    // and arrays holding something come back unchanged
+    pub fn flatten_with(&self,options:&FlattenOptions)->Json {
+        let nested = match self {
+            Json::Object(map)=>!map.is_empty(),
+            Json::Array(vec)=>!vec.is_empty(),
+            _=>false
+        };
+        if !nested {
+            return self.clone();
+        }
+        Json::Object(leaves(self, options).into_iter().map(|(key,value)|(key,value.clone())).collect())
+    }
+    pub fn unflatten(&self)->Result<Json,FlattenErr> {
+        self.unflatten_with(&FlattenOptions::default())
+    }
+    // rebuilds the nested value from flattened keys. an index may not be
+    // larger than the number of keys, and gaps left in arrays are null
+    pub fn unflatten_with(&self,options:&FlattenOptions)->Result<Json,FlattenErr> {
+        let map = self.as_object().ok_or(FlattenErr::NotAnObject)?;
+        let mut keys : Vec<&String> = map.keys().collect();
+        keys.sort();
+        let mut root = None;
+        for key in keys {
+            let segments = parse(key, map.len(), options).ok_or_else(||FlattenErr::InvalidKey(key.clone()))?;
+            let root = root.get_or_insert_with(||container(&segments[0]));
+            insert(root, &segments, map[key].clone()).ok_or_else(||FlattenErr::Conflict(key.clone()))?;
+        }
+        Ok(root.map_or(Json::Object(HashMap::new()), to_json))
+    }
+}
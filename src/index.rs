@@ -0,0 +1,119 @@
+use std::ops::{Index,IndexMut};
+
+use crate::Json;
+
+static NULL : Json = Json::Null;
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T:?Sized + Sealed> Sealed for &T {}
+}
+
+// implemented for object keys (str, String) and array positions (usize)
+pub trait JsonIndex : private::Sealed {
+    fn index_into<'v>(&self,json:&'v Json)->Option<&'v Json>;
+    fn index_into_mut<'v>(&self,json:&'v mut Json)->Option<&'v mut Json>;
+    fn index_or_insert<'v>(&self,json:&'v mut Json)->&'v mut Json;
+}
+
+pub(crate) fn type_name(json:&Json)->&'static str {
+    match json {
+        Json::Null=>"null",
+        Json::Bool(_)=>"boolean",
+        Json::Number(_)=>"number",
+        Json::String(_)=>"string",
+        Json::Array(_)=>"array",
+        Json::Object(_)=>"object"
+    }
+}
+
+impl JsonIndex for usize {
+    fn index_into<'v>(&self,json:&'v Json)->Option<&'v Json> {
+        json.as_array().and_then(|vec|vec.get(*self))
+    }
+    fn index_into_mut<'v>(&self,json:&'v mut Json)->Option<&'v mut Json> {
+        json.as_mut_array().and_then(|vec|vec.get_mut(*self))
+    }
+    fn index_or_insert<'v>(&self,json:&'v mut Json)->&'v mut Json {
+        match json {
+            Json::Array(vec)=>{
+                let len = vec.len();
+                match vec.get_mut(*self) {
+                    Some(json)=>json,
+                    None=>panic!("index {} is out of bounds for array of length {}",self,len)
+                }
+            },
+            _=>panic!("cannot index into {} with a number",type_name(json))
+        }
+    }
+}
+
+impl JsonIndex for str {
+    fn index_into<'v>(&self,json:&'v Json)->Option<&'v Json> {
+        json.as_object().and_then(|map|map.get(self))
+    }
+    fn index_into_mut<'v>(&self,json:&'v mut Json)->Option<&'v mut Json> {
+        json.as_mut_object().and_then(|map|map.get_mut(self))
+    }
+    // null turns into an object, so nested keys can be assigned in one go
+    fn index_or_insert<'v>(&self,json:&'v mut Json)->&'v mut Json {
+        if *json == Json::Null {
+            *json = Json::Object(Default::default());
+        }
+        match json {
+            Json::Object(map)=>map.entry(String::from(self)).or_insert(Json::Null),
+            _=>panic!("cannot index into {} with a key",type_name(json))
+        }
+    }
+}
+
+impl JsonIndex for String {
+    fn index_into<'v>(&self,json:&'v Json)->Option<&'v Json> {
+        self.as_str().index_into(json)
+    }
+    fn index_into_mut<'v>(&self,json:&'v mut Json)->Option<&'v mut Json> {
+        self.as_str().index_into_mut(json)
+    }
+    fn index_or_insert<'v>(&self,json:&'v mut Json)->&'v mut Json {
+        self.as_str().index_or_insert(json)
+    }
+}
+
+impl<T:?Sized + JsonIndex> JsonIndex for &T {
+    fn index_into<'v>(&self,json:&'v Json)->Option<&'v Json> {
+        (**self).index_into(json)
+    }
+    fn index_into_mut<'v>(&self,json:&'v mut Json)->Option<&'v mut Json> {
+        (**self).index_into_mut(json)
+    }
+    fn index_or_insert<'v>(&self,json:&'v mut Json)->&'v mut Json {
+        (**self).index_or_insert(json)
+    }
+}
+
+// missing keys and positions read as null
+impl<I:JsonIndex> Index<I> for Json {
+    type Output = Json;
+    fn index(&self,index:I)->&Json {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+// writing to a missing key inserts it, while positions must already exist
+impl<I:JsonIndex> IndexMut<I> for Json {
+    fn index_mut(&mut self,index:I)->&mut Json {
+        index.index_or_insert(self)
+    }
+}
+
+impl Json {
+    pub fn get<I:JsonIndex>(&self,index:I)->Option<&Json> {
+        index.index_into(self)
+    }
+    pub fn get_mut<I:JsonIndex>(&mut self,index:I)->Option<&mut Json> {
+        index.index_into_mut(self)
+    }
+}
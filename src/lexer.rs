@@ -0,0 +1,127 @@
+// the character reader under the path and filter parsers. positions are
+// char indices in the text, and the errors turn into the parser's own
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub(crate) enum LexErr {
+    UnexpectedChar(usize),
+    UnexpectedEnd,
+    InvalidEscape(usize)
+}
+
+pub(crate) struct Lexer {
+    pub chars:Vec<char>,
+    pub pos:usize
+}
+
+impl Lexer {
+    pub fn new(text:&str)->Lexer {
+        Lexer{
+            chars:text.chars().collect(),
+            pos:0
+        }
+    }
+
+    pub fn peek(&self)->Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    pub fn peek_at(&self,offset:usize)->Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    pub fn unexpected(&self)->LexErr {
+        match self.peek() {
+            Some(_)=>LexErr::UnexpectedChar(self.pos),
+            None=>LexErr::UnexpectedEnd
+        }
+    }
+
+    pub fn expect(&mut self,c:char)->Result<(),LexErr> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        }
+        else {
+            Err(self.unexpected())
+        }
+    }
+
+    pub fn eat(&mut self,text:&str)->bool {
+        let matches = text.chars().enumerate().all(|(i,c)|self.peek_at(i) == Some(c));
+        if matches {
+            self.pos += text.chars().count();
+        }
+        matches
+    }
+
+    pub fn skip_space(&mut self) {
+        while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn hex4(&mut self)->Result<u32,LexErr> {
+        let mut value = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c|c.to_digit(16)) {
+                Some(d)=>value = value * 16 + d,
+                None=>return Err(LexErr::InvalidEscape(self.pos))
+            }
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+
+    fn unicode_escape(&mut self,start:usize)->Result<char,LexErr> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.eat("\\u") {
+                return Err(LexErr::InvalidEscape(start));
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(LexErr::InvalidEscape(start));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        }
+        else {
+            high
+        };
+        char::from_u32(code).ok_or(LexErr::InvalidEscape(start))
+    }
+
+    // a string closed by the quote it starts with, which may be escaped
+    // inside it. control characters are refused unless controls is set
+    pub fn string(&mut self,controls:bool)->Result<String,LexErr> {
+        let quote = self.peek().ok_or(LexErr::UnexpectedEnd)?;
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            let c = self.peek().ok_or(LexErr::UnexpectedEnd)?;
+            let start = self.pos;
+            self.pos += 1;
+            if c == quote {
+                return Ok(text);
+            }
+            if !controls && (c as u32) < 0x20 {
+                return Err(LexErr::UnexpectedChar(start));
+            }
+            if c != '\\' {
+                text.push(c);
+                continue;
+            }
+            let escaped = self.peek().ok_or(LexErr::UnexpectedEnd)?;
+            self.pos += 1;
+            match escaped {
+                'b'=>text.push('\x08'),
+                'f'=>text.push('\x0C'),
+                'n'=>text.push('\n'),
+                'r'=>text.push('\r'),
+                't'=>text.push('\t'),
+                '/' | '\\'=>text.push(escaped),
+                'u'=>text.push(self.unicode_escape(start)?),
+                e if e == quote=>text.push(e),
+                _=>return Err(LexErr::InvalidEscape(start))
+            }
+        }
+    }
+}
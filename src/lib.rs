@@ -1,6 +1,51 @@
+#[macro_use]
+mod macros;
 mod parser;
 mod display;
-pub use parser::Jerr;
+mod serializer;
+mod number;
+mod semantic;
+mod pointer;
+mod lexer;
+mod path;
+mod patch;
+mod merge;
+mod compare;
+mod schema;
+mod index;
+mod convert;
+mod borrowed;
+mod tape;
+mod canonical;
+mod ordering;
+mod writer;
+mod filter;
+mod cbor;
+mod msgpack;
+mod bson;
+mod csv;
+mod yaml;
+mod flatten;
+pub use parser::{Jerr,Diagnostic};
+pub use pointer::PointerErr;
+pub use path::{JsonPath,PathMatch,PathErr};
+pub use patch::{Patch,PatchOp,PatchErr,diff};
+pub use merge::{MergeOptions,ArrayMerge,ConflictPolicy,MergeErr};
+pub use compare::{Difference,compare,render_diff};
+pub use schema::{Schema,SchemaErr,ValidationError,SchemaInferrer,InferOptions};
+pub use index::JsonIndex;
+pub use borrowed::JsonRef;
+pub use tape::{Tape,Cursor,Items,Members};
+pub use canonical::CanonicalErr;
+pub use writer::{JsonWriter,WriterErr};
+pub use serializer::FormatOptions;
+pub use filter::{Filter,FilterErr};
+pub use cbor::CborErr;
+pub use msgpack::{MsgPackOptions,ExtPolicy,MsgPackErr};
+pub use bson::BsonErr;
+pub use csv::{CsvOptions,CsvArrays,CsvErr};
+pub use yaml::YamlErr;
+pub use flatten::{FlattenOptions,IndexNotation,FlattenErr};
 
 #[derive(PartialEq,Eq,Clone)]
 pub enum Json {
@@ -0,0 +1,218 @@
+use std::{env, fs, io::{self, Read, Write}, process};
+
+use ryson::{Filter, FilterErr, FormatOptions, Jerr, Json};
+
+const USAGE : &str = "usage: ryson <command> [options] [file...]
+       ryson query <filter> [options] [file...]
+
+commands:
+  validate      check that each input is valid json
+  format        pretty-print each input
+  minify        print each input on a single line
+  query         print every output of a jq-style filter for each input
+
+options:
+  --indent N    spaces per level when formatting or querying (default 2)
+  -c, --compact print query outputs on a single line
+  --sort-keys   write object keys in sorted order
+  -i, --in-place
+                rewrite the files instead of printing them
+
+with no files, or a file named -, the input is read from stdin.
+exits with 1 when an input is not valid json or the filter fails on it,
+and 2 on other errors.";
+
+const STDIN : &str = "-";
+
+enum Command {
+    Validate,
+    Format,
+    Minify,
+    Query(Filter)
+}
+
+struct Args {
+    command:Command,
+    options:FormatOptions,
+    in_place:bool,
+    files:Vec<String>
+}
+
+// the filter of a query is its first argument that is not an option
+fn parse_args(args:&[String])->Result<Args,String> {
+    let query = args.first().map(String::as_str) == Some("query");
+    let command = match args.first().map(String::as_str) {
+        Some("validate")=>Some(Command::Validate),
+        Some("format")=>Some(Command::Format),
+        Some("minify")=>Some(Command::Minify),
+        Some("query")=>None,
+        Some(other)=>return Err(format!("unknown command '{}'",other)),
+        None=>return Err(String::from("missing command"))
+    };
+    let pretty = query || matches!(command,Some(Command::Format));
+    let mut options = FormatOptions{
+        indent:if pretty {Some(2)} else {None},
+        sort_keys:false
+    };
+    let mut in_place = false;
+    let mut files = vec![];
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--indent"=>{
+                let indent = iter.next().and_then(|n|n.parse().ok()).ok_or("--indent needs a number")?;
+                if !pretty {
+                    return Err(String::from("--indent only applies to format and query"));
+                }
+                options.indent = Some(indent);
+            },
+            "-c" | "--compact"=>{
+                if !query {
+                    return Err(String::from("--compact only applies to query"));
+                }
+                options.indent = None;
+            },
+            "--sort-keys"=>options.sort_keys = true,
+            "-i" | "--in-place"=>in_place = true,
+            option if option.starts_with('-') && option != STDIN=>{
+                return Err(format!("unknown option '{}'",option));
+            },
+            file=>files.push(String::from(file))
+        }
+    }
+    let command = match command {
+        Some(command)=>command,
+        None if files.is_empty()=>return Err(String::from("missing filter")),
+        None=>Command::Query(Filter::parse(&files.remove(0)).map_err(|err|describe_filter(&err))?)
+    };
+    if files.is_empty() {
+        files.push(String::from(STDIN));
+    }
+    if in_place && matches!(command,Command::Validate | Command::Query(_)) {
+        return Err(String::from("--in-place only applies to format and minify"));
+    }
+    if in_place && files.iter().any(|file|file == STDIN) {
+        return Err(String::from("--in-place needs files to rewrite"));
+    }
+    Ok(Args{command,options,in_place,files})
+}
+
+fn describe(err:&Jerr)->String {
+    match err {
+        Jerr::InvalidToken(token,_)=>format!("invalid token '{}'",token),
+        Jerr::UnexpectedChar(_)=>String::from("unexpected character"),
+        Jerr::UnexpectedEnd=>String::from("unexpected end of input"),
+        Jerr::InvalidUnicodeSequence(sequence,_)=>format!("invalid unicode escape '\\u{}'",sequence),
+        Jerr::UnknownEscape(c,_)=>format!("unknown escape '\\{}'",c),
+        Jerr::ExpectedCommaOrEnd(_)=>String::from("expected ',' or the end of the container"),
+        Jerr::ExpectedColon(_)=>String::from("expected ':'"),
+        Jerr::ExpectedValue(_)=>String::from("expected a value"),
+        Jerr::ExpectedProperty(_)=>String::from("expected a property name"),
+        Jerr::ExpectedEnd(_)=>String::from("expected the end of the input")
+    }
+}
+
+fn describe_filter(err:&FilterErr)->String {
+    match err {
+        FilterErr::UnexpectedChar(pos)=>format!("unexpected character in filter at {}",pos),
+        FilterErr::UnexpectedEnd=>String::from("unexpected end of filter"),
+        FilterErr::InvalidNumber(pos)=>format!("invalid number in filter at {}",pos),
+        FilterErr::InvalidEscape(pos)=>format!("invalid escape in filter at {}",pos),
+        FilterErr::UnknownFunction(name)=>format!("unknown function {}",name),
+        FilterErr::CannotIndex(value,index)=>format!("cannot index {} with {}",value,index),
+        FilterErr::CannotIterate(value)=>format!("cannot iterate over {}",value),
+        FilterErr::InvalidOperands(op,left,right) if left.is_empty()=>format!("cannot apply {} to {}",op,right),
+        FilterErr::InvalidOperands(op,left,right)=>format!("cannot apply {} to {} and {}",op,left,right),
+        FilterErr::InvalidArgument(name,value)=>format!("{} cannot be applied to {}",name,value),
+        FilterErr::InvalidKey(key)=>format!("object keys must be strings, not {}",key),
+        FilterErr::DivisionByZero=>String::from("division by zero"),
+        FilterErr::NumberOutOfRange=>String::from("number out of range")
+    }
+}
+
+fn display_name(file:&str)->&str {
+    if file == STDIN {"<stdin>"} else {file}
+}
+
+fn read_input(file:&str)->io::Result<String> {
+    if file == STDIN {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    }
+    else {
+        fs::read_to_string(file)
+    }
+}
+
+fn output(json:&Json,args:&Args,file:&str)->io::Result<()> {
+    let mut bytes = vec![];
+    json.to_writer_with(&mut bytes, &args.options)?;
+    bytes.push(b'\n');
+    if args.in_place {
+        fs::write(file, bytes)
+    }
+    else {
+        io::stdout().write_all(&bytes)
+    }
+}
+
+// the exit code is the worst outcome over all the inputs
+fn run(args:&Args)->i32 {
+    let mut code = 0;
+    for file in args.files.iter() {
+        let name = display_name(file);
+        let text = match read_input(file) {
+            Ok(text)=>text,
+            Err(err)=>{
+                eprintln!("{}: {}",name,err);
+                code = 2;
+                continue;
+            }
+        };
+        let json = match Json::parse(&text) {
+            Ok(json)=>json,
+            Err(err)=>{
+                let (line,column) = err.line_col(&text);
+                eprintln!("{}:{}:{}: {}",name,line,column,describe(&err));
+                code = code.max(1);
+                continue;
+            }
+        };
+        let outputs = match &args.command {
+            Command::Validate=>vec![],
+            Command::Query(filter)=>match filter.apply(&json) {
+                Ok(outputs)=>outputs,
+                Err(err)=>{
+                    eprintln!("{}: {}",name,describe_filter(&err));
+                    code = code.max(1);
+                    continue;
+                }
+            },
+            _=>vec![json]
+        };
+        for json in outputs.iter() {
+            if let Err(err) = output(json, args, file) {
+                eprintln!("{}: {}",name,err);
+                code = 2;
+                break;
+            }
+        }
+    }
+    code
+}
+
+fn main() {
+    let args : Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg|arg == "-h" || arg == "--help") {
+        println!("{}",USAGE);
+        return;
+    }
+    match parse_args(&args) {
+        Ok(args)=>process::exit(run(&args)),
+        Err(message)=>{
+            eprintln!("ryson: {}\n\n{}",message,USAGE);
+            process::exit(2);
+        }
+    }
+}
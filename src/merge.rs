@@ -0,0 +1,135 @@
+use crate::Json;
+use crate::pointer::escape_token;
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum ArrayMerge {
+    // arrays are taken as whole values, differing arrays are a conflict
+    Replace,
+    Append,
+    // object elements with the same value under the key are merged,
+    // the others are appended
+    MergeByKey(String)
+}
+
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum ConflictPolicy {
+    Error,
+    LeftWins,
+    RightWins
+}
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct MergeOptions {
+    pub arrays:ArrayMerge,
+    pub conflicts:ConflictPolicy
+}
+
+impl Default for MergeOptions {
+    fn default()->MergeOptions {
+        MergeOptions{
+            arrays:ArrayMerge::Replace,
+            conflicts:ConflictPolicy::RightWins
+        }
+    }
+}
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum MergeErr {
+    // json pointer to the conflicting value
+    Conflict(String)
+}
+
+fn key_of<'a>(json:&'a Json,key:&str)->Option<&'a Json> {
+    json.as_object().and_then(|map|map.get(key))
+}
+
+fn merge_by_key(left:&mut Vec<Json>,right:&[Json],key:&str,path:&str,options:&MergeOptions)->Result<(),MergeErr> {
+    for element in right {
+        let position = key_of(element, key).and_then(|id|{
+            left.iter().position(|candidate|match key_of(candidate, key) {
+                Some(other)=>other.semantic_eq(id),
+                None=>false
+            })
+        });
+        match position {
+            Some(i)=>merge_into(&mut left[i], element, &format!("{}/{}",path,i), options)?,
+            None=>left.push(element.clone())
+        }
+    }
+    Ok(())
+}
+
+fn merge_into(left:&mut Json,right:&Json,path:&str,options:&MergeOptions)->Result<(),MergeErr> {
+    match (left,right) {
+        (Json::Object(a),Json::Object(b))=>{
+            for (key,value) in b {
+                match a.get_mut(key) {
+                    Some(existing)=>{
+                        let at = format!("{}/{}",path,escape_token(key));
+                        merge_into(existing, value, &at, options)?;
+                    },
+                    None=>{
+                        a.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Ok(())
+        },
+        (Json::Array(a),Json::Array(b)) if options.arrays != ArrayMerge::Replace=>{
+            match &options.arrays {
+                ArrayMerge::MergeByKey(key)=>merge_by_key(a, b, key, path, options),
+                _=>{
+                    a.extend(b.iter().cloned());
+                    Ok(())
+                }
+            }
+        },
+        (left,right)=>{
+            if left.semantic_eq(right) {
+                return Ok(());
+            }
+            match options.conflicts {
+                ConflictPolicy::Error=>Err(MergeErr::Conflict(String::from(path))),
+                ConflictPolicy::LeftWins=>Ok(()),
+                ConflictPolicy::RightWins=>{
+                    *left = right.clone();
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+impl Json {
+    // RFC 7396: members of the patch replace those of the value,
+    // null members remove them and non-object patches replace it entirely
+    pub fn merge_patch(&mut self,patch:&Json) {
+        let patch_map = match patch {
+            Json::Object(map)=>map,
+            _=>{
+                *self = patch.clone();
+                return;
+            }
+        };
+        if self.as_object().is_none() {
+            *self = Json::Object(Default::default());
+        }
+        let map = self.as_mut_object().unwrap();
+        for (key,value) in patch_map {
+            if *value == Json::Null {
+                map.remove(key);
+            }
+            else {
+                map.entry(key.clone()).or_insert(Json::Null).merge_patch(value);
+            }
+        }
+    }
+    // merges other into self, objects are merged member by member and
+    // everything else follows the options; on error self is unchanged
+    pub fn deep_merge(&mut self,other:&Json,options:&MergeOptions)->Result<(),MergeErr> {
+        let mut merged = self.clone();
+        merge_into(&mut merged, other, "", options)?;
+        *self = merged;
+        Ok(())
+    }
+}
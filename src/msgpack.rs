@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::canonical::format_double;
+use crate::cbor::{base64,BASE64_ALPHABET};
+use crate::number::Decimal;
+use crate::pointer::escape_token;
+
+// decoding errors carry the byte offset of the offending value, like the
+// char positions of Jerr. encoding fails only on numbers that are not json
+// numbers or overflow a double, with their json pointer
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum MsgPackErr {
+    UnexpectedEnd,
+    InvalidMarker(usize),
+    InvalidUtf8(usize),
+    UnsupportedExt(usize),
+    TrailingBytes(usize),
+    // values nested more than 256 levels deep
+    TooDeep(usize),
+    InvalidNumber(String)
+}
+
+// what extension types decode to
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum ExtPolicy {
+    Error,
+    Null,
+    // {"type": <ext type>, "data": <base64 of the payload>}
+    Object
+}
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct MsgPackOptions {
+    pub ext:ExtPolicy
+}
+
+impl Default for MsgPackOptions {
+    fn default()->MsgPackOptions {
+        MsgPackOptions{
+            ext:ExtPolicy::Error
+        }
+    }
+}
+
+const NIL : u8 = 0xc0;
+const FALSE : u8 = 0xc2;
+const TRUE : u8 = 0xc3;
+const BIN8 : u8 = 0xc4;
+const BIN16 : u8 = 0xc5;
+const BIN32 : u8 = 0xc6;
+const EXT8 : u8 = 0xc7;
+const EXT16 : u8 = 0xc8;
+const EXT32 : u8 = 0xc9;
+const FLOAT32 : u8 = 0xca;
+const FLOAT64 : u8 = 0xcb;
+const UINT8 : u8 = 0xcc;
+const UINT16 : u8 = 0xcd;
+const UINT32 : u8 = 0xce;
+const UINT64 : u8 = 0xcf;
+const INT8 : u8 = 0xd0;
+const INT16 : u8 = 0xd1;
+const INT32 : u8 = 0xd2;
+const INT64 : u8 = 0xd3;
+const FIXEXT1 : u8 = 0xd4;
+const FIXEXT16 : u8 = 0xd8;
+const STR8 : u8 = 0xd9;
+const STR16 : u8 = 0xda;
+const STR32 : u8 = 0xdb;
+const ARRAY16 : u8 = 0xdc;
+const ARRAY32 : u8 = 0xdd;
+const MAP16 : u8 = 0xde;
+const MAP32 : u8 = 0xdf;
+
+fn write_unsigned(out:&mut Vec<u8>,value:u64) {
+    if value < 0x80 {
+        out.push(value as u8);
+    }
+    else if value <= u8::MAX as u64 {
+        out.push(UINT8);
+        out.push(value as u8);
+    }
+    else if value <= u16::MAX as u64 {
+        out.push(UINT16);
+        out.extend_from_slice(&(value as u16).to_be_bytes());
+    }
+    else if value <= u32::MAX as u64 {
+        out.push(UINT32);
+        out.extend_from_slice(&(value as u32).to_be_bytes());
+    }
+    else {
+        out.push(UINT64);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_signed(out:&mut Vec<u8>,value:i64) {
+    if value >= -32 {
+        out.push(value as u8);
+    }
+    else if value >= i8::MIN as i64 {
+        out.push(INT8);
+        out.push(value as u8);
+    }
+    else if value >= i16::MIN as i64 {
+        out.push(INT16);
+        out.extend_from_slice(&(value as i16).to_be_bytes());
+    }
+    else if value >= i32::MIN as i64 {
+        out.push(INT32);
+        out.extend_from_slice(&(value as i32).to_be_bytes());
+    }
+    else {
+        out.push(INT64);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+// the fix, 8, 16 and 32 bit forms of a length. str8 has no array or map
+// counterpart, so those pass None for it
+fn write_length(out:&mut Vec<u8>,len:usize,fix:(u8,usize),long:(Option<u8>,u8,u8)) {
+    let (fix_marker,fix_limit) = fix;
+    let (marker8,marker16,marker32) = long;
+    if len < fix_limit {
+        out.push(fix_marker | len as u8);
+    }
+    else if let (Some(marker8),true) = (marker8,len <= u8::MAX as usize) {
+        out.push(marker8);
+        out.push(len as u8);
+    }
+    else if len <= u16::MAX as usize {
+        out.push(marker16);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    }
+    else {
+        out.push(marker32);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+fn write_str(out:&mut Vec<u8>,str:&str) {
+    write_length(out, str.len(), (0xa0,32), (Some(STR8),STR16,STR32));
+    out.extend_from_slice(str.as_bytes());
+}
+
+// integers take the smallest integer form that holds them, other numbers
+// the smallest float that reads back as the same double. numbers beyond
+// those get the nearest double
+fn write_number(out:&mut Vec<u8>,num:&str)->Option<()> {
+    Decimal::parse(num)?;
+    if !num.contains(['.','e','E']) && num != "-0" {
+        if let Ok(value) = num.parse::<u64>() {
+            write_unsigned(out, value);
+            return Some(());
+        }
+        if let Ok(value) = num.parse::<i64>() {
+            write_signed(out, value);
+            return Some(());
+        }
+    }
+    let value : f64 = num.parse().ok()?;
+    if !value.is_finite() {
+        return None;
+    }
+    if value as f32 as f64 == value {
+        out.push(FLOAT32);
+        out.extend_from_slice(&(value as f32).to_be_bytes());
+    }
+    else {
+        out.push(FLOAT64);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    Some(())
+}
+
+fn write_msgpack(json:&Json,out:&mut Vec<u8>,path:&mut String)->Result<(),MsgPackErr> {
+    match json {
+        Json::Null=>out.push(NIL),
+        Json::Bool(b)=>out.push(if *b {TRUE} else {FALSE}),
+        Json::Number(num)=>{
+            if write_number(out, num).is_none() {
+                return Err(MsgPackErr::InvalidNumber(path.clone()));
+            }
+        },
+        Json::String(str)=>write_str(out, str),
+        Json::Array(vec)=>{
+            write_length(out, vec.len(), (0x90,16), (None,ARRAY16,ARRAY32));
+            for (i,item) in vec.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("/{}",i));
+                write_msgpack(item, out, path)?;
+                path.truncate(len);
+            }
+        },
+        Json::Object(map)=>{
+            // sorted so that equal values always encode the same way
+            let mut keys : Vec<&String> = map.keys().collect();
+            keys.sort();
+            write_length(out, keys.len(), (0x80,16), (None,MAP16,MAP32));
+            for key in keys {
+                write_str(out, key);
+                let len = path.len();
+                path.push('/');
+                path.push_str(&escape_token(key));
+                write_msgpack(&map[key], out, path)?;
+                path.truncate(len);
+            }
+        }
+    }
+    Ok(())
+}
+
+// nesting allowed when decoding, so hostile input cannot exhaust the stack
+const MAX_DEPTH : usize = 256;
+
+struct MsgPackReader<'a> {
+    bytes:&'a [u8],
+    pos:usize,
+    depth:usize,
+    options:&'a MsgPackOptions
+}
+
+impl<'a> MsgPackReader<'a> {
+    fn take(&mut self,len:u64)->Result<&'a [u8],MsgPackErr> {
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(MsgPackErr::UnexpectedEnd);
+        }
+        let slice = &self.bytes[self.pos..self.pos + len as usize];
+        self.pos += len as usize;
+        Ok(slice)
+    }
+    fn uint(&mut self,len:u64)->Result<u64,MsgPackErr> {
+        Ok(self.take(len)?.iter().fold(0u64,|value,byte|value << 8 | *byte as u64))
+    }
+    // a signed integer of len bytes, sign extended
+    fn int(&mut self,len:u64)->Result<i64,MsgPackErr> {
+        let shift = 64 - 8 * len;
+        Ok(((self.uint(len)? << shift) as i64) >> shift)
+    }
+    fn float(&mut self,len:u64)->Result<Json,MsgPackErr> {
+        let bits = self.uint(len)?;
+        let value = if len == 4 {f32::from_bits(bits as u32) as f64} else {f64::from_bits(bits)};
+        if value == 0.0 && value.is_sign_negative() {
+            return Ok(Json::Number(String::from("-0")));
+        }
+        Ok(format_double(value).map(Json::Number).unwrap_or(Json::Null))
+    }
+    fn str(&mut self,len:u64,start:usize)->Result<Json,MsgPackErr> {
+        match std::str::from_utf8(self.take(len)?) {
+            Ok(str)=>Ok(Json::String(String::from(str))),
+            Err(_)=>Err(MsgPackErr::InvalidUtf8(start))
+        }
+    }
+    fn array(&mut self,len:u64)->Result<Json,MsgPackErr> {
+        let mut vec = vec![];
+        for _ in 0..len {
+            vec.push(self.value()?);
+        }
+        Ok(Json::Array(vec))
+    }
+    // keys that are not strings are written out as json
+    fn map(&mut self,len:u64)->Result<Json,MsgPackErr> {
+        let mut map = HashMap::new();
+        for _ in 0..len {
+            let key = match self.value()? {
+                Json::String(key)=>key,
+                key=>{
+                    let mut text = String::new();
+                    // writing to a String cannot fail
+                    let _ = key.write_json(&mut text);
+                    text
+                }
+            };
+            map.insert(key, self.value()?);
+        }
+        Ok(Json::Object(map))
+    }
+    fn ext(&mut self,len:u64,start:usize)->Result<Json,MsgPackErr> {
+        let kind = self.int(1)?;
+        let data = self.take(len)?;
+        match self.options.ext {
+            ExtPolicy::Error=>Err(MsgPackErr::UnsupportedExt(start)),
+            ExtPolicy::Null=>Ok(Json::Null),
+            ExtPolicy::Object=>{
+                let mut map = HashMap::new();
+                map.insert(String::from("type"), Json::from(kind));
+                map.insert(String::from("data"), Json::String(base64(data, BASE64_ALPHABET, true)));
+                Ok(Json::Object(map))
+            }
+        }
+    }
+    fn value(&mut self)->Result<Json,MsgPackErr> {
+        if self.depth == MAX_DEPTH {
+            return Err(MsgPackErr::TooDeep(self.pos));
+        }
+        self.depth += 1;
+        let result = self.nested();
+        self.depth -= 1;
+        result
+    }
+    fn nested(&mut self)->Result<Json,MsgPackErr> {
+        let start = self.pos;
+        let marker = self.take(1)?[0];
+        match marker {
+            0x00..=0x7f=>Ok(Json::from(marker)),
+            0x80..=0x8f=>self.map((marker & 0x0f) as u64),
+            0x90..=0x9f=>self.array((marker & 0x0f) as u64),
+            0xa0..=0xbf=>self.str((marker & 0x1f) as u64, start),
+            NIL=>Ok(Json::Null),
+            FALSE=>Ok(Json::Bool(false)),
+            TRUE=>Ok(Json::Bool(true)),
+            BIN8 | BIN16 | BIN32=>{
+                let len = self.uint(1 << (marker - BIN8))?;
+                Ok(Json::String(base64(self.take(len)?, BASE64_ALPHABET, true)))
+            },
+            EXT8 | EXT16 | EXT32=>{
+                let len = self.uint(1 << (marker - EXT8))?;
+                self.ext(len, start)
+            },
+            FLOAT32=>self.float(4),
+            FLOAT64=>self.float(8),
+            UINT8..=UINT64=>Ok(Json::Number(self.uint(1 << (marker - UINT8))?.to_string())),
+            INT8..=INT64=>Ok(Json::Number(self.int(1 << (marker - INT8))?.to_string())),
+            FIXEXT1..=FIXEXT16=>self.ext(1 << (marker - FIXEXT1), start),
+            STR8 | STR16 | STR32=>{
+                let len = self.uint(1 << (marker - STR8))?;
+                self.str(len, start)
+            },
+            ARRAY16 | ARRAY32=>{
+                let len = self.uint(2 << (marker - ARRAY16))?;
+                self.array(len)
+            },
+            MAP16 | MAP32=>{
+                let len = self.uint(2 << (marker - MAP16))?;
+                self.map(len)
+            },
+            0xe0..=0xff=>Ok(Json::from(marker as i8)),
+            // 0xc1 is never used
+            _=>Err(MsgPackErr::InvalidMarker(start))
+        }
+    }
+}
+
+impl Json {
+    pub fn to_msgpack(&self)->Result<Vec<u8>,MsgPackErr> {
+        let mut out = vec![];
+        write_msgpack(self, &mut out, &mut String::new())?;
+        Ok(out)
+    }
+    // bin values become base64 strings, NaN and the infinities null
+    pub fn from_msgpack(bytes:&[u8])->Result<Json,MsgPackErr> {
+        Json::from_msgpack_with(bytes, &MsgPackOptions::default())
+    }
+    pub fn from_msgpack_with(bytes:&[u8],options:&MsgPackOptions)->Result<Json,MsgPackErr> {
+        let mut reader = MsgPackReader{bytes,pos:0,depth:0,options};
+        let json = reader.value()?;
+        if reader.pos != bytes.len() {
+            return Err(MsgPackErr::TrailingBytes(reader.pos));
+        }
+        Ok(json)
+    }
+}
@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+
+// exact decimal view of a json number: value = digits * 10^exponent
+// digits carries no leading or trailing zeros and is empty for zero
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct Decimal {
+    pub negative:bool,
+    pub digits:String,
+    pub exponent:i64
+}
+
+fn take_digits(bytes:&[u8],mut i:usize)->usize {
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    i
+}
+
+impl Decimal {
+    // accepts the json number grammar: -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+    pub fn parse(text:&str)->Option<Decimal> {
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        let negative = bytes.first() == Some(&b'-');
+        if negative {
+            i += 1;
+        }
+        let int_start = i;
+        i = take_digits(bytes, i);
+        let int_part = &text[int_start..i];
+        if int_part.is_empty() || (int_part.len() > 1 && int_part.starts_with('0')) {
+            return None;
+        }
+        let mut frac_part = "";
+        if i < bytes.len() && bytes[i] == b'.' {
+            let frac_start = i + 1;
+            i = take_digits(bytes, frac_start);
+            frac_part = &text[frac_start..i];
+            if frac_part.is_empty() {
+                return None;
+            }
+        }
+        let mut exponent : i64 = 0;
+        if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+            i += 1;
+            let mut exp_negative = false;
+            if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+                exp_negative = bytes[i] == b'-';
+                i += 1;
+            }
+            let exp_start = i;
+            i = take_digits(bytes, exp_start);
+            let exp_text = text[exp_start..i].trim_start_matches('0');
+            if i == exp_start || exp_text.len() > 15 {
+                return None;
+            }
+            exponent = exp_text.parse().unwrap_or(0);
+            if exp_negative {
+                exponent = -exponent;
+            }
+        }
+        if i != bytes.len() {
+            return None;
+        }
+        let mut digits = format!("{}{}",int_part,frac_part);
+        exponent -= frac_part.len() as i64;
+        let trimmed = digits.trim_end_matches('0').len();
+        exponent += (digits.len() - trimmed) as i64;
+        digits.truncate(trimmed);
+        let leading = digits.len() - digits.trim_start_matches('0').len();
+        digits.drain(..leading);
+        if digits.is_empty() {
+            return Some(Decimal{negative:false,digits,exponent:0});
+        }
+        Some(Decimal{negative,digits,exponent})
+    }
+
+    pub fn is_zero(&self)->bool {
+        self.digits.is_empty()
+    }
+
+    // position of the decimal point relative to the first digit
+    fn point(&self)->i64 {
+        self.digits.len() as i64 + self.exponent
+    }
+
+    fn cmp_magnitude(&self,other:&Decimal)->Ordering {
+        match (self.is_zero(),other.is_zero()) {
+            (true,true)=>return Ordering::Equal,
+            (true,false)=>return Ordering::Less,
+            (false,true)=>return Ordering::Greater,
+            _=>{}
+        }
+        self.point().cmp(&other.point()).then_with(||{
+            let a = self.digits.as_bytes();
+            let b = other.digits.as_bytes();
+            for k in 0..a.len().max(b.len()) {
+                let x = a.get(k).copied().unwrap_or(b'0');
+                let y = b.get(k).copied().unwrap_or(b'0');
+                if x != y {
+                    return x.cmp(&y);
+                }
+            }
+            Ordering::Equal
+        })
+    }
+
+    // renders like ECMAScript's Number::toString, on the exact digits
+    pub fn to_es_string(&self)->String {
+        if self.is_zero() {
+            return String::from("0");
+        }
+        let mut text = String::new();
+        if self.negative {
+            text.push('-');
+        }
+        let n = self.digits.len() as i64;
+        let p = self.point();
+        if self.exponent >= 0 && p <= 21 {
+            text.push_str(&self.digits);
+            text.push_str(&"0".repeat(self.exponent as usize));
+        }
+        else if p > 0 && p <= 21 {
+            text.push_str(&self.digits[..p as usize]);
+            text.push('.');
+            text.push_str(&self.digits[p as usize..]);
+        }
+        else if p > -6 && p <= 0 {
+            text.push_str("0.");
+            text.push_str(&"0".repeat((-p) as usize));
+            text.push_str(&self.digits);
+        }
+        else {
+            text.push_str(&self.digits[..1]);
+            if n > 1 {
+                text.push('.');
+                text.push_str(&self.digits[1..]);
+            }
+            text.push('e');
+            text.push(if p - 1 < 0 {'-'} else {'+'});
+            text.push_str(&(p - 1).abs().to_string());
+        }
+        text
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self,other:&Decimal)->Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self,other:&Decimal)->Ordering {
+        match (self.negative,other.negative) {
+            (false,true)=>Ordering::Greater,
+            (true,false)=>Ordering::Less,
+            (false,false)=>self.cmp_magnitude(other),
+            (true,true)=>other.cmp_magnitude(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::number::Decimal;
+
+    #[test]
+    fn strips_trailing_and_leading_zeros(){
+        let d = Decimal::parse("120.500").unwrap();
+        assert_eq!(d.digits,"1205");
+        assert_eq!(d.exponent,-1);
+    }
+
+    #[test]
+    fn negative_zero_is_zero(){
+        assert_eq!(Decimal::parse("-0.0e5").unwrap(),Decimal::parse("0").unwrap());
+    }
+
+    #[test]
+    fn rejects_non_json_numbers(){
+        assert!(Decimal::parse("01").is_none());
+        assert!(Decimal::parse("1.").is_none());
+        assert!(Decimal::parse("+1").is_none());
+        assert!(Decimal::parse("1e").is_none());
+    }
+
+    #[test]
+    fn compares_by_value(){
+        let a = Decimal::parse("-2.5").unwrap();
+        let b = Decimal::parse("-25e-2").unwrap();
+        let c = Decimal::parse("0.3").unwrap();
+        assert!(a < b);
+        assert!(b < c);
+        assert_eq!(Decimal::parse("1.50").unwrap(),Decimal::parse("15E-1").unwrap());
+    }
+}
@@ -0,0 +1,81 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash,Hasher};
+
+use crate::Json;
+use crate::number::Decimal;
+
+fn rank(json:&Json)->u8 {
+    match json {
+        Json::Null=>0,
+        Json::Bool(_)=>1,
+        Json::Number(_)=>2,
+        Json::String(_)=>3,
+        Json::Array(_)=>4,
+        Json::Object(_)=>5
+    }
+}
+
+// numbers compare by value, and equal values by their text so that only
+// identical numbers compare equal, as with ==. text that is not a valid
+// json number sorts after every valid one
+fn cmp_numbers(a:&str,b:&str)->Ordering {
+    match (Decimal::parse(a),Decimal::parse(b)) {
+        (Some(x),Some(y))=>x.cmp(&y),
+        (Some(_),None)=>Ordering::Less,
+        (None,Some(_))=>Ordering::Greater,
+        (None,None)=>Ordering::Equal
+    }.then_with(||a.cmp(b))
+}
+
+// null < bool < number < string < array < object. arrays compare element
+// by element, objects as their members sorted by key
+impl Ord for Json {
+    fn cmp(&self,other:&Json)->Ordering {
+        match (self,other) {
+            (Json::Bool(a),Json::Bool(b))=>a.cmp(b),
+            (Json::Number(a),Json::Number(b))=>cmp_numbers(a, b),
+            (Json::String(a),Json::String(b))=>a.cmp(b),
+            (Json::Array(a),Json::Array(b))=>a.cmp(b),
+            (Json::Object(a),Json::Object(b))=>{
+                let mut a : Vec<(&String,&Json)> = a.iter().collect();
+                let mut b : Vec<(&String,&Json)> = b.iter().collect();
+                a.sort();
+                b.sort();
+                a.cmp(&b)
+            },
+            _=>rank(self).cmp(&rank(other))
+        }
+    }
+}
+
+impl PartialOrd for Json {
+    fn partial_cmp(&self,other:&Json)->Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// members are hashed on their own and summed, so the result does not
+// depend on the iteration order of the map
+impl Hash for Json {
+    fn hash<H:Hasher>(&self,state:&mut H) {
+        rank(self).hash(state);
+        match self {
+            Json::Null=>{},
+            Json::Bool(b)=>b.hash(state),
+            Json::Number(num)=>num.hash(state),
+            Json::String(str)=>str.hash(state),
+            Json::Array(vec)=>vec.hash(state),
+            Json::Object(map)=>{
+                let mut sum : u64 = 0;
+                for member in map.iter() {
+                    let mut hasher = DefaultHasher::new();
+                    member.hash(&mut hasher);
+                    sum = sum.wrapping_add(hasher.finish());
+                }
+                map.len().hash(state);
+                sum.hash(state);
+            }
+        }
+    }
+}
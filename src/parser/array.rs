@@ -1,6 +1,6 @@
-use std::{mem, usize, vec};
+use std::mem;
 
-use crate::parser::common::{Jerr,IteratorParser,StrIt};
+use crate::parser::common::{Jerr,IteratorParser,StrIt,resync,is_space};
 
 #[derive(Debug,PartialEq,Eq)]
 enum ArrayParserState {
@@ -33,15 +33,18 @@ impl JArrayParser {
             Err(Jerr::ExpectedValue(i))
         }
     }
+    fn finish(&mut self)->crate::Json{
+        let vec = mem::take(&mut self.vector);
+        self.reset();
+        crate::Json::Array(vec)
+    }
     fn push_bracket(&mut self,iter:&mut StrIt,i:usize)->Result<crate::Json,Jerr>{
         if self.state == ArrayParserState::Comma || self.state == ArrayParserState::Beginning {
             iter.next();
-            let vec = mem::replace(&mut self.vector, vec![]);
-            self.reset();
-            return Ok(crate::Json::Array(vec));
+            Ok(self.finish())
         }
         else{
-            return Err(Jerr::ExpectedValue(i));
+            Err(Jerr::ExpectedValue(i))
         }
     }
     fn push_value(&mut self,iter:&mut StrIt,iterparser:&impl IteratorParser,i:usize)->Result<(),Jerr>{
@@ -51,24 +54,41 @@ impl JArrayParser {
             Ok(())
         }
         else{
-            return Err(Jerr::ExpectedCommaOrEnd(i));
+            Err(Jerr::ExpectedCommaOrEnd(i))
         }
     }
     fn push_space(&mut self,iter:&mut StrIt){
         iter.next();
     }
     pub fn parse(&mut self,iter:&mut StrIt,iterparser:&impl IteratorParser)->Result<crate::Json,Jerr>{
-        iter.next().unwrap();
+        let start = iter.next().unwrap().0;
         loop {
             match iter.peek().cloned() {
                 None=>{
-                    return Err(Jerr::UnexpectedEnd);
+                    iterparser.recover(Jerr::UnexpectedEnd,start)?;
+                    return Ok(self.finish());
                 },
                 Some((i,c))=>match c {
-                    ',' => self.push_comma(iter,i)?,
-                    ']' => return self.push_bracket(iter,i),
-                    ' ' | '\n' | '\t'=>self.push_space(iter),
-                    _=> self.push_value(iter,iterparser, i)?
+                    ',' => if let Err(err) = self.push_comma(iter,i) {
+                        // a comma where a value belongs is passed over
+                        iterparser.recover(err,i)?;
+                        iter.next();
+                    },
+                    ']' => return self.push_bracket(iter,i).or_else(|err|{
+                        iterparser.recover(err,i)?;
+                        iter.next();
+                        Ok(self.finish())
+                    }),
+                    c if is_space(c)=>self.push_space(iter),
+                    _=> if let Err(err) = self.push_value(iter,iterparser, i) {
+                        iterparser.recover(err,i)?;
+                        resync(iter);
+                        self.state = ArrayParserState::Comma;
+                        // the closing bracket of an enclosing object
+                        if let Some((_,'}')) = iter.peek() {
+                            return Ok(self.finish());
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,333 @@
+use std::{borrow::Cow, iter::{Enumerate, Peekable}, str::CharIndices};
+
+use crate::JsonRef;
+use crate::parser::common::*;
+
+// yields (char position,(byte offset,char)): positions in errors count
+// chars like the other parsers, byte offsets are used to slice the input
+type SliceIt<'a> = Peekable<Enumerate<CharIndices<'a>>>;
+
+pub enum Token<'a> {
+    Null,
+    Bool(bool),
+    Number(&'a str),
+    String(Cow<'a,str>),
+    ArrayStart,
+    ObjectStart
+}
+
+// same states as JArrayParser
+pub enum ArrayState {
+    Beginning,
+    Comma,
+    Value
+}
+
+// reads values straight out of the input text, following the same
+// grammar and reporting the same errors as Json::parse
+pub struct SliceParser<'a> {
+    text:&'a str,
+    iter:SliceIt<'a>
+}
+
+impl<'a> SliceParser<'a> {
+    pub fn new(text:&'a str)->SliceParser<'a> {
+        SliceParser{
+            text,
+            iter:text.char_indices().enumerate().peekable()
+        }
+    }
+
+    // position and char of the next char
+    pub fn peek(&mut self)->Option<(usize,char)> {
+        self.iter.peek().map(|(i,(_,c))|(*i,*c))
+    }
+
+    pub fn advance(&mut self) {
+        self.iter.next();
+    }
+
+    pub fn skip_space(&mut self) {
+        while self.peek().is_some_and(|(_,c)|is_space(c)) {
+            self.advance();
+        }
+    }
+
+    fn offset(&mut self)->usize {
+        match self.iter.peek() {
+            Some((_,(offset,_)))=>*offset,
+            None=>self.text.len()
+        }
+    }
+
+    pub fn begins_with(&mut self,word:&str)->bool {
+        let offset = self.offset();
+        if self.text[offset..].starts_with(word) {
+            for _ in word.chars() {
+                self.advance();
+            }
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    // the caller has seen a digit or a minus
+    pub fn number(&mut self)->Result<&'a str,Jerr> {
+        let position = self.peek().map_or(0,|(i,_)|i);
+        let start = self.offset();
+        let num = &self.text[start..start + number_len(&self.text[start..])];
+        for _ in 0..num.len() {
+            self.advance();
+        }
+        if valid_number(num) {
+            Ok(num)
+        }
+        else {
+            Err(Jerr::InvalidToken(String::from(num),position))
+        }
+    }
+
+    // at is the position of the backslash before the u
+    fn unicode(&mut self,at:usize)->Result<u16,Jerr> {
+        let mut unicode = String::new();
+        while unicode.chars().count() < 4 {
+            match self.iter.next() {
+                Some((_,(_,c)))=>unicode.push(c),
+                None=>return Err(Jerr::UnexpectedEnd)
+            }
+        }
+        match u8arr_to_u16arr(convert_to_u8(&unicode, at)?).first() {
+            Some(unit)=>Ok(*unit),
+            None=>Err(Jerr::InvalidUnicodeSequence(unicode,at))
+        }
+    }
+
+    // a high surrogate must be followed by an escaped low surrogate
+    fn push_unicode(&mut self,buffer:&mut String,at:usize)->Result<(),Jerr> {
+        let unit = self.unicode(at)?;
+        let mut units = vec![unit];
+        let next = self.peek().map_or(0,|(i,_)|i);
+        if (0xD800..0xDC00).contains(&unit) && self.begins_with("\\u") {
+            units.push(self.unicode(next)?);
+        }
+        match String::from_utf16(&units) {
+            Ok(str)=>{
+                buffer.push_str(&str);
+                Ok(())
+            },
+            Err(_)=>Err(Jerr::InvalidUnicodeSequence(units.iter().map(|unit|format!("{:04x}",unit)).collect(),at))
+        }
+    }
+
+    // at is the position of the backslash
+    fn push_escape(&mut self,buffer:&mut String,at:usize)->Result<(),Jerr> {
+        match self.iter.next() {
+            None=>Err(Jerr::UnexpectedEnd),
+            Some((_,(_,c)))=>{
+                match c {
+                    '"' | '\\' | '/' => buffer.push(c),
+                    'r' => buffer.push('\r'),
+                    'b' => buffer.push('\x08'),
+                    't' => buffer.push('\t'),
+                    'n' => buffer.push('\n'),
+                    'f' => buffer.push('\x0C'),
+                    'u' => self.push_unicode(buffer, at)?,
+                    _ => return Err(Jerr::UnknownEscape(c,at))
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // the caller has seen the opening quote. the text is borrowed up to
+    // the first escape and copied from there on
+    pub fn string(&mut self)->Result<Cow<'a,str>,Jerr> {
+        self.advance();
+        let start = self.offset();
+        let mut buffer : Option<String> = None;
+        loop {
+            match self.iter.next() {
+                None=>return Err(Jerr::UnexpectedEnd),
+                Some((i,(offset,c)))=>match c {
+                    '"'=>return Ok(match buffer {
+                        Some(buffer)=>Cow::Owned(buffer),
+                        None=>Cow::Borrowed(&self.text[start..offset])
+                    }),
+                    '\\'=>{
+                        let text = self.text;
+                        let buffer = buffer.get_or_insert_with(||String::from(&text[start..offset]));
+                        self.push_escape(buffer, i)?;
+                    },
+                    _=>if let Some(buffer) = &mut buffer {
+                        buffer.push(c);
+                    }
+                }
+            }
+        }
+    }
+
+    // consumes a scalar, or just the bracket that opens a container
+    pub fn token(&mut self)->Result<Token<'a>,Jerr> {
+        if self.begins_with("true") {
+            return Ok(Token::Bool(true));
+        }
+        else if self.begins_with("false") {
+            return Ok(Token::Bool(false));
+        }
+        else if self.begins_with("null") {
+            return Ok(Token::Null);
+        }
+        match self.peek() {
+            None=>Err(Jerr::UnexpectedEnd),
+            Some((_,c)) if is_number_start(c)=>self.number().map(Token::Number),
+            Some((_,'"'))=>self.string().map(Token::String),
+            Some((_,'['))=>{
+                self.advance();
+                Ok(Token::ArrayStart)
+            },
+            Some((_,'{'))=>{
+                self.advance();
+                Ok(Token::ObjectStart)
+            },
+            Some((i,_))=>Err(Jerr::UnexpectedChar(i))
+        }
+    }
+
+    // moves past whitespace and commas to the next array element, giving
+    // false once the closing bracket is consumed. the state starts as
+    // Beginning and is tracked by the caller across elements
+    pub fn array_next(&mut self,state:&mut ArrayState)->Result<bool,Jerr> {
+        loop {
+            self.skip_space();
+            match self.peek() {
+                None=>return Err(Jerr::UnexpectedEnd),
+                Some((i,','))=>match state {
+                    ArrayState::Comma=>{
+                        self.advance();
+                        *state = ArrayState::Value;
+                    },
+                    _=>return Err(Jerr::ExpectedValue(i))
+                },
+                Some((i,']'))=>match state {
+                    ArrayState::Value=>return Err(Jerr::ExpectedValue(i)),
+                    _=>{
+                        self.advance();
+                        return Ok(false);
+                    }
+                },
+                Some((i,_))=>match state {
+                    ArrayState::Comma=>return Err(Jerr::ExpectedCommaOrEnd(i)),
+                    _=>{
+                        *state = ArrayState::Comma;
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+
+    // a closing brace after the opening one and any whitespace makes an
+    // empty object
+    pub fn object_empty(&mut self)->bool {
+        self.skip_space();
+        if let Some((_,'}')) = self.peek() {
+            self.advance();
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    // reads a key and its colon, giving the position of the value that
+    // follows, where any error in the value is reported
+    pub fn object_key(&mut self)->Result<(Cow<'a,str>,usize),Jerr> {
+        self.skip_space();
+        let key = match self.peek() {
+            None=>return Err(Jerr::UnexpectedEnd),
+            Some((i,'"'))=>self.string().map_err(|_|Jerr::ExpectedProperty(i))?,
+            Some((i,_))=>return Err(Jerr::ExpectedProperty(i))
+        };
+        self.skip_space();
+        match self.peek() {
+            None=>return Err(Jerr::UnexpectedEnd),
+            Some((_,':'))=>self.advance(),
+            Some((i,_))=>return Err(Jerr::ExpectedColon(i))
+        }
+        self.skip_space();
+        match self.peek() {
+            None=>Err(Jerr::UnexpectedEnd),
+            Some((i,_))=>Ok((key,i))
+        }
+    }
+
+    // gives false once the closing brace is consumed
+    pub fn object_next(&mut self)->Result<bool,Jerr> {
+        self.skip_space();
+        match self.peek() {
+            None=>Err(Jerr::UnexpectedEnd),
+            Some((_,','))=>{
+                self.advance();
+                Ok(true)
+            },
+            Some((_,'}'))=>{
+                self.advance();
+                Ok(false)
+            },
+            Some((i,_))=>Err(Jerr::ExpectedCommaOrEnd(i))
+        }
+    }
+
+    fn array(&mut self)->Result<JsonRef<'a>,Jerr> {
+        let mut vec = vec![];
+        let mut state = ArrayState::Beginning;
+        while self.array_next(&mut state)? {
+            vec.push(self.value()?);
+        }
+        Ok(JsonRef::Array(vec))
+    }
+
+    fn object(&mut self)->Result<JsonRef<'a>,Jerr> {
+        let mut members = vec![];
+        if self.object_empty() {
+            return Ok(JsonRef::Object(members));
+        }
+        loop {
+            let (key,i) = self.object_key()?;
+            let value = self.value().map_err(|_|Jerr::ExpectedValue(i))?;
+            members.push((key,value));
+            if !self.object_next()? {
+                return Ok(JsonRef::Object(members));
+            }
+        }
+    }
+
+    pub fn value(&mut self)->Result<JsonRef<'a>,Jerr> {
+        match self.token()? {
+            Token::Null=>Ok(JsonRef::Null),
+            Token::Bool(b)=>Ok(JsonRef::Bool(b)),
+            Token::Number(num)=>Ok(JsonRef::Number(num)),
+            Token::String(str)=>Ok(JsonRef::String(str)),
+            Token::ArrayStart=>self.array(),
+            Token::ObjectStart=>self.object()
+        }
+    }
+
+    pub fn end(&mut self)->Result<(),Jerr> {
+        match self.peek() {
+            None=>Ok(()),
+            Some((i,_))=>Err(Jerr::ExpectedEnd(i))
+        }
+    }
+}
+
+impl<'a> JsonRef<'a> {
+    pub fn parse(input:&'a str)->Result<JsonRef<'a>,Jerr> {
+        let mut parser = SliceParser::new(input.trim());
+        let json = parser.value()?;
+        parser.end()?;
+        Ok(json)
+    }
+}
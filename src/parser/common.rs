@@ -2,13 +2,15 @@ use std::{collections::HashMap, iter::{Enumerate, Peekable}};
 pub type StrIt<'a> = Peekable<Enumerate<std::str::Chars<'a>>>;
 pub type Jmap = HashMap<String,crate::Json>;
 
+// positions count chars in the trimmed input. escape errors point at the
+// backslash, and at the first one of a surrogate pair
 #[derive(Debug,PartialEq,Eq)]
 pub enum Jerr {
-    InvalidToken(String),
+    InvalidToken(String,usize),
     UnexpectedChar(usize),
     UnexpectedEnd,
-    InvalidUnicodeSequence(String),
-    UnknownEscape(char),
+    InvalidUnicodeSequence(String,usize),
+    UnknownEscape(char,usize),
     ExpectedCommaOrEnd(usize),
     ExpectedColon(usize),
     ExpectedValue(usize),
@@ -16,33 +18,122 @@ pub enum Jerr {
     ExpectedEnd(usize)
 }
 
+impl Jerr {
+    // char position in the trimmed input that Json::parse reads, for all
+    // errors but an unexpected end
+    pub fn position(&self)->Option<usize> {
+        match self {
+            Jerr::UnexpectedEnd=>None,
+            Jerr::InvalidToken(_,i) | Jerr::InvalidUnicodeSequence(_,i) | Jerr::UnknownEscape(_,i)
+            | Jerr::UnexpectedChar(i) | Jerr::ExpectedCommaOrEnd(i) | Jerr::ExpectedColon(i)
+            | Jerr::ExpectedValue(i) | Jerr::ExpectedProperty(i) | Jerr::ExpectedEnd(i)=>Some(*i)
+        }
+    }
+
+    // 1-based line and column in the input as given to Json::parse. an
+    // unexpected end points just past the last character
+    pub fn line_col(&self,input:&str)->(usize,usize) {
+        line_col_at(input, self.position().unwrap_or_else(||input.trim().chars().count()))
+    }
+}
+
+// line and column of a char position in the trimmed input
+fn line_col_at(input:&str,position:usize)->(usize,usize) {
+    let leading = input.chars().count() - input.trim_start().chars().count();
+    let mut line = 1;
+    let mut column = 1;
+    for c in input.chars().take(leading + position) {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        }
+        else {
+            column += 1;
+        }
+    }
+    (line,column)
+}
+
+// a syntax error found by Json::parse_recovering. the position is the
+// one the error carries, or the end of the input for an unexpected end
+#[derive(Debug,PartialEq,Eq)]
+pub struct Diagnostic {
+    pub error:Jerr,
+    pub position:usize
+}
+
+impl Diagnostic {
+    pub fn line_col(&self,input:&str)->(usize,usize) {
+        line_col_at(input, self.position)
+    }
+}
+
 pub fn is_digit(c:char)->bool{
-    c >= '0' && c <= '9'
+    c.is_ascii_digit()
+}
+
+// the four whitespace characters of json
+pub fn is_space(c:char)->bool{
+    matches!(c,' ' | '\t' | '\n' | '\r')
+}
+
+// a number starts with a digit or a minus
+pub fn is_number_start(c:char)->bool{
+    is_digit(c) || c == '-'
 }
 
-// all take non-empty strings except parse
 pub fn is_number(iter:&mut StrIt)->bool{
-    is_digit(iter.peek().unwrap().1)
+    iter.peek().is_some_and(|(_,c)|is_number_start(*c))
 }
 
 pub fn is_string(iter:&mut StrIt)->bool{
-    iter.peek().unwrap().1 == '"'
+    iter.peek().is_some_and(|(_,c)|*c == '"')
 }
 
 pub fn is_array(iter:&mut StrIt)->bool{
-    iter.peek().unwrap().1 == '['
+    iter.peek().is_some_and(|(_,c)|*c == '[')
 }
 
 pub fn is_object(iter:&mut StrIt)->bool{
-    iter.peek().unwrap().1 == '{'
+    iter.peek().is_some_and(|(_,c)|*c == '{')
 }
 
-pub fn starts_with(text:&str,c:char)->bool{
-    return text.chars().next().unwrap() == c; 
+// length of the number at the start of text: a minus, digits, a fraction
+// and an exponent, each part taken when present. whether they make a valid
+// number is left to valid_number, so that a bad one is reported whole
+pub fn number_len(text:&str)->usize{
+    let bytes = text.as_bytes();
+    let digits = |i:usize|i + bytes[i..].iter().take_while(|b|b.is_ascii_digit()).count();
+    let mut i = if bytes.first() == Some(&b'-') {1} else {0};
+    i = digits(i);
+    if bytes.get(i) == Some(&b'.') {
+        i = digits(i + 1);
+    }
+    if let Some(b'e') | Some(b'E') = bytes.get(i) {
+        i += 1;
+        if let Some(b'+') | Some(b'-') = bytes.get(i) {
+            i += 1;
+        }
+        i = digits(i);
+    }
+    i
 }
 
-pub fn ends_with(text:&str,c:char)->bool{
-    return text.chars().rev().next().unwrap() == c; 
+// -?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)? as given by number_len
+pub fn valid_number(num:&str)->bool{
+    let digits = |text:&str|!text.is_empty() && text.bytes().all(|b|b.is_ascii_digit());
+    let unsigned = num.strip_prefix('-').unwrap_or(num);
+    let (mantissa,exponent) = match unsigned.split_once(['e','E']) {
+        Some((mantissa,exponent))=>(mantissa,Some(exponent.trim_start_matches(['+','-']))),
+        None=>(unsigned,None)
+    };
+    let (int,fraction) = match mantissa.split_once('.') {
+        Some((int,fraction))=>(int,Some(fraction)),
+        None=>(mantissa,None)
+    };
+    digits(int) && (int == "0" || !int.starts_with('0'))
+        && fraction.is_none_or(digits)
+        && exponent.is_none_or(digits)
 }
 
 pub fn u8arr_to_u16arr(v:Vec<u8>)->Vec<u16>{
@@ -55,13 +146,14 @@ pub fn u8arr_to_u16arr(v:Vec<u8>)->Vec<u16>{
         hd += oc2 as u16;
         nv.push(hd)
     }
-    return nv;
+    nv
 }
 
-pub fn convert_to_u8(unicode:&String)->Result<Vec<u8>,Jerr>{
+// position is that of the escape holding the digits
+pub fn convert_to_u8(unicode:&str,position:usize)->Result<Vec<u8>,Jerr>{
     match hex::decode(unicode) {
         Ok(vec)=>Ok(vec),
-        Err(_)=>Err(Jerr::InvalidUnicodeSequence(unicode.clone()))
+        Err(_)=>Err(Jerr::InvalidUnicodeSequence(String::from(unicode),position))
     }
 }
 
@@ -84,10 +176,47 @@ pub fn begins_with_str(iter:&mut StrIt,text:&str)->bool {
 
 }
 
-pub fn make_iterator(text:&str)->StrIt{
+pub fn make_iterator(text:&str)->StrIt<'_>{
     text.chars().enumerate().peekable()
 }
 
+// skips what is left of a broken value, up to the next comma or closing
+// bracket outside of any nested array, object or string
+pub fn resync(iter:&mut StrIt) {
+    let mut depth = 0;
+    let mut string = false;
+    let mut escape = false;
+    while let Some((_,c)) = iter.peek().cloned() {
+        if string {
+            if escape {
+                escape = false;
+            }
+            else if c == '\\' {
+                escape = true;
+            }
+            else if c == '"' {
+                string = false;
+            }
+        }
+        else {
+            match c {
+                '"'=>string = true,
+                '[' | '{'=>depth += 1,
+                ',' | ']' | '}' if depth == 0=>return,
+                ']' | '}'=>depth -= 1,
+                _=>{}
+            }
+        }
+        iter.next();
+    }
+}
+
 pub trait IteratorParser {
     fn parse (&self,iter: &mut StrIt)->Result<crate::Json,Jerr>;
+    // called by arrays and objects with an error inside them and where the
+    // value being read began. Ok has them skip to the next comma or closing
+    // bracket and carry on with what they have
+    fn recover(&self,err:Jerr,_start:usize)->Result<(),Jerr> {
+        Err(err)
+    }
 }
\ No newline at end of file
@@ -3,48 +3,83 @@ mod object;
 mod string;
 mod array;
 mod number;
+mod borrowed;
+mod tape;
+
+use std::cell::RefCell;
 
 use common::*;
-pub use common::{Jerr,Jmap};
+pub use common::{Jerr,Jmap,Diagnostic};
 
 struct BasicIteratorParser;
 
+// keeps every error arrays and objects run into and lets them carry on
+struct RecoveringIteratorParser {
+    end:usize,
+    diagnostics:RefCell<Vec<Diagnostic>>
+}
+
+fn parse_value(iter:&mut StrIt,iterparser:&impl IteratorParser)->Result<crate::Json,Jerr> {
+    if begins_with_str(iter, "true"){
+        Ok(crate::Json::Bool(true))
+    }
+    else if begins_with_str(iter, "false"){
+        Ok(crate::Json::Bool(false))
+    }
+    else if begins_with_str(iter, "null"){
+        Ok(crate::Json::Null)
+    }
+    else if is_number(iter) {
+        number::JNumberParser::parse(iter)
+    }
+    else if is_string(iter) {
+        let mut parser = crate::parser::string::JStringParser::new();
+        parser.parse(iter)
+    }
+    else if is_array(iter) {
+        let mut parser = crate::parser::array::JArrayParser::new();
+        parser.parse(iter,iterparser)
+    }
+    else if is_object(iter) {
+        let mut parser = crate::parser::object::JObjectParser::new();
+        parser.parse(iter,iterparser)
+    }
+    else { // unknown token
+        match iter.peek() {
+            Some((i,_))=>Err(Jerr::UnexpectedChar(*i)),
+            None=>Err(Jerr::UnexpectedEnd)
+        }
+    }
+}
+
 impl IteratorParser for BasicIteratorParser {
     fn parse(&self,iter:&mut StrIt)->Result<crate::Json,Jerr> {
-        
-        if begins_with_str(iter, "true"){
-            return Ok(crate::Json::Bool(true));
-        }
-        else if begins_with_str(iter, "false"){
-            return Ok(crate::Json::Bool(false));
-        }
-        else if begins_with_str(iter, "null"){
-            return Ok(crate::Json::Null);
-        }
-        else if is_number(iter) {
-            number::JNumberParser::parse(iter)
-        }
-        else if is_string(iter) {
-            let mut parser = crate::parser::string::JStringParser::new();
-            parser.parse(iter)
-        }
-        else if is_array(iter) {
-            let mut parser = crate::parser::array::JArrayParser::new();
-            parser.parse(iter,self)
-        }
-        else if is_object(iter) {
-            let mut parser = crate::parser::object::JObjectParser::new();
-            parser.parse(iter,self)
-        }
-        else { // unknown token
-            Err(Jerr::UnexpectedChar(iter.peek().unwrap().0))
-        }
+        parse_value(iter, self)
+    }
+}
+
+impl IteratorParser for RecoveringIteratorParser {
+    fn parse(&self,iter:&mut StrIt)->Result<crate::Json,Jerr> {
+        parse_value(iter, self)
+    }
+    fn recover(&self,err:Jerr,_start:usize)->Result<(),Jerr> {
+        let mut diagnostics = self.diagnostics.borrow_mut();
+        // every unclosed array and object runs into the same end
+        if err == Jerr::UnexpectedEnd && diagnostics.iter().any(|diagnostic|diagnostic.error == Jerr::UnexpectedEnd) {
+            return Ok(());
+        }
+        let position = err.position().unwrap_or(self.end);
+        diagnostics.push(Diagnostic{error:err,position});
+        Ok(())
     }
 }
 
 impl crate::Json {
-    pub fn parse(input:&String)->Result<crate::Json,Jerr> {
-        let mut iter = make_iterator(input.as_str().trim());
+    pub fn parse(input:&str)->Result<crate::Json,Jerr> {
+        let mut iter = make_iterator(input.trim());
+        if iter.peek().is_none() {
+            return Err(Jerr::UnexpectedEnd);
+        }
         let basic_parser = BasicIteratorParser;
         let json = basic_parser.parse(&mut iter)?;
         match iter.peek()  {
@@ -52,6 +87,32 @@ impl crate::Json {
             Some((i,_))=>Err(Jerr::ExpectedEnd(*i))
         }
     }
+    // like parse, but carries on past syntax errors: arrays and objects
+    // skip to their next comma or closing bracket and keep what they could
+    // read. there are no diagnostics exactly when parse succeeds
+    pub fn parse_recovering(input:&str)->(crate::Json,Vec<Diagnostic>) {
+        let input = input.trim();
+        let mut iter = make_iterator(input);
+        let parser = RecoveringIteratorParser{end:input.chars().count(),diagnostics:RefCell::new(vec![])};
+        let json = match iter.peek() {
+            None=>Err(Jerr::UnexpectedEnd),
+            Some(_)=>parser.parse(&mut iter)
+        };
+        let json = match json {
+            Ok(json)=>{
+                if let Some((i,_)) = iter.peek() {
+                    parser.recover(Jerr::ExpectedEnd(*i), *i).ok();
+                }
+                json
+            },
+            // a broken value at the top has nothing to recover into
+            Err(err)=>{
+                parser.recover(err, 0).ok();
+                crate::Json::Null
+            }
+        };
+        (json,parser.diagnostics.into_inner())
+    }
     pub fn as_number(&self)-> Option<&String> {
         match self {
             crate::Json::Number(num)=>Some(num),
@@ -59,7 +59,7 @@ impl JObjectParser {
     fn push_value(&mut self,iter:&mut StrIt,i:usize,iterparser:&impl IteratorParser)->Result<(),Jerr>{
         match iterparser.parse(iter) {
             Ok(json)=>{
-                self.map.insert(mem::replace(&mut self.identifier, String::new()),json);
+                self.map.insert(mem::take(&mut self.identifier),json);
                 self.state = ObjectParserState::Comma;
                 Ok(())
             }
@@ -79,7 +79,7 @@ impl JObjectParser {
         }
     }
     fn push(&mut self,iter:&mut StrIt,c:char,i:usize,iterparser:&impl IteratorParser)->Result<bool,Jerr>{
-        if c == ' ' || c == '\t' || c == '\n' {
+        if is_space(c) {
             iter.next();
         }
         else {
@@ -97,6 +97,9 @@ impl JObjectParser {
         Ok(false)
     }
     fn init_check(&self,iter:&mut StrIt)->bool{
+        while iter.peek().is_some_and(|(_,c)|is_space(*c)) {
+            iter.next();
+        }
         if let Some((_,c)) = iter.peek(){
             if *c == '}' {
                 iter.next();
@@ -105,23 +108,53 @@ impl JObjectParser {
         }
         false
     }
+    fn finish(&mut self)->crate::Json{
+        let map = mem::take(&mut self.map);
+        self.reset();
+        crate::Json::Object(map)
+    }
+    // after a recovered error the member being read is dropped
+    fn resync(&mut self,iter:&mut StrIt)->Option<crate::Json>{
+        resync(iter);
+        self.identifier.clear();
+        self.state = ObjectParserState::Label;
+        match iter.peek() {
+            None=>None,
+            Some((_,','))=>{
+                iter.next();
+                None
+            },
+            Some((_,'}'))=>{
+                iter.next();
+                Some(self.finish())
+            },
+            // the closing bracket of an enclosing array
+            Some(_)=>Some(self.finish())
+        }
+    }
     pub fn parse(&mut self,iter:&mut StrIt,iterparser:&impl IteratorParser)->Result<crate::Json,Jerr>{
-        iter.next().unwrap();
+        let start = iter.next().unwrap().0;
         if self.init_check(iter) {
             return Ok(crate::Json::Object(HashMap::new()));
         }
         loop {
             match iter.peek() {
                 None=>{
-                    return Err(Jerr::UnexpectedEnd);
+                    iterparser.recover(Jerr::UnexpectedEnd,start)?;
+                    return Ok(self.finish());
                 },
                 Some((i,c))=> {
                     let c = *c;
                     let i = *i;
-                    if self.push(iter,c,i,iterparser)? {
-                        let map = mem::replace(&mut self.map, HashMap::new());
-                        self.reset();
-                        return Ok(crate::Json::Object(map));
+                    match self.push(iter,c,i,iterparser) {
+                        Ok(true)=>return Ok(self.finish()),
+                        Ok(false)=>{},
+                        Err(err)=>{
+                            iterparser.recover(err,i)?;
+                            if let Some(json) = self.resync(iter) {
+                                return Ok(json);
+                            }
+                        }
                     }
                 }
             }
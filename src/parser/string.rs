@@ -6,7 +6,12 @@ pub struct JStringParser {
     buffer : String,
     escape : bool,
     unicode : String,
-    is_unicode : bool
+    is_unicode : bool,
+    // position of the backslash starting the current escape
+    escape_at : usize,
+    // a high surrogate waiting for the low surrogate escaped after it,
+    // with the position of its escape
+    high : Option<(u16,usize)>
 }
 
 impl JStringParser {
@@ -17,7 +22,9 @@ impl JStringParser {
             buffer : String::new(),
             escape : false,
             unicode : String::new(),
-            is_unicode : false
+            is_unicode : false,
+            escape_at : 0,
+            high : None
         }
     }
 
@@ -25,9 +32,10 @@ impl JStringParser {
         *self = JStringParser::new();
     }
 
-    fn push_char_non_escape(&mut self,c:char) {
+    fn push_char_non_escape(&mut self,i:usize,c:char) {
         if c == '\\' {
             self.escape = true;
+            self.escape_at = i;
         }
         else if c == '"' {
             self.has_ended = true;
@@ -40,31 +48,52 @@ impl JStringParser {
 
     fn push_char_unicode(&mut self,c:char)->Result<(),Jerr>{
         self.unicode.push(c);
-        if self.unicode.len() == 4 {
-            let bytes = u8arr_to_u16arr(convert_to_u8(&self.unicode)?);
-            self.buffer.push_str(String::from_utf16(bytes.as_ref()).unwrap().as_str());
+        if self.unicode.chars().count() == 4 {
+            let unit = match u8arr_to_u16arr(convert_to_u8(&self.unicode, self.escape_at)?).first() {
+                Some(unit)=>*unit,
+                None=>return Err(Jerr::InvalidUnicodeSequence(self.unicode.clone(),self.escape_at))
+            };
             self.is_unicode = false;
             self.unicode.clear();
+            let (units,at) = match self.high.take() {
+                Some((high,at))=>(vec![high,unit],at),
+                None if (0xD800..0xDC00).contains(&unit)=>{
+                    self.high = Some((unit,self.escape_at));
+                    return Ok(());
+                },
+                None=>(vec![unit],self.escape_at)
+            };
+            match String::from_utf16(&units) {
+                Ok(str)=>self.buffer.push_str(&str),
+                Err(_)=>return Err(Jerr::InvalidUnicodeSequence(units.iter().map(|unit|format!("{:04x}",unit)).collect(),at))
+            }
         }
         Ok(())
     }
 
     fn push_char_escape(&mut self,c:char)->Result<(),Jerr> {
         match c {
-            '"' | '\\' => self.buffer.push(c),
+            '"' | '\\' | '/' => self.buffer.push(c),
             'r' => self.buffer.push('\r'),
             'b' => self.buffer.push('\x08'),
             't' => self.buffer.push('\t'),
             'n' => self.buffer.push('\n'),
             'f' => self.buffer.push('\x0C'),
             'u' => self.is_unicode = true,
-            _ => return Err(Jerr::UnknownEscape(c))
+            _ => return Err(Jerr::UnknownEscape(c,self.escape_at))
         }
         self.escape = false;
         Ok(())
     }
 
-    fn push_char(&mut self,c:char)->Result<(),Jerr> {
+    fn push_char(&mut self,i:usize,c:char)->Result<(),Jerr> {
+        if let Some((high,at)) = self.high {
+            let continues = self.is_unicode || if self.escape {c == 'u'} else {c == '\\'};
+            if !continues {
+                self.has_ended = !self.escape && c == '"';
+                return Err(Jerr::InvalidUnicodeSequence(format!("{:04x}",high),at));
+            }
+        }
         if self.is_unicode {
             self.push_char_unicode(c)?;
         }
@@ -72,14 +101,14 @@ impl JStringParser {
             self.push_char_escape(c)?;
         }
         else{
-            self.push_char_non_escape(c);
+            self.push_char_non_escape(i,c);
         }
         Ok(())
     }
 
     fn finalize(&mut self)->Result<crate::Json,Jerr>{
         if self.has_ended {
-            let buff = mem::replace(&mut self.buffer, String::new());
+            let buff = mem::take(&mut self.buffer);
             self.reset();
             Ok(crate::Json::String(buff))
         }
@@ -89,13 +118,33 @@ impl JStringParser {
         }
     }
 
+    // passes the rest of a broken string, so a recovering parser does not
+    // take its closing quote for the start of another
+    fn skip_rest(&mut self,iter:&mut StrIt){
+        loop {
+            match iter.next() {
+                None | Some((_,'"'))=>break,
+                Some((_,'\\'))=>{
+                    iter.next();
+                },
+                _=>{}
+            }
+        }
+    }
+
     pub fn parse(&mut self,iter:&mut StrIt)->Result<crate::Json,Jerr> {
         iter.next();
         loop {
             if !self.has_ended {
                 match iter.next() {
                     None=>break,
-                    Some((_,c))=> self.push_char(c)?
+                    Some((i,c))=> if let Err(err) = self.push_char(i,c) {
+                        if !self.has_ended {
+                            self.skip_rest(iter);
+                        }
+                        self.reset();
+                        return Err(err);
+                    }
                 }
             }
             else{
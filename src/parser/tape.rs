@@ -0,0 +1,71 @@
+use crate::parser::borrowed::{SliceParser,Token,ArrayState};
+use crate::parser::common::Jerr;
+use crate::tape::{Tape,Node};
+
+fn push_text(tape:&mut Tape,text:&str)->(usize,usize) {
+    let start = tape.text.len();
+    tape.text.push_str(text);
+    (start,tape.text.len())
+}
+
+// the container node is pushed first and patched once its end is known
+fn push_value(tape:&mut Tape,parser:&mut SliceParser)->Result<(),Jerr> {
+    match parser.token()? {
+        Token::Null=>tape.nodes.push(Node::Null),
+        Token::Bool(b)=>tape.nodes.push(Node::Bool(b)),
+        Token::Number(num)=>{
+            let (start,end) = push_text(tape, num);
+            tape.nodes.push(Node::Number(start,end));
+        },
+        Token::String(str)=>{
+            let (start,end) = push_text(tape, &str);
+            tape.nodes.push(Node::String(start,end));
+        },
+        Token::ArrayStart=>{
+            let at = tape.nodes.len();
+            tape.nodes.push(Node::Array{len:0,end:0});
+            let mut len = 0;
+            let mut state = ArrayState::Beginning;
+            while parser.array_next(&mut state)? {
+                push_value(tape, parser)?;
+                len += 1;
+            }
+            tape.nodes[at] = Node::Array{len,end:tape.nodes.len()};
+        },
+        Token::ObjectStart=>{
+            let at = tape.nodes.len();
+            tape.nodes.push(Node::Object{len:0,end:0});
+            let mut len = 0;
+            if !parser.object_empty() {
+                loop {
+                    let (key,i) = parser.object_key()?;
+                    let (start,end) = push_text(tape, &key);
+                    tape.nodes.push(Node::String(start,end));
+                    push_value(tape, parser).map_err(|_|Jerr::ExpectedValue(i))?;
+                    len += 1;
+                    if !parser.object_next()? {
+                        break;
+                    }
+                }
+            }
+            tape.nodes[at] = Node::Object{len,end:tape.nodes.len()};
+        }
+    }
+    Ok(())
+}
+
+impl Tape {
+    // accepts the same documents and reports the same errors as Json::parse
+    pub fn parse(input:&str)->Result<Tape,Jerr> {
+        let mut tape = Tape{
+            nodes:vec![],
+            text:String::new()
+        };
+        let mut parser = SliceParser::new(input.trim());
+        push_value(&mut tape, &mut parser)?;
+        parser.end()?;
+        tape.nodes.shrink_to_fit();
+        tape.text.shrink_to_fit();
+        Ok(tape)
+    }
+}
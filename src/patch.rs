@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::pointer::{PointerErr,escape_token};
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum PatchOp {
+    Add{path:String,value:Json},
+    Remove{path:String},
+    Replace{path:String,value:Json},
+    Move{from:String,path:String},
+    Copy{from:String,path:String},
+    Test{path:String,value:Json}
+}
+
+// a json patch document (RFC 6902)
+#[derive(Debug,PartialEq,Eq,Clone,Default)]
+pub struct Patch(pub Vec<PatchOp>);
+
+// errors carry the index of the operation that failed
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum PatchErr {
+    ExpectedArray,
+    InvalidOperation(usize),
+    UnknownOperation(usize,String),
+    PointerFailed(usize,PointerErr),
+    TestFailed(usize),
+    MoveIntoChild(usize)
+}
+
+fn member(map:&HashMap<String,Json>,name:&str,index:usize)->Result<Json,PatchErr> {
+    map.get(name).cloned().ok_or(PatchErr::InvalidOperation(index))
+}
+
+fn string_member(map:&HashMap<String,Json>,name:&str,index:usize)->Result<String,PatchErr> {
+    match map.get(name) {
+        Some(Json::String(str))=>Ok(str.clone()),
+        _=>Err(PatchErr::InvalidOperation(index))
+    }
+}
+
+impl PatchOp {
+    fn from_json(json:&Json,index:usize)->Result<PatchOp,PatchErr> {
+        let map = json.as_object().ok_or(PatchErr::InvalidOperation(index))?;
+        let op = string_member(map, "op", index)?;
+        let path = string_member(map, "path", index)?;
+        match op.as_str() {
+            "add"=>Ok(PatchOp::Add{path,value:member(map, "value", index)?}),
+            "remove"=>Ok(PatchOp::Remove{path}),
+            "replace"=>Ok(PatchOp::Replace{path,value:member(map, "value", index)?}),
+            "move"=>Ok(PatchOp::Move{from:string_member(map, "from", index)?,path}),
+            "copy"=>Ok(PatchOp::Copy{from:string_member(map, "from", index)?,path}),
+            "test"=>Ok(PatchOp::Test{path,value:member(map, "value", index)?}),
+            _=>Err(PatchErr::UnknownOperation(index,op))
+        }
+    }
+
+    pub fn to_json(&self)->Json {
+        let mut map = HashMap::new();
+        let (op,path) = match self {
+            PatchOp::Add{path,value}=>{
+                map.insert(String::from("value"), value.clone());
+                ("add",path)
+            },
+            PatchOp::Remove{path}=>("remove",path),
+            PatchOp::Replace{path,value}=>{
+                map.insert(String::from("value"), value.clone());
+                ("replace",path)
+            },
+            PatchOp::Move{from,path}=>{
+                map.insert(String::from("from"), Json::String(from.clone()));
+                ("move",path)
+            },
+            PatchOp::Copy{from,path}=>{
+                map.insert(String::from("from"), Json::String(from.clone()));
+                ("copy",path)
+            },
+            PatchOp::Test{path,value}=>{
+                map.insert(String::from("value"), value.clone());
+                ("test",path)
+            }
+        };
+        map.insert(String::from("op"), Json::String(String::from(op)));
+        map.insert(String::from("path"), Json::String(path.clone()));
+        Json::Object(map)
+    }
+
+    fn apply(&self,json:&mut Json,index:usize)->Result<(),PatchErr> {
+        let failed = |err|PatchErr::PointerFailed(index,err);
+        match self {
+            PatchOp::Add{path,value}=>{
+                json.insert_at_pointer(path, value.clone()).map_err(failed)?;
+            },
+            PatchOp::Remove{path}=>{
+                json.remove_at_pointer(path).map_err(failed)?;
+            },
+            PatchOp::Replace{path,value}=>{
+                *json.pointer_mut(path).map_err(failed)? = value.clone();
+            },
+            PatchOp::Move{from,path}=>{
+                if path.starts_with(&format!("{}/",from)) {
+                    return Err(PatchErr::MoveIntoChild(index));
+                }
+                let value = json.remove_at_pointer(from).map_err(failed)?;
+                json.insert_at_pointer(path, value).map_err(failed)?;
+            },
+            PatchOp::Copy{from,path}=>{
+                let value = json.pointer(from).map_err(failed)?.clone();
+                json.insert_at_pointer(path, value).map_err(failed)?;
+            },
+            PatchOp::Test{path,value}=>{
+                if !json.pointer(path).map_err(failed)?.semantic_eq(value) {
+                    return Err(PatchErr::TestFailed(index));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Patch {
+    pub fn from_json(json:&Json)->Result<Patch,PatchErr> {
+        let ops = json.as_array().ok_or(PatchErr::ExpectedArray)?;
+        let mut patch = vec![];
+        for (index,op) in ops.iter().enumerate() {
+            patch.push(PatchOp::from_json(op, index)?);
+        }
+        Ok(Patch(patch))
+    }
+    pub fn to_json(&self)->Json {
+        Json::Array(self.0.iter().map(|op|op.to_json()).collect())
+    }
+}
+
+enum Edit {
+    Keep,
+    Change,
+    Insert,
+    Delete
+}
+
+// levenshtein alignment of two arrays, in order from the first element
+fn align(a:&[Json],b:&[Json])->Vec<Edit> {
+    let (n,m) = (a.len(),b.len());
+    let mut cost = vec![vec![0usize;m + 1];n + 1];
+    for (i,row) in cost.iter_mut().enumerate() {
+        row[m] = n - i;
+    }
+    for (j,cell) in cost[n].iter_mut().enumerate() {
+        *cell = m - j;
+    }
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            cost[i][j] = if a[i] == b[j] {
+                cost[i + 1][j + 1]
+            } else {
+                1 + cost[i + 1][j + 1].min(cost[i + 1][j]).min(cost[i][j + 1])
+            };
+        }
+    }
+    let (mut i,mut j) = (0,0);
+    let mut edits = vec![];
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        }
+        else if i < n && j < m && cost[i][j] == 1 + cost[i + 1][j + 1] {
+            edits.push(Edit::Change);
+            i += 1;
+            j += 1;
+        }
+        else if i < n && cost[i][j] == 1 + cost[i + 1][j] {
+            edits.push(Edit::Delete);
+            i += 1;
+        }
+        else {
+            edits.push(Edit::Insert);
+            j += 1;
+        }
+    }
+    edits
+}
+
+fn diff_arrays(a:&[Json],b:&[Json],path:&str,ops:&mut Vec<PatchOp>) {
+    let (mut i,mut j) = (0,0);
+    // index is the position in the array as it is being patched
+    let mut index = 0;
+    for edit in align(a, b) {
+        let at = format!("{}/{}",path,index);
+        match edit {
+            Edit::Keep=>{
+                i += 1;
+                j += 1;
+                index += 1;
+            },
+            Edit::Change=>{
+                diff_into(&a[i], &b[j], &at, ops);
+                i += 1;
+                j += 1;
+                index += 1;
+            },
+            Edit::Delete=>{
+                ops.push(PatchOp::Remove{path:at});
+                i += 1;
+            },
+            Edit::Insert=>{
+                ops.push(PatchOp::Add{path:at,value:b[j].clone()});
+                j += 1;
+                index += 1;
+            }
+        }
+    }
+}
+
+fn diff_objects(a:&HashMap<String,Json>,b:&HashMap<String,Json>,path:&str,ops:&mut Vec<PatchOp>) {
+    let mut keys : Vec<&String> = a.keys().chain(b.keys().filter(|key|!a.contains_key(*key))).collect();
+    keys.sort();
+    for key in keys {
+        let at = format!("{}/{}",path,escape_token(key));
+        match (a.get(key),b.get(key)) {
+            (Some(_),None)=>ops.push(PatchOp::Remove{path:at}),
+            (None,Some(value))=>ops.push(PatchOp::Add{path:at,value:value.clone()}),
+            (Some(x),Some(y))=>diff_into(x, y, &at, ops),
+            (None,None)=>{}
+        }
+    }
+}
+
+fn diff_into(a:&Json,b:&Json,path:&str,ops:&mut Vec<PatchOp>) {
+    if a == b {
+        return;
+    }
+    match (a,b) {
+        (Json::Object(x),Json::Object(y))=>diff_objects(x, y, path, ops),
+        (Json::Array(x),Json::Array(y))=>diff_arrays(x, y, path, ops),
+        _=>ops.push(PatchOp::Replace{path:String::from(path),value:b.clone()})
+    }
+}
+
+// builds a patch turning a into b, touching only the members and
+// elements that differ; array edits follow a shortest edit script
+pub fn diff(a:&Json,b:&Json)->Patch {
+    let mut ops = vec![];
+    diff_into(a, b, "", &mut ops);
+    Patch(ops)
+}
+
+impl Json {
+    // the patch is applied to a copy, so on failure the value is left unchanged
+    pub fn apply_patch(&mut self,patch:&Patch)->Result<(),PatchErr> {
+        let mut patched = self.clone();
+        for (index,op) in patch.0.iter().enumerate() {
+            op.apply(&mut patched, index)?;
+        }
+        *self = patched;
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use std::borrow::Cow;
+
+use crate::Json;
+use crate::number::Decimal;
+use crate::path::*;
+
+fn child_name(path:&str,name:&str)->String {
+    let mut path = String::from(path);
+    path.push_str("['");
+    for c in name.chars() {
+        match c {
+            '\'' => path.push_str("\\'"),
+            '\\' => path.push_str("\\\\"),
+            '\x08' => path.push_str("\\b"),
+            '\x0C' => path.push_str("\\f"),
+            '\n' => path.push_str("\\n"),
+            '\r' => path.push_str("\\r"),
+            '\t' => path.push_str("\\t"),
+            c if (c as u32) < 0x20 => path.push_str(&format!("\\u{:04x}",c as u32)),
+            c => path.push(c)
+        }
+    }
+    path.push_str("']");
+    path
+}
+
+fn child_index(path:&str,index:usize)->String {
+    format!("{}[{}]",path,index)
+}
+
+// object members are visited in key order so that results are stable
+fn children<'a>(node:&PathMatch<'a>)->Vec<PathMatch<'a>> {
+    match node.value {
+        Json::Array(vec)=>vec.iter().enumerate().map(|(i,value)|PathMatch{
+            path:child_index(&node.path, i),
+            value
+        }).collect(),
+        Json::Object(map)=>{
+            let mut keys : Vec<&String> = map.keys().collect();
+            keys.sort();
+            keys.into_iter().map(|key|PathMatch{
+                path:child_name(&node.path, key),
+                value:&map[key]
+            }).collect()
+        },
+        _=>vec![]
+    }
+}
+
+fn descendants<'a>(node:PathMatch<'a>,out:&mut Vec<PathMatch<'a>>) {
+    let kids = children(&node);
+    out.push(node);
+    for kid in kids {
+        descendants(kid, out);
+    }
+}
+
+fn normalize_index(index:i64,len:i64)->i64 {
+    if index >= 0 { index } else { len + index }
+}
+
+fn slice_indices(start:Option<i64>,end:Option<i64>,step:Option<i64>,len:usize)->Vec<usize> {
+    let len = len as i64;
+    let step = step.unwrap_or(1);
+    let mut indices = vec![];
+    if step > 0 {
+        let lower = normalize_index(start.unwrap_or(0), len).clamp(0, len);
+        let upper = normalize_index(end.unwrap_or(len), len).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    else if step < 0 {
+        let upper = normalize_index(start.unwrap_or(len - 1), len).clamp(-1, len - 1);
+        let lower = match end {
+            Some(end)=>normalize_index(end, len).clamp(-1, len - 1),
+            None=>-1
+        };
+        let mut i = upper;
+        while lower < i {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    indices
+}
+
+fn apply_selector<'a>(selector:&Selector,node:&PathMatch<'a>,root:&'a Json,out:&mut Vec<PathMatch<'a>>) {
+    match selector {
+        Selector::Name(name)=>{
+            if let Json::Object(map) = node.value {
+                if let Some(value) = map.get(name) {
+                    out.push(PathMatch{path:child_name(&node.path, name),value});
+                }
+            }
+        },
+        Selector::Wildcard=>out.extend(children(node)),
+        Selector::Index(index)=>{
+            if let Json::Array(vec) = node.value {
+                let i = normalize_index(*index, vec.len() as i64);
+                if i >= 0 && (i as usize) < vec.len() {
+                    out.push(PathMatch{path:child_index(&node.path, i as usize),value:&vec[i as usize]});
+                }
+            }
+        },
+        Selector::Slice(start,end,step)=>{
+            if let Json::Array(vec) = node.value {
+                for i in slice_indices(*start, *end, *step, vec.len()) {
+                    out.push(PathMatch{path:child_index(&node.path, i),value:&vec[i]});
+                }
+            }
+        },
+        Selector::Filter(expr)=>{
+            for kid in children(node) {
+                if test(expr, kid.value, root) {
+                    out.push(kid);
+                }
+            }
+        }
+    }
+}
+
+fn apply_segment<'a>(segment:&Segment,nodes:Vec<PathMatch<'a>>,root:&'a Json)->Vec<PathMatch<'a>> {
+    let mut out = vec![];
+    for node in nodes {
+        match segment {
+            Segment::Child(selectors)=>{
+                for selector in selectors {
+                    apply_selector(selector, &node, root, &mut out);
+                }
+            },
+            Segment::Descendant(selectors)=>{
+                let mut all = vec![];
+                descendants(node, &mut all);
+                for descendant in all.iter() {
+                    for selector in selectors {
+                        apply_selector(selector, descendant, root, &mut out);
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+pub fn select<'a>(segments:&[Segment],root:&'a Json)->Vec<PathMatch<'a>> {
+    let start = PathMatch{path:String::from("$"),value:root};
+    run(segments, start, root)
+}
+
+fn run<'a>(segments:&[Segment],start:PathMatch<'a>,root:&'a Json)->Vec<PathMatch<'a>> {
+    let mut nodes = vec![start];
+    for segment in segments {
+        nodes = apply_segment(segment, nodes, root);
+    }
+    nodes
+}
+
+fn run_query<'a>(query:&Query,current:&'a Json,root:&'a Json)->Vec<PathMatch<'a>> {
+    let start = if query.relative {
+        PathMatch{path:String::from("@"),value:current}
+    } else {
+        PathMatch{path:String::from("$"),value:root}
+    };
+    run(&query.segments, start, root)
+}
+
+fn test(expr:&Expr,current:&Json,root:&Json)->bool {
+    match expr {
+        Expr::Or(operands)=>operands.iter().any(|e|test(e, current, root)),
+        Expr::And(operands)=>operands.iter().all(|e|test(e, current, root)),
+        Expr::Not(operand)=>!test(operand, current, root),
+        Expr::Exists(query)=>!run_query(query, current, root).is_empty(),
+        Expr::Compare(left,op,right)=>{
+            let left = value_of(left, current, root);
+            let right = value_of(right, current, root);
+            compare(left.as_deref(), *op, right.as_deref())
+        }
+    }
+}
+
+fn count(n:usize)->Json {
+    Json::Number(n.to_string())
+}
+
+fn value_of<'a>(comparable:&'a Comparable,current:&'a Json,root:&'a Json)->Option<Cow<'a,Json>> {
+    match comparable {
+        Comparable::Literal(json)=>Some(Cow::Borrowed(json)),
+        Comparable::Query(query)=>run_query(query, current, root).pop().map(|m|Cow::Borrowed(m.value)),
+        Comparable::Function(Function::Length(argument))=>{
+            match value_of(argument, current, root)?.as_ref() {
+                Json::String(str)=>Some(Cow::Owned(count(str.chars().count()))),
+                Json::Array(vec)=>Some(Cow::Owned(count(vec.len()))),
+                Json::Object(map)=>Some(Cow::Owned(count(map.len()))),
+                _=>None
+            }
+        },
+        Comparable::Function(Function::Count(query))=>{
+            Some(Cow::Owned(count(run_query(query, current, root).len())))
+        },
+        Comparable::Function(Function::Value(query))=>{
+            let mut nodes = run_query(query, current, root);
+            if nodes.len() == 1 {
+                nodes.pop().map(|m|Cow::Borrowed(m.value))
+            }
+            else {
+                None
+            }
+        }
+    }
+}
+
+fn less(left:&Json,right:&Json)->bool {
+    match (left,right) {
+        (Json::Number(a),Json::Number(b))=>match (Decimal::parse(a),Decimal::parse(b)) {
+            (Some(x),Some(y))=>x < y,
+            _=>false
+        },
+        (Json::String(a),Json::String(b))=>a < b,
+        _=>false
+    }
+}
+
+fn equal(left:Option<&Json>,right:Option<&Json>)->bool {
+    match (left,right) {
+        (None,None)=>true,
+        (Some(a),Some(b))=>a.semantic_eq(b),
+        _=>false
+    }
+}
+
+fn compare(left:Option<&Json>,op:CompareOp,right:Option<&Json>)->bool {
+    let lt = |a:Option<&Json>,b:Option<&Json>|match (a,b) {
+        (Some(x),Some(y))=>less(x, y),
+        _=>false
+    };
+    match op {
+        CompareOp::Eq=>equal(left, right),
+        CompareOp::Ne=>!equal(left, right),
+        CompareOp::Lt=>lt(left, right),
+        CompareOp::Gt=>lt(right, left),
+        CompareOp::Le=>lt(left, right) || equal(left, right),
+        CompareOp::Ge=>lt(right, left) || equal(left, right)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::path::eval::slice_indices;
+
+    #[test]
+    fn slices_forward_and_backward(){
+        assert_eq!(slice_indices(Some(1), Some(3), None, 5),vec![1,2]);
+        assert_eq!(slice_indices(None, None, Some(2), 5),vec![0,2,4]);
+        assert_eq!(slice_indices(None, None, Some(-1), 3),vec![2,1,0]);
+        assert_eq!(slice_indices(Some(-1), Some(-3), Some(-1), 5),vec![4,3]);
+        assert_eq!(slice_indices(None, None, Some(0), 5),Vec::<usize>::new());
+    }
+}
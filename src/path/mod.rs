@@ -0,0 +1,109 @@
+mod parser;
+mod eval;
+
+use crate::Json;
+use crate::lexer::LexErr;
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum PathErr {
+    UnexpectedChar(usize),
+    UnexpectedEnd,
+    InvalidNumber(usize),
+    InvalidEscape(usize),
+    NonSingularQuery(usize),
+    UnknownFunction(String),
+    InvalidFunctionArgument(usize),
+    ExpectedComparison(usize)
+}
+
+impl From<LexErr> for PathErr {
+    fn from(err:LexErr)->PathErr {
+        match err {
+            LexErr::UnexpectedChar(pos)=>PathErr::UnexpectedChar(pos),
+            LexErr::UnexpectedEnd=>PathErr::UnexpectedEnd,
+            LexErr::InvalidEscape(pos)=>PathErr::InvalidEscape(pos)
+        }
+    }
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>)
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>,Option<i64>,Option<i64>),
+    Filter(Expr)
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) struct Query {
+    relative:bool,
+    segments:Vec<Segment>
+}
+
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub(crate) enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Function {
+    Length(Box<Comparable>),
+    Count(Query),
+    Value(Query)
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Comparable {
+    Literal(Json),
+    Query(Query),
+    Function(Function)
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Exists(Query),
+    Compare(Comparable,CompareOp,Comparable)
+}
+
+// a compiled JSONPath expression (RFC 9535)
+#[derive(Debug,PartialEq,Clone)]
+pub struct JsonPath {
+    segments:Vec<Segment>
+}
+
+// a node selected by a query, along with its normalized path such as $['items'][0]
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct PathMatch<'a> {
+    pub path:String,
+    pub value:&'a Json
+}
+
+impl JsonPath {
+    pub fn parse(text:&str)->Result<JsonPath,PathErr> {
+        parser::PathParser::new(text).parse()
+    }
+    pub fn query<'a>(&self,json:&'a Json)->Vec<PathMatch<'a>> {
+        eval::select(&self.segments, json)
+    }
+}
+
+impl Json {
+    pub fn query(&self,path:&str)->Result<Vec<PathMatch<'_>>,PathErr> {
+        Ok(JsonPath::parse(path)?.query(self))
+    }
+}
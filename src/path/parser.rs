@@ -0,0 +1,363 @@
+use crate::Json;
+use crate::lexer::Lexer;
+use crate::path::*;
+
+pub struct PathParser {
+    lexer:Lexer
+}
+
+fn is_name_first(c:char)->bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name_char(c:char)->bool {
+    is_name_first(c) || c.is_ascii_digit()
+}
+
+fn is_singular(query:&Query)->bool {
+    query.segments.iter().all(|segment|match segment {
+        Segment::Child(selectors)=>selectors.len() == 1 && matches!(
+            selectors[0],Selector::Name(_) | Selector::Index(_)
+        ),
+        Segment::Descendant(_)=>false
+    })
+}
+
+// integers in a query must stay within the I-JSON range
+const MAX_INT : i64 = 9007199254740991;
+
+impl PathParser {
+    pub fn new(text:&str)->PathParser {
+        PathParser{
+            lexer:Lexer::new(text)
+        }
+    }
+
+    pub fn parse(&mut self)->Result<JsonPath,PathErr> {
+        self.lexer.expect('$')?;
+        let segments = self.parse_segments()?;
+        if self.lexer.peek().is_some() {
+            return Err(self.lexer.unexpected().into());
+        }
+        Ok(JsonPath{segments})
+    }
+
+    fn parse_segments(&mut self)->Result<Vec<Segment>,PathErr> {
+        let mut segments = vec![];
+        loop {
+            let start = self.lexer.pos;
+            self.lexer.skip_space();
+            match self.lexer.peek() {
+                Some('[')=>segments.push(Segment::Child(self.parse_bracketed()?)),
+                Some('.')=>segments.push(self.parse_dot_segment()?),
+                _=>{
+                    self.lexer.pos = start;
+                    return Ok(segments);
+                }
+            }
+        }
+    }
+
+    fn parse_dot_segment(&mut self)->Result<Segment,PathErr> {
+        self.lexer.pos += 1;
+        let descendant = self.lexer.peek() == Some('.');
+        if descendant {
+            self.lexer.pos += 1;
+        }
+        let selectors = match self.lexer.peek() {
+            Some('*')=>{
+                self.lexer.pos += 1;
+                vec![Selector::Wildcard]
+            },
+            Some('[') if descendant=>self.parse_bracketed()?,
+            Some(c) if is_name_first(c)=>vec![Selector::Name(self.parse_member_name())],
+            _=>return Err(self.lexer.unexpected().into())
+        };
+        if descendant {
+            Ok(Segment::Descendant(selectors))
+        }
+        else {
+            Ok(Segment::Child(selectors))
+        }
+    }
+
+    fn parse_member_name(&mut self)->String {
+        let mut name = String::new();
+        while let Some(c) = self.lexer.peek() {
+            if !is_name_char(c) {
+                break;
+            }
+            name.push(c);
+            self.lexer.pos += 1;
+        }
+        name
+    }
+
+    fn parse_bracketed(&mut self)->Result<Vec<Selector>,PathErr> {
+        self.lexer.expect('[')?;
+        let mut selectors = vec![];
+        loop {
+            self.lexer.skip_space();
+            selectors.push(self.parse_selector()?);
+            self.lexer.skip_space();
+            match self.lexer.peek() {
+                Some(',')=>self.lexer.pos += 1,
+                Some(']')=>{
+                    self.lexer.pos += 1;
+                    return Ok(selectors);
+                },
+                _=>return Err(self.lexer.unexpected().into())
+            }
+        }
+    }
+
+    fn parse_selector(&mut self)->Result<Selector,PathErr> {
+        match self.lexer.peek() {
+            Some('\'') | Some('"')=>Ok(Selector::Name(self.lexer.string(false)?)),
+            Some('*')=>{
+                self.lexer.pos += 1;
+                Ok(Selector::Wildcard)
+            },
+            Some('?')=>{
+                self.lexer.pos += 1;
+                self.lexer.skip_space();
+                Ok(Selector::Filter(self.parse_or()?))
+            },
+            Some(':')=>self.parse_slice(None),
+            Some(c) if c == '-' || c.is_ascii_digit()=>{
+                let index = self.parse_int()?;
+                self.lexer.skip_space();
+                if self.lexer.peek() == Some(':') {
+                    self.parse_slice(Some(index))
+                }
+                else {
+                    Ok(Selector::Index(index))
+                }
+            },
+            _=>Err(self.lexer.unexpected().into())
+        }
+    }
+
+    fn parse_slice(&mut self,start:Option<i64>)->Result<Selector,PathErr> {
+        self.lexer.expect(':')?;
+        self.lexer.skip_space();
+        let end = self.parse_optional_int()?;
+        self.lexer.skip_space();
+        let mut step = None;
+        if self.lexer.peek() == Some(':') {
+            self.lexer.pos += 1;
+            self.lexer.skip_space();
+            step = self.parse_optional_int()?;
+        }
+        Ok(Selector::Slice(start,end,step))
+    }
+
+    fn parse_optional_int(&mut self)->Result<Option<i64>,PathErr> {
+        match self.lexer.peek() {
+            Some(c) if c == '-' || c.is_ascii_digit()=>Ok(Some(self.parse_int()?)),
+            _=>Ok(None)
+        }
+    }
+
+    fn parse_int(&mut self)->Result<i64,PathErr> {
+        let start = self.lexer.pos;
+        let mut text = String::new();
+        if self.lexer.peek() == Some('-') {
+            text.push('-');
+            self.lexer.pos += 1;
+        }
+        let digits_start = self.lexer.pos;
+        while let Some(c) = self.lexer.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            text.push(c);
+            self.lexer.pos += 1;
+        }
+        let digits = &self.lexer.chars[digits_start..self.lexer.pos];
+        let leading_zero = digits.len() > 1 && digits[0] == '0';
+        let negative_zero = text == "-0";
+        if digits.is_empty() || leading_zero || negative_zero {
+            return Err(PathErr::InvalidNumber(start));
+        }
+        match text.parse::<i64>() {
+            Ok(n) if (-MAX_INT..=MAX_INT).contains(&n)=>Ok(n),
+            _=>Err(PathErr::InvalidNumber(start))
+        }
+    }
+
+    fn parse_number_literal(&mut self)->Result<Json,PathErr> {
+        let start = self.lexer.pos;
+        let mut text = String::new();
+        while let Some(c) = self.lexer.peek() {
+            if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+                break;
+            }
+            text.push(c);
+            self.lexer.pos += 1;
+        }
+        // the literal follows json number syntax, except that -0 is allowed
+        match crate::number::Decimal::parse(&text) {
+            Some(_)=>Ok(Json::Number(text)),
+            None=>Err(PathErr::InvalidNumber(start))
+        }
+    }
+
+    fn parse_or(&mut self)->Result<Expr,PathErr> {
+        let mut operands = vec![self.parse_and()?];
+        loop {
+            self.lexer.skip_space();
+            if !self.lexer.eat("||") {
+                break;
+            }
+            self.lexer.skip_space();
+            operands.push(self.parse_and()?);
+        }
+        if operands.len() == 1 {
+            Ok(operands.pop().unwrap())
+        }
+        else {
+            Ok(Expr::Or(operands))
+        }
+    }
+
+    fn parse_and(&mut self)->Result<Expr,PathErr> {
+        let mut operands = vec![self.parse_basic()?];
+        loop {
+            self.lexer.skip_space();
+            if !self.lexer.eat("&&") {
+                break;
+            }
+            self.lexer.skip_space();
+            operands.push(self.parse_basic()?);
+        }
+        if operands.len() == 1 {
+            Ok(operands.pop().unwrap())
+        }
+        else {
+            Ok(Expr::And(operands))
+        }
+    }
+
+    fn parse_basic(&mut self)->Result<Expr,PathErr> {
+        if self.lexer.peek() == Some('!') {
+            self.lexer.pos += 1;
+            self.lexer.skip_space();
+            let negated = if self.lexer.peek() == Some('(') {
+                self.parse_paren()?
+            }
+            else {
+                let start = self.lexer.pos;
+                match self.parse_comparable()? {
+                    Comparable::Query(query)=>Expr::Exists(query),
+                    _=>return Err(PathErr::ExpectedComparison(start))
+                }
+            };
+            return Ok(Expr::Not(Box::new(negated)));
+        }
+        if self.lexer.peek() == Some('(') {
+            return self.parse_paren();
+        }
+        let start = self.lexer.pos;
+        let left = self.parse_comparable()?;
+        self.lexer.skip_space();
+        match self.parse_compare_op() {
+            Some(op)=>{
+                self.check_comparable(&left, start)?;
+                self.lexer.skip_space();
+                let right_start = self.lexer.pos;
+                let right = self.parse_comparable()?;
+                self.check_comparable(&right, right_start)?;
+                Ok(Expr::Compare(left,op,right))
+            },
+            None=>match left {
+                Comparable::Query(query)=>Ok(Expr::Exists(query)),
+                _=>Err(PathErr::ExpectedComparison(start))
+            }
+        }
+    }
+
+    fn parse_paren(&mut self)->Result<Expr,PathErr> {
+        self.lexer.expect('(')?;
+        self.lexer.skip_space();
+        let expr = self.parse_or()?;
+        self.lexer.skip_space();
+        self.lexer.expect(')')?;
+        Ok(expr)
+    }
+
+    fn parse_compare_op(&mut self)->Option<CompareOp> {
+        let ops = [
+            ("==",CompareOp::Eq),("!=",CompareOp::Ne),("<=",CompareOp::Le),
+            (">=",CompareOp::Ge),("<",CompareOp::Lt),(">",CompareOp::Gt)
+        ];
+        ops.iter().find(|(text,_)|self.lexer.eat(text)).map(|(_,op)|*op)
+    }
+
+    // queries used as comparison operands must select at most one node
+    fn check_comparable(&self,comparable:&Comparable,start:usize)->Result<(),PathErr> {
+        match comparable {
+            Comparable::Query(query) if !is_singular(query)=>Err(PathErr::NonSingularQuery(start)),
+            _=>Ok(())
+        }
+    }
+
+    fn parse_query(&mut self)->Result<Query,PathErr> {
+        let relative = self.lexer.peek() == Some('@');
+        self.lexer.pos += 1;
+        Ok(Query{
+            relative,
+            segments:self.parse_segments()?
+        })
+    }
+
+    fn parse_comparable(&mut self)->Result<Comparable,PathErr> {
+        match self.lexer.peek() {
+            Some('@') | Some('$')=>Ok(Comparable::Query(self.parse_query()?)),
+            Some('\'') | Some('"')=>Ok(Comparable::Literal(Json::String(self.lexer.string(false)?))),
+            Some(c) if c == '-' || c.is_ascii_digit()=>Ok(Comparable::Literal(self.parse_number_literal()?)),
+            Some(c) if c.is_ascii_lowercase()=>{
+                if self.lexer.eat("true") {
+                    Ok(Comparable::Literal(Json::Bool(true)))
+                }
+                else if self.lexer.eat("false") {
+                    Ok(Comparable::Literal(Json::Bool(false)))
+                }
+                else if self.lexer.eat("null") {
+                    Ok(Comparable::Literal(Json::Null))
+                }
+                else {
+                    Ok(Comparable::Function(self.parse_function()?))
+                }
+            },
+            _=>Err(self.lexer.unexpected().into())
+        }
+    }
+
+    fn parse_function(&mut self)->Result<Function,PathErr> {
+        let mut name = String::new();
+        while let Some(c) = self.lexer.peek() {
+            if !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+                break;
+            }
+            name.push(c);
+            self.lexer.pos += 1;
+        }
+        self.lexer.expect('(')?;
+        self.lexer.skip_space();
+        let start = self.lexer.pos;
+        let argument = self.parse_comparable()?;
+        self.lexer.skip_space();
+        self.lexer.expect(')')?;
+        match (name.as_str(),argument) {
+            ("length",argument)=>{
+                self.check_comparable(&argument, start)?;
+                Ok(Function::Length(Box::new(argument)))
+            },
+            ("count",Comparable::Query(query))=>Ok(Function::Count(query)),
+            ("value",Comparable::Query(query))=>Ok(Function::Value(query)),
+            ("count",_) | ("value",_)=>Err(PathErr::InvalidFunctionArgument(start)),
+            _=>Err(PathErr::UnknownFunction(name))
+        }
+    }
+}
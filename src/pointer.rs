@@ -0,0 +1,171 @@
+use crate::Json;
+
+// every error carries the pointer prefix up to the segment that failed
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum PointerErr {
+    MissingSlash(String),
+    InvalidEscape(String),
+    KeyNotFound(String),
+    InvalidIndex(String),
+    IndexOutOfBounds(String),
+    NotAContainer(String),
+    RemoveRoot
+}
+
+pub(crate) fn escape_token(token:&str)->String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_token(raw:&str,prefix:&str)->Result<String,PointerErr> {
+    let mut token = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c == '~' {
+            match chars.next() {
+                Some('0')=>token.push('~'),
+                Some('1')=>token.push('/'),
+                _=>return Err(PointerErr::InvalidEscape(String::from(prefix)))
+            }
+        }
+        else {
+            token.push(c);
+        }
+    }
+    Ok(token)
+}
+
+struct Segment {
+    token:String,
+    prefix:String
+}
+
+fn split(pointer:&str)->Result<Vec<Segment>,PointerErr> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    if !pointer.starts_with('/') {
+        return Err(PointerErr::MissingSlash(String::from(pointer)));
+    }
+    let mut segments = vec![];
+    let mut prefix = String::new();
+    for raw in pointer[1..].split('/') {
+        prefix.push('/');
+        prefix.push_str(raw);
+        segments.push(Segment{
+            token:unescape_token(raw, &prefix)?,
+            prefix:prefix.clone()
+        });
+    }
+    Ok(segments)
+}
+
+fn parse_index(segment:&Segment)->Result<usize,PointerErr> {
+    let token = segment.token.as_str();
+    let valid = !token.is_empty()
+        && token.chars().all(|c|c.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    if !valid {
+        return Err(PointerErr::InvalidIndex(segment.prefix.clone()));
+    }
+    token.parse().map_err(|_|PointerErr::IndexOutOfBounds(segment.prefix.clone()))
+}
+
+fn step<'a>(json:&'a Json,segment:&Segment)->Result<&'a Json,PointerErr> {
+    match json {
+        Json::Object(map)=>map.get(&segment.token)
+            .ok_or_else(||PointerErr::KeyNotFound(segment.prefix.clone())),
+        Json::Array(vec)=>{
+            if segment.token == "-" {
+                return Err(PointerErr::IndexOutOfBounds(segment.prefix.clone()));
+            }
+            vec.get(parse_index(segment)?)
+                .ok_or_else(||PointerErr::IndexOutOfBounds(segment.prefix.clone()))
+        },
+        _=>Err(PointerErr::NotAContainer(segment.prefix.clone()))
+    }
+}
+
+fn step_mut<'a>(json:&'a mut Json,segment:&Segment)->Result<&'a mut Json,PointerErr> {
+    match json {
+        Json::Object(map)=>map.get_mut(&segment.token)
+            .ok_or_else(||PointerErr::KeyNotFound(segment.prefix.clone())),
+        Json::Array(vec)=>{
+            if segment.token == "-" {
+                return Err(PointerErr::IndexOutOfBounds(segment.prefix.clone()));
+            }
+            vec.get_mut(parse_index(segment)?)
+                .ok_or_else(||PointerErr::IndexOutOfBounds(segment.prefix.clone()))
+        },
+        _=>Err(PointerErr::NotAContainer(segment.prefix.clone()))
+    }
+}
+
+fn walk_mut<'a>(json:&'a mut Json,segments:&[Segment])->Result<&'a mut Json,PointerErr> {
+    let mut current = json;
+    for segment in segments {
+        current = step_mut(current, segment)?;
+    }
+    Ok(current)
+}
+
+impl Json {
+    pub fn pointer(&self,pointer:&str)->Result<&Json,PointerErr> {
+        let mut current = self;
+        for segment in split(pointer)? {
+            current = step(current, &segment)?;
+        }
+        Ok(current)
+    }
+    pub fn pointer_mut(&mut self,pointer:&str)->Result<&mut Json,PointerErr> {
+        let segments = split(pointer)?;
+        walk_mut(self, &segments)
+    }
+    // follows the "add" semantics of json patch: object members are set
+    // (returning the previous value) and array elements are inserted,
+    // with "-" appending to the end of the array
+    pub fn insert_at_pointer(&mut self,pointer:&str,value:Json)->Result<Option<Json>,PointerErr> {
+        let segments = split(pointer)?;
+        let (last,parents) = match segments.split_last() {
+            None=>return Ok(Some(std::mem::replace(self, value))),
+            Some(pair)=>pair
+        };
+        match walk_mut(self, parents)? {
+            Json::Object(map)=>Ok(map.insert(last.token.clone(), value)),
+            Json::Array(vec)=>{
+                let index = if last.token == "-" {
+                    vec.len()
+                } else {
+                    parse_index(last)?
+                };
+                if index > vec.len() {
+                    return Err(PointerErr::IndexOutOfBounds(last.prefix.clone()));
+                }
+                vec.insert(index, value);
+                Ok(None)
+            },
+            _=>Err(PointerErr::NotAContainer(last.prefix.clone()))
+        }
+    }
+    pub fn remove_at_pointer(&mut self,pointer:&str)->Result<Json,PointerErr> {
+        let segments = split(pointer)?;
+        let (last,parents) = match segments.split_last() {
+            None=>return Err(PointerErr::RemoveRoot),
+            Some(pair)=>pair
+        };
+        match walk_mut(self, parents)? {
+            Json::Object(map)=>map.remove(&last.token)
+                .ok_or_else(||PointerErr::KeyNotFound(last.prefix.clone())),
+            Json::Array(vec)=>{
+                if last.token == "-" {
+                    return Err(PointerErr::IndexOutOfBounds(last.prefix.clone()));
+                }
+                let index = parse_index(last)?;
+                if index >= vec.len() {
+                    return Err(PointerErr::IndexOutOfBounds(last.prefix.clone()));
+                }
+                Ok(vec.remove(index))
+            },
+            _=>Err(PointerErr::NotAContainer(last.prefix.clone()))
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap,HashMap};
+
+use crate::Json;
+use crate::number::Decimal;
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct InferOptions {
+    // strings become an enum when they take at most this many distinct values
+    pub max_enum_values:usize,
+    // ... and each distinct value was seen at least this many times on average
+    pub min_enum_repeats:usize
+}
+
+impl Default for InferOptions {
+    fn default()->InferOptions {
+        InferOptions{
+            max_enum_values:8,
+            min_enum_repeats:2
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shape {
+    nulls:usize,
+    booleans:usize,
+    integers:usize,
+    numbers:usize,
+    strings:usize,
+    // distinct strings seen, dropped once there are too many for an enum
+    values:BTreeMap<String,usize>,
+    too_many_values:bool,
+    arrays:usize,
+    items:Option<Box<Shape>>,
+    objects:usize,
+    properties:HashMap<String,Shape>
+}
+
+impl Shape {
+    fn count(&self)->usize {
+        self.nulls + self.booleans + self.integers + self.numbers
+            + self.strings + self.arrays + self.objects
+    }
+
+    fn add(&mut self,json:&Json,options:&InferOptions) {
+        match json {
+            Json::Null=>self.nulls += 1,
+            Json::Bool(_)=>self.booleans += 1,
+            Json::Number(num)=>match Decimal::parse(num) {
+                Some(d) if d.exponent >= 0=>self.integers += 1,
+                _=>self.numbers += 1
+            },
+            Json::String(str)=>{
+                self.strings += 1;
+                if !self.too_many_values {
+                    *self.values.entry(str.clone()).or_insert(0) += 1;
+                    if self.values.len() > options.max_enum_values {
+                        self.values.clear();
+                        self.too_many_values = true;
+                    }
+                }
+            },
+            Json::Array(vec)=>{
+                self.arrays += 1;
+                let items = self.items.get_or_insert_with(Default::default);
+                for item in vec {
+                    items.add(item, options);
+                }
+            },
+            Json::Object(map)=>{
+                self.objects += 1;
+                for (key,value) in map {
+                    self.properties.entry(key.clone()).or_default().add(value, options);
+                }
+            }
+        }
+    }
+
+    fn type_names(&self)->Vec<&'static str> {
+        let mut names = vec![];
+        if self.objects > 0 { names.push("object"); }
+        if self.arrays > 0 { names.push("array"); }
+        if self.strings > 0 { names.push("string"); }
+        if self.numbers > 0 {
+            names.push("number");
+        }
+        else if self.integers > 0 {
+            names.push("integer");
+        }
+        if self.booleans > 0 { names.push("boolean"); }
+        if self.nulls > 0 { names.push("null"); }
+        names
+    }
+
+    fn string_enum(&self,options:&InferOptions)->Option<Vec<Json>> {
+        let values = &self.values;
+        let only_strings = self.strings + self.nulls == self.count();
+        if !only_strings || values.is_empty() || self.strings < values.len() * options.min_enum_repeats {
+            return None;
+        }
+        let mut allowed : Vec<Json> = values.keys().map(|value|Json::String(value.clone())).collect();
+        if self.nulls > 0 {
+            allowed.push(Json::Null);
+        }
+        Some(allowed)
+    }
+
+    fn to_schema(&self,options:&InferOptions)->Json {
+        let mut schema = HashMap::new();
+        let names = self.type_names();
+        match names.len() {
+            0=>{},
+            1=>{
+                schema.insert(String::from("type"), Json::String(String::from(names[0])));
+            },
+            _=>{
+                let names = names.iter().map(|name|Json::String(String::from(*name))).collect();
+                schema.insert(String::from("type"), Json::Array(names));
+            }
+        }
+        if let Some(allowed) = self.string_enum(options) {
+            schema.insert(String::from("enum"), Json::Array(allowed));
+        }
+        if let Some(items) = &self.items {
+            if items.count() > 0 {
+                schema.insert(String::from("items"), items.to_schema(options));
+            }
+        }
+        if self.objects > 0 {
+            let mut properties = HashMap::new();
+            let mut required = vec![];
+            for (key,shape) in self.properties.iter() {
+                properties.insert(key.clone(), shape.to_schema(options));
+                if shape.count() == self.objects {
+                    required.push(key.clone());
+                }
+            }
+            required.sort();
+            schema.insert(String::from("properties"), Json::Object(properties));
+            if !required.is_empty() {
+                schema.insert(String::from("required"), Json::Array(required.into_iter().map(Json::String).collect()));
+            }
+        }
+        Json::Object(schema)
+    }
+}
+
+// derives a JSON Schema from sample documents: keys present in every
+// sampled object are required, values seen as null make the type nullable
+pub struct SchemaInferrer {
+    options:InferOptions,
+    root:Shape
+}
+
+impl Default for SchemaInferrer {
+    fn default()->SchemaInferrer {
+        SchemaInferrer::new(InferOptions::default())
+    }
+}
+
+impl SchemaInferrer {
+    pub fn new(options:InferOptions)->SchemaInferrer {
+        SchemaInferrer{
+            options,
+            root:Shape::default()
+        }
+    }
+    pub fn add(&mut self,sample:&Json) {
+        self.root.add(sample, &self.options);
+    }
+    pub fn infer(&self)->Json {
+        let mut schema = self.root.to_schema(&self.options);
+        if let Json::Object(map) = &mut schema {
+            let dialect = String::from("https://json-schema.org/draft/2020-12/schema");
+            map.insert(String::from("$schema"), Json::String(dialect));
+        }
+        schema
+    }
+}
@@ -0,0 +1,266 @@
+mod validate;
+mod infer;
+
+use std::collections::{HashMap,HashSet};
+use regex::Regex;
+
+use crate::Json;
+use crate::number::Decimal;
+use crate::pointer::escape_token;
+pub use infer::{SchemaInferrer,InferOptions};
+
+// every error carries the json pointer of the offending schema keyword
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum SchemaErr {
+    InvalidSchema(String),
+    InvalidKeyword(String),
+    InvalidPattern(String),
+    UnresolvedRef(String)
+}
+
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct ValidationError {
+    pub instance_path:String,
+    pub schema_path:String,
+    pub message:String
+}
+
+// a compiled JSON Schema (draft 2020-12)
+#[derive(Debug,Clone)]
+pub struct Schema {
+    root:Json,
+    patterns:HashMap<String,Regex>,
+    anchors:HashMap<String,String>
+}
+
+const TYPES : [&str;7] = ["null","boolean","object","array","number","string","integer"];
+
+const SCHEMA_KEYWORDS : [&str;8] = [
+    "additionalProperties","items","contains","propertyNames","not","if","then","else"
+];
+const SCHEMA_MAP_KEYWORDS : [&str;4] = ["properties","patternProperties","$defs","dependentSchemas"];
+const SCHEMA_ARRAY_KEYWORDS : [&str;4] = ["prefixItems","allOf","anyOf","oneOf"];
+const COUNT_KEYWORDS : [&str;8] = [
+    "maxLength","minLength","maxItems","minItems","maxContains","minContains",
+    "maxProperties","minProperties"
+];
+const NUMBER_KEYWORDS : [&str;4] = ["maximum","exclusiveMaximum","minimum","exclusiveMinimum"];
+
+fn is_number(json:&Json)->bool {
+    match json {
+        Json::Number(num)=>Decimal::parse(num).is_some(),
+        _=>false
+    }
+}
+
+fn is_count(json:&Json)->bool {
+    match json {
+        Json::Number(num)=>match Decimal::parse(num) {
+            Some(d)=>!d.negative && d.exponent >= 0,
+            None=>false
+        },
+        _=>false
+    }
+}
+
+fn is_string_array(json:&Json)->bool {
+    match json {
+        Json::Array(vec)=>vec.iter().all(|item|item.as_string().is_some()),
+        _=>false
+    }
+}
+
+fn is_type_name(json:&Json)->bool {
+    match json {
+        Json::String(name)=>TYPES.contains(&name.as_str()),
+        _=>false
+    }
+}
+
+fn percent_decode(text:&str)->Option<String> {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        }
+        else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+// only references within the schema itself are supported: "#",
+// "#/json/pointer" and "#anchor". gives the json pointer of the target
+fn target_pointer(anchors:&HashMap<String,String>,target:&str)->Option<String> {
+    let fragment = target.strip_prefix('#')?;
+    let fragment = percent_decode(fragment)?;
+    if fragment.is_empty() || fragment.starts_with('/') {
+        Some(fragment)
+    }
+    else {
+        anchors.get(&fragment).cloned()
+    }
+}
+
+struct Compiler {
+    patterns:HashMap<String,Regex>,
+    anchors:HashMap<String,String>,
+    refs:Vec<(String,String)>
+}
+
+impl Compiler {
+    fn pattern(&mut self,pattern:&Json,path:&str)->Result<(),SchemaErr> {
+        let pattern = pattern.as_string().ok_or_else(||SchemaErr::InvalidKeyword(String::from(path)))?;
+        if !self.patterns.contains_key(pattern) {
+            let regex = Regex::new(pattern).map_err(|_|SchemaErr::InvalidPattern(String::from(path)))?;
+            self.patterns.insert(pattern.clone(), regex);
+        }
+        Ok(())
+    }
+
+    fn check_keyword(&mut self,keyword:&str,value:&Json,path:&str)->Result<(),SchemaErr> {
+        let invalid = ||SchemaErr::InvalidKeyword(String::from(path));
+        if SCHEMA_KEYWORDS.contains(&keyword) {
+            self.check(value, path)?;
+        }
+        else if SCHEMA_MAP_KEYWORDS.contains(&keyword) {
+            let map = value.as_object().ok_or_else(invalid)?;
+            for (name,subschema) in map {
+                if keyword == "patternProperties" {
+                    self.pattern(&Json::String(name.clone()), path)?;
+                }
+                self.check(subschema, &format!("{}/{}",path,escape_token(name)))?;
+            }
+        }
+        else if SCHEMA_ARRAY_KEYWORDS.contains(&keyword) {
+            let vec = value.as_array().ok_or_else(invalid)?;
+            if vec.is_empty() {
+                return Err(invalid());
+            }
+            for (i,subschema) in vec.iter().enumerate() {
+                self.check(subschema, &format!("{}/{}",path,i))?;
+            }
+        }
+        else if COUNT_KEYWORDS.contains(&keyword) {
+            if !is_count(value) {
+                return Err(invalid());
+            }
+        }
+        else if NUMBER_KEYWORDS.contains(&keyword) {
+            if !is_number(value) {
+                return Err(invalid());
+            }
+        }
+        else {
+            let valid = match keyword {
+                "type"=>is_type_name(value) || match value {
+                    Json::Array(vec)=>vec.iter().all(is_type_name),
+                    _=>false
+                },
+                "enum"=>value.as_array().is_some(),
+                "multipleOf"=>match value {
+                    Json::Number(num)=>Decimal::parse(num).map(|d|!d.negative && !d.is_zero()).unwrap_or(false),
+                    _=>false
+                },
+                "required"=>is_string_array(value),
+                "uniqueItems"=>value.as_bool().is_some(),
+                "dependentRequired"=>match value {
+                    Json::Object(map)=>map.values().all(is_string_array),
+                    _=>false
+                },
+                "pattern"=>{
+                    self.pattern(value, path)?;
+                    true
+                },
+                "$ref"=>match value {
+                    Json::String(target)=>{
+                        self.refs.push((target.clone(),String::from(path)));
+                        true
+                    },
+                    _=>false
+                },
+                "$anchor"=>match value {
+                    Json::String(name)=>{
+                        let schema_path = &path[..path.len() - "/$anchor".len()];
+                        self.anchors.insert(name.clone(), String::from(schema_path));
+                        true
+                    },
+                    _=>false
+                },
+                _=>true
+            };
+            if !valid {
+                return Err(invalid());
+            }
+        }
+        Ok(())
+    }
+
+    fn check(&mut self,schema:&Json,path:&str)->Result<(),SchemaErr> {
+        match schema {
+            Json::Bool(_)=>Ok(()),
+            Json::Object(map)=>{
+                for (keyword,value) in map {
+                    self.check_keyword(keyword, value, &format!("{}/{}",path,escape_token(keyword)))?;
+                }
+                Ok(())
+            },
+            _=>Err(SchemaErr::InvalidSchema(String::from(path)))
+        }
+    }
+}
+
+impl Schema {
+    pub fn compile(schema:&Json)->Result<Schema,SchemaErr> {
+        let mut compiler = Compiler{
+            patterns:HashMap::new(),
+            anchors:HashMap::new(),
+            refs:vec![]
+        };
+        compiler.check(schema, "")?;
+        // targets may sit under keywords check does not walk, such as
+        // definitions, so each one is checked and its patterns compiled.
+        // refs found there join the list
+        let mut checked = HashSet::new();
+        let mut i = 0;
+        while i < compiler.refs.len() {
+            let (target,path) = compiler.refs[i].clone();
+            let unresolved = ||SchemaErr::UnresolvedRef(path.clone());
+            let pointer = target_pointer(&compiler.anchors, &target).ok_or_else(unresolved)?;
+            let subschema = schema.pointer(&pointer).map_err(|_|unresolved())?;
+            if checked.insert(pointer.clone()) {
+                compiler.check(subschema, &pointer)?;
+            }
+            i += 1;
+        }
+        Ok(Schema{
+            root:schema.clone(),
+            patterns:compiler.patterns,
+            anchors:compiler.anchors
+        })
+    }
+
+    fn resolve(&self,target:&str)->Option<&Json> {
+        self.root.pointer(&target_pointer(&self.anchors, target)?).ok()
+    }
+
+    pub fn validate(&self,instance:&Json)->Result<(),Vec<ValidationError>> {
+        let errors = validate::Validator::new(self).run(instance);
+        if errors.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(errors)
+        }
+    }
+
+    pub fn is_valid(&self,instance:&Json)->bool {
+        self.validate(instance).is_ok()
+    }
+}
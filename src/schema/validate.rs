@@ -0,0 +1,393 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::Json;
+use crate::number::Decimal;
+use crate::pointer::escape_token;
+use crate::schema::{Schema,ValidationError};
+
+// references are followed at most this deep, which stops
+// self-referencing schemas from recursing forever
+const MAX_DEPTH : usize = 256;
+
+pub struct Validator<'s> {
+    schema:&'s Schema
+}
+
+struct Location<'p> {
+    instance:&'p str,
+    schema:&'p str,
+    depth:usize
+}
+
+impl<'p> Location<'p> {
+    fn keyword(&self,keyword:&str)->String {
+        format!("{}/{}",self.schema,escape_token(keyword))
+    }
+}
+
+fn decimal(json:&Json)->Option<Decimal> {
+    match json {
+        Json::Number(num)=>Decimal::parse(num),
+        _=>None
+    }
+}
+
+fn count(json:&Json)->usize {
+    decimal(json).map(|d|d.to_es_string().parse().unwrap_or(usize::MAX)).unwrap_or(0)
+}
+
+fn type_matches(name:&str,instance:&Json)->bool {
+    match (name,instance) {
+        ("null",Json::Null) | ("boolean",Json::Bool(_)) | ("object",Json::Object(_))
+            | ("array",Json::Array(_)) | ("number",Json::Number(_)) | ("string",Json::String(_))=>true,
+        ("integer",Json::Number(num))=>match Decimal::parse(num) {
+            Some(d)=>d.exponent >= 0,
+            None=>false
+        },
+        _=>false
+    }
+}
+
+fn pow10(exponent:i64)->Option<u128> {
+    10u128.checked_pow(u32::try_from(exponent).ok()?)
+}
+
+// exact when both numbers fit in 128 bits after scaling,
+// otherwise falls back to floating point division
+fn is_multiple(value:&Decimal,divisor:&Decimal)->bool {
+    if value.is_zero() {
+        return true;
+    }
+    let exponent = value.exponent.min(divisor.exponent);
+    let scaled = |d:&Decimal|->Option<u128> {
+        d.digits.parse::<u128>().ok()?.checked_mul(pow10(d.exponent - exponent)?)
+    };
+    match (scaled(value),scaled(divisor)) {
+        (Some(v),Some(m))=>v % m == 0,
+        _=>{
+            let v : f64 = value.to_es_string().parse().unwrap_or(f64::NAN);
+            let m : f64 = divisor.to_es_string().parse().unwrap_or(f64::NAN);
+            let quotient = v / m;
+            quotient.is_finite() && quotient.fract() == 0.0
+        }
+    }
+}
+
+impl<'s> Validator<'s> {
+    pub fn new(schema:&'s Schema)->Validator<'s> {
+        Validator{schema}
+    }
+
+    pub fn run(&self,instance:&Json)->Vec<ValidationError> {
+        let mut errors = vec![];
+        let location = Location{instance:"",schema:"",depth:0};
+        self.validate(&self.schema.root, instance, &location, &mut errors);
+        errors
+    }
+
+    fn error(&self,errors:&mut Vec<ValidationError>,location:&Location,keyword:&str,message:String) {
+        errors.push(ValidationError{
+            instance_path:String::from(location.instance),
+            schema_path:location.keyword(keyword),
+            message
+        });
+    }
+
+    fn is_valid_at(&self,schema:&Json,instance:&Json,location:&Location)->bool {
+        let mut errors = vec![];
+        self.validate(schema, instance, location, &mut errors);
+        errors.is_empty()
+    }
+
+    fn validate(&self,schema:&Json,instance:&Json,location:&Location,errors:&mut Vec<ValidationError>) {
+        let map = match schema {
+            Json::Bool(true)=>return,
+            Json::Object(map)=>map,
+            _=>{
+                errors.push(ValidationError{
+                    instance_path:String::from(location.instance),
+                    schema_path:String::from(location.schema),
+                    message:String::from("no value is allowed here")
+                });
+                return;
+            }
+        };
+        self.validate_ref(map, instance, location, errors);
+        self.validate_generic(map, instance, location, errors);
+        self.validate_combinators(map, instance, location, errors);
+        match instance {
+            Json::Number(num)=>{
+                if let Some(value) = Decimal::parse(num) {
+                    self.validate_number(map, &value, location, errors);
+                }
+            },
+            Json::String(str)=>self.validate_string(map, str, location, errors),
+            Json::Array(vec)=>self.validate_array(map, vec, location, errors),
+            Json::Object(object)=>self.validate_object(map, instance, object, location, errors),
+            _=>{}
+        }
+    }
+
+    fn validate_ref(&self,map:&HashMap<String,Json>,instance:&Json,location:&Location,errors:&mut Vec<ValidationError>) {
+        let target = match map.get("$ref").and_then(|r|r.as_string()) {
+            Some(target)=>target,
+            None=>return
+        };
+        if location.depth >= MAX_DEPTH {
+            self.error(errors, location, "$ref", String::from("too many nested references"));
+            return;
+        }
+        if let Some(resolved) = self.schema.resolve(target) {
+            let schema_path = location.keyword("$ref");
+            let inner = Location{instance:location.instance,schema:&schema_path,depth:location.depth + 1};
+            self.validate(resolved, instance, &inner, errors);
+        }
+    }
+
+    fn validate_generic(&self,map:&HashMap<String,Json>,instance:&Json,location:&Location,errors:&mut Vec<ValidationError>) {
+        if let Some(types) = map.get("type") {
+            let names : Vec<&String> = match types {
+                Json::String(name)=>vec![name],
+                Json::Array(vec)=>vec.iter().filter_map(|name|name.as_string()).collect(),
+                _=>vec![]
+            };
+            if !names.iter().any(|name|type_matches(name, instance)) {
+                let expected : Vec<&str> = names.iter().map(|name|name.as_str()).collect();
+                self.error(errors, location, "type", format!("expected {}",expected.join(" or ")));
+            }
+        }
+        if let Some(Json::Array(values)) = map.get("enum") {
+            if !values.iter().any(|value|value.semantic_eq(instance)) {
+                self.error(errors, location, "enum", String::from("value is not one of the allowed values"));
+            }
+        }
+        if let Some(value) = map.get("const") {
+            if !value.semantic_eq(instance) {
+                self.error(errors, location, "const", format!("expected {}",value));
+            }
+        }
+    }
+
+    fn validate_combinators(&self,map:&HashMap<String,Json>,instance:&Json,location:&Location,errors:&mut Vec<ValidationError>) {
+        let subschemas = |keyword:&str|->Vec<&Json> {
+            match map.get(keyword) {
+                Some(Json::Array(vec))=>vec.iter().collect(),
+                _=>vec![]
+            }
+        };
+        let at = |keyword:&str,i:usize|format!("{}/{}",location.keyword(keyword),i);
+        for (i,subschema) in subschemas("allOf").into_iter().enumerate() {
+            let path = at("allOf", i);
+            let inner = Location{schema:&path,..*location};
+            self.validate(subschema, instance, &inner, errors);
+        }
+        if map.contains_key("anyOf") {
+            let matched = subschemas("anyOf").into_iter().enumerate().any(|(i,subschema)|{
+                let path = at("anyOf", i);
+                self.is_valid_at(subschema, instance, &Location{schema:&path,..*location})
+            });
+            if !matched {
+                self.error(errors, location, "anyOf", String::from("value does not match any of the schemas"));
+            }
+        }
+        if map.contains_key("oneOf") {
+            let matched = subschemas("oneOf").into_iter().enumerate().filter(|(i,subschema)|{
+                let path = at("oneOf", *i);
+                self.is_valid_at(subschema, instance, &Location{schema:&path,..*location})
+            }).count();
+            if matched != 1 {
+                self.error(errors, location, "oneOf", format!("value matches {} of the schemas instead of exactly one",matched));
+            }
+        }
+        if let Some(subschema) = map.get("not") {
+            let path = location.keyword("not");
+            if self.is_valid_at(subschema, instance, &Location{schema:&path,..*location}) {
+                self.error(errors, location, "not", String::from("value must not match the schema"));
+            }
+        }
+        if let Some(condition) = map.get("if") {
+            let path = location.keyword("if");
+            let branch = if self.is_valid_at(condition, instance, &Location{schema:&path,..*location}) {
+                "then"
+            } else {
+                "else"
+            };
+            if let Some(subschema) = map.get(branch) {
+                let path = location.keyword(branch);
+                self.validate(subschema, instance, &Location{schema:&path,..*location}, errors);
+            }
+        }
+    }
+
+    fn validate_number(&self,map:&HashMap<String,Json>,value:&Decimal,location:&Location,errors:&mut Vec<ValidationError>) {
+        let bounds = [
+            ("maximum",[Ordering::Greater].as_ref(),"greater than"),
+            ("exclusiveMaximum",[Ordering::Greater,Ordering::Equal].as_ref(),"not less than"),
+            ("minimum",[Ordering::Less].as_ref(),"less than"),
+            ("exclusiveMinimum",[Ordering::Less,Ordering::Equal].as_ref(),"not greater than")
+        ];
+        for (keyword,failing,relation) in bounds.iter() {
+            if let Some(limit) = map.get(*keyword).and_then(decimal) {
+                if failing.contains(&value.cmp(&limit)) {
+                    let message = format!("value is {} {}",relation,limit.to_es_string());
+                    self.error(errors, location, keyword, message);
+                }
+            }
+        }
+        if let Some(divisor) = map.get("multipleOf").and_then(decimal) {
+            if !is_multiple(value, &divisor) {
+                self.error(errors, location, "multipleOf", format!("value is not a multiple of {}",divisor.to_es_string()));
+            }
+        }
+    }
+
+    fn validate_string(&self,map:&HashMap<String,Json>,str:&str,location:&Location,errors:&mut Vec<ValidationError>) {
+        let length = str.chars().count();
+        if let Some(max) = map.get("maxLength") {
+            if length > count(max) {
+                self.error(errors, location, "maxLength", format!("string is longer than {} characters",count(max)));
+            }
+        }
+        if let Some(min) = map.get("minLength") {
+            if length < count(min) {
+                self.error(errors, location, "minLength", format!("string is shorter than {} characters",count(min)));
+            }
+        }
+        if let Some(Json::String(pattern)) = map.get("pattern") {
+            if !self.schema.patterns[pattern].is_match(str) {
+                self.error(errors, location, "pattern", format!("string does not match pattern {}",pattern));
+            }
+        }
+    }
+
+    fn validate_array(&self,map:&HashMap<String,Json>,vec:&[Json],location:&Location,errors:&mut Vec<ValidationError>) {
+        if let Some(max) = map.get("maxItems") {
+            if vec.len() > count(max) {
+                self.error(errors, location, "maxItems", format!("array has more than {} items",count(max)));
+            }
+        }
+        if let Some(min) = map.get("minItems") {
+            if vec.len() < count(min) {
+                self.error(errors, location, "minItems", format!("array has fewer than {} items",count(min)));
+            }
+        }
+        if let Some(Json::Bool(true)) = map.get("uniqueItems") {
+            let duplicate = (0..vec.len()).any(|i|(i + 1..vec.len()).any(|j|vec[i].semantic_eq(&vec[j])));
+            if duplicate {
+                self.error(errors, location, "uniqueItems", String::from("array items are not unique"));
+            }
+        }
+        let prefix = match map.get("prefixItems") {
+            Some(Json::Array(schemas))=>schemas.as_slice(),
+            _=>&[]
+        };
+        for (i,item) in vec.iter().enumerate() {
+            let instance_path = format!("{}/{}",location.instance,i);
+            let (subschema,schema_path) = match prefix.get(i) {
+                Some(subschema)=>(subschema,format!("{}/{}",location.keyword("prefixItems"),i)),
+                None=>match map.get("items") {
+                    Some(subschema)=>(subschema,location.keyword("items")),
+                    None=>continue
+                }
+            };
+            let inner = Location{instance:&instance_path,schema:&schema_path,depth:location.depth};
+            self.validate(subschema, item, &inner, errors);
+        }
+        if let Some(subschema) = map.get("contains") {
+            let schema_path = location.keyword("contains");
+            let matches = vec.iter().enumerate().filter(|(i,item)|{
+                let instance_path = format!("{}/{}",location.instance,i);
+                let inner = Location{instance:&instance_path,schema:&schema_path,depth:location.depth};
+                self.is_valid_at(subschema, item, &inner)
+            }).count();
+            let min = map.get("minContains").map(count).unwrap_or(1);
+            if matches < min {
+                self.error(errors, location, "contains", format!("array contains {} matching items, expected at least {}",matches,min));
+            }
+            if let Some(max) = map.get("maxContains") {
+                if matches > count(max) {
+                    self.error(errors, location, "maxContains", format!("array contains more than {} matching items",count(max)));
+                }
+            }
+        }
+    }
+
+    fn validate_object(&self,map:&HashMap<String,Json>,instance:&Json,object:&HashMap<String,Json>,location:&Location,errors:&mut Vec<ValidationError>) {
+        if let Some(max) = map.get("maxProperties") {
+            if object.len() > count(max) {
+                self.error(errors, location, "maxProperties", format!("object has more than {} properties",count(max)));
+            }
+        }
+        if let Some(min) = map.get("minProperties") {
+            if object.len() < count(min) {
+                self.error(errors, location, "minProperties", format!("object has fewer than {} properties",count(min)));
+            }
+        }
+        if let Some(Json::Array(required)) = map.get("required") {
+            for name in required.iter().filter_map(|name|name.as_string()) {
+                if !object.contains_key(name) {
+                    self.error(errors, location, "required", format!("missing required property {}",name));
+                }
+            }
+        }
+        if let Some(Json::Object(dependencies)) = map.get("dependentRequired") {
+            for (property,required) in dependencies {
+                if !object.contains_key(property) {
+                    continue;
+                }
+                for name in required.as_array().unwrap().iter().filter_map(|name|name.as_string()) {
+                    if !object.contains_key(name) {
+                        let message = format!("property {} requires property {}",property,name);
+                        self.error(errors, location, "dependentRequired", message);
+                    }
+                }
+            }
+        }
+        if let Some(Json::Object(dependencies)) = map.get("dependentSchemas") {
+            for (property,subschema) in dependencies {
+                if object.contains_key(property) {
+                    let schema_path = format!("{}/{}",location.keyword("dependentSchemas"),escape_token(property));
+                    self.validate(subschema, instance, &Location{schema:&schema_path,..*location}, errors);
+                }
+            }
+        }
+        let properties = map.get("properties").and_then(|p|p.as_object());
+        let patterns = map.get("patternProperties").and_then(|p|p.as_object());
+        let mut names : Vec<&String> = object.keys().collect();
+        names.sort();
+        for name in names {
+            let value = &object[name];
+            let instance_path = format!("{}/{}",location.instance,escape_token(name));
+            if let Some(subschema) = map.get("propertyNames") {
+                let schema_path = location.keyword("propertyNames");
+                let inner = Location{instance:&instance_path,schema:&schema_path,depth:location.depth};
+                self.validate(subschema, &Json::String(name.clone()), &inner, errors);
+            }
+            let mut evaluated = false;
+            if let Some(subschema) = properties.and_then(|p|p.get(name)) {
+                evaluated = true;
+                let schema_path = format!("{}/{}",location.keyword("properties"),escape_token(name));
+                let inner = Location{instance:&instance_path,schema:&schema_path,depth:location.depth};
+                self.validate(subschema, value, &inner, errors);
+            }
+            for (pattern,subschema) in patterns.into_iter().flatten() {
+                if self.schema.patterns[pattern].is_match(name) {
+                    evaluated = true;
+                    let schema_path = format!("{}/{}",location.keyword("patternProperties"),escape_token(pattern));
+                    let inner = Location{instance:&instance_path,schema:&schema_path,depth:location.depth};
+                    self.validate(subschema, value, &inner, errors);
+                }
+            }
+            if evaluated {
+                continue;
+            }
+            if let Some(subschema) = map.get("additionalProperties") {
+                let schema_path = location.keyword("additionalProperties");
+                let inner = Location{instance:&instance_path,schema:&schema_path,depth:location.depth};
+                self.validate(subschema, value, &inner, errors);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::Json;
+use crate::number::Decimal;
+
+pub(crate) fn numbers_eq(a:&str,b:&str)->bool {
+    match (Decimal::parse(a),Decimal::parse(b)) {
+        (Some(x),Some(y))=>x == y,
+        _=>a == b
+    }
+}
+
+pub(crate) fn canonical_number(num:&str)->Option<String> {
+    Decimal::parse(num).map(|d|d.to_es_string())
+}
+
+impl Json {
+    // like ==, but numbers are compared by value so 1.0 equals 1
+    pub fn semantic_eq(&self,other:&Json)->bool {
+        match (self,other) {
+            (Json::Number(a),Json::Number(b))=>numbers_eq(a, b),
+            (Json::Array(a),Json::Array(b))=>{
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(x,y)|x.semantic_eq(y))
+            },
+            (Json::Object(a),Json::Object(b))=>{
+                a.len() == b.len() && a.iter().all(|(key,x)|{
+                    match b.get(key) {
+                        Some(y)=>x.semantic_eq(y),
+                        None=>false
+                    }
+                })
+            },
+            _=>self == other
+        }
+    }
+    // rewrites every number in the tree to its shortest form,
+    // numbers that are not valid json are left untouched
+    pub fn normalize(&mut self) {
+        match self {
+            Json::Number(num)=>{
+                if let Some(canonical) = canonical_number(num) {
+                    *num = canonical;
+                }
+            },
+            Json::Array(vec)=>vec.iter_mut().for_each(|json|json.normalize()),
+            Json::Object(map)=>map.values_mut().for_each(|json|json.normalize()),
+            _=>{}
+        }
+    }
+    pub fn normalized(&self)->Json {
+        let mut json = self.clone();
+        json.normalize();
+        json
+    }
+}
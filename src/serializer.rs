@@ -0,0 +1,174 @@
+use std::fmt::{self, Write};
+use std::io::{self, BufWriter};
+
+use crate::Json;
+
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub(crate) enum Style {
+    // standard json
+    Json,
+    // the format Display has always produced: keys unquoted and strings
+    // written as they are
+    Legacy
+}
+
+// quotes, backslashes and control characters are escaped, the latter with
+// the short forms where json has one. runs of plain text are written whole
+pub(crate) fn write_string<W:Write + ?Sized>(out:&mut W,str:&str)->fmt::Result {
+    out.write_char('"')?;
+    let mut start = 0;
+    for (i,c) in str.char_indices() {
+        let escaped = match c {
+            '"'=>"\\\"",
+            '\\'=>"\\\\",
+            '\x08'=>"\\b",
+            '\t'=>"\\t",
+            '\n'=>"\\n",
+            '\x0C'=>"\\f",
+            '\r'=>"\\r",
+            c if c < ' '=>"",
+            _=>continue
+        };
+        out.write_str(&str[start..i])?;
+        if escaped.is_empty() {
+            write!(out, "\\u{:04x}", c as u32)?;
+        }
+        else {
+            out.write_str(escaped)?;
+        }
+        start = i + c.len_utf8();
+    }
+    out.write_str(&str[start..])?;
+    out.write_char('"')
+}
+
+#[derive(Debug,PartialEq,Eq,Clone,Default)]
+pub struct FormatOptions {
+    // spaces per nesting level, or None to write everything on one line
+    pub indent:Option<usize>,
+    pub sort_keys:bool
+}
+
+fn write_newline<W:Write + ?Sized>(out:&mut W,options:&FormatOptions,depth:usize)->fmt::Result {
+    if let Some(indent) = options.indent {
+        out.write_char('\n')?;
+        for _ in 0..indent * depth {
+            out.write_char(' ')?;
+        }
+    }
+    Ok(())
+}
+
+fn write_nested<W:Write + ?Sized>(json:&Json,out:&mut W,style:Style,options:&FormatOptions,depth:usize)->fmt::Result {
+    match json {
+        Json::Null=>out.write_str("null"),
+        Json::Bool(b)=>out.write_str(if *b {"true"} else {"false"}),
+        Json::Number(num)=>out.write_str(num),
+        Json::String(str)=>match style {
+            Style::Json=>write_string(out, str),
+            Style::Legacy=>write!(out, "\"{}\"", str)
+        },
+        Json::Array(vec)=>{
+            out.write_char('[')?;
+            for (i,item) in vec.iter().enumerate() {
+                if i > 0 {
+                    out.write_char(',')?;
+                }
+                write_newline(out, options, depth + 1)?;
+                write_nested(item, out, style, options, depth + 1)?;
+            }
+            if !vec.is_empty() {
+                write_newline(out, options, depth)?;
+            }
+            out.write_char(']')
+        },
+        Json::Object(map)=>{
+            let mut members : Vec<(&String,&Json)> = map.iter().collect();
+            if options.sort_keys {
+                members.sort_by(|a,b|a.0.cmp(b.0));
+            }
+            out.write_char('{')?;
+            for (i,(key,value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.write_char(',')?;
+                }
+                write_newline(out, options, depth + 1)?;
+                match style {
+                    Style::Json=>write_string(out, key)?,
+                    Style::Legacy=>out.write_str(key)?
+                }
+                out.write_str(if options.indent.is_some() {": "} else {":"})?;
+                write_nested(value, out, style, options, depth + 1)?;
+            }
+            if !map.is_empty() {
+                write_newline(out, options, depth)?;
+            }
+            out.write_char('}')
+        }
+    }
+}
+
+pub(crate) fn write_value<W:Write + ?Sized>(json:&Json,out:&mut W,style:Style)->fmt::Result {
+    write_nested(json, out, style, &FormatOptions::default(), 0)
+}
+
+// lets the serializer write to an io sink, keeping the io error that
+// fmt::Error has no room for
+pub(crate) struct IoWriter<W:io::Write> {
+    pub(crate) inner:W,
+    error:Option<io::Error>
+}
+
+impl<W:io::Write> IoWriter<W> {
+    pub(crate) fn new(inner:W)->IoWriter<W> {
+        IoWriter{
+            inner,
+            error:None
+        }
+    }
+    // the error behind the last fmt::Error
+    pub(crate) fn take_error(&mut self)->io::Error {
+        self.error.take().unwrap_or_else(||io::Error::other("formatting failed"))
+    }
+}
+
+impl<W:io::Write> Write for IoWriter<W> {
+    fn write_str(&mut self,str:&str)->fmt::Result {
+        self.inner.write_all(str.as_bytes()).map_err(|err|{
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+impl Json {
+    // writes standard json, unlike Display which keeps its older format
+    pub fn write_json<W:Write>(&self,out:&mut W)->fmt::Result {
+        write_value(self, out, Style::Json)
+    }
+
+    pub fn write_json_with<W:Write>(&self,out:&mut W,options:&FormatOptions)->fmt::Result {
+        write_nested(self, out, Style::Json, options, 0)
+    }
+
+    // output is buffered, and flushed before returning
+    pub fn to_writer<W:io::Write>(&self,writer:W)->io::Result<()> {
+        self.to_writer_with(writer, &FormatOptions::default())
+    }
+
+    pub fn to_writer_with<W:io::Write>(&self,writer:W,options:&FormatOptions)->io::Result<()> {
+        let mut out = IoWriter::new(BufWriter::new(writer));
+        if write_nested(self, &mut out, Style::Json, options, 0).is_err() {
+            return Err(out.take_error());
+        }
+        out.inner.into_inner().map_err(|err|err.into_error())?;
+        Ok(())
+    }
+
+    pub fn to_vec(&self)->Vec<u8> {
+        let mut out = String::new();
+        // writing to a String cannot fail
+        let _ = write_value(self, &mut out, Style::Json);
+        out.into_bytes()
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::Json;
+
+// a container node records how many elements (or members) it has and the
+// index just past its last descendant, so whole subtrees can be skipped.
+// strings and numbers are ranges into one shared text buffer
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub(crate) enum Node {
+    Null,
+    Bool(bool),
+    Number(usize,usize),
+    String(usize,usize),
+    Array{len:usize,end:usize},
+    Object{len:usize,end:usize}
+}
+
+// a parsed document stored as a flat list of nodes in document order, with
+// object keys as string nodes right before their values. however large the
+// input, the whole document lives in two buffers
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Tape {
+    pub(crate) nodes:Vec<Node>,
+    pub(crate) text:String
+}
+
+impl Tape {
+    pub fn root(&self)->Cursor<'_> {
+        Cursor{
+            tape:self,
+            index:0
+        }
+    }
+    pub fn to_json(&self)->Json {
+        self.root().to_json()
+    }
+    pub fn node_count(&self)->usize {
+        self.nodes.len()
+    }
+}
+
+// points at one value of a tape
+#[derive(Debug,Clone,Copy)]
+pub struct Cursor<'t> {
+    tape:&'t Tape,
+    index:usize
+}
+
+impl<'t> Cursor<'t> {
+    fn node(&self)->Node {
+        self.tape.nodes[self.index]
+    }
+
+    fn at_index(&self,index:usize)->Cursor<'t> {
+        Cursor{
+            tape:self.tape,
+            index
+        }
+    }
+
+    // index of the node following this value and all of its descendants
+    fn skip(&self)->usize {
+        match self.node() {
+            Node::Array{end,..} | Node::Object{end,..}=>end,
+            _=>self.index + 1
+        }
+    }
+
+    fn text(&self,start:usize,end:usize)->&'t str {
+        &self.tape.text[start..end]
+    }
+
+    pub fn is_null(&self)->bool {
+        self.node() == Node::Null
+    }
+    pub fn is_array(&self)->bool {
+        matches!(self.node(),Node::Array{..})
+    }
+    pub fn is_object(&self)->bool {
+        matches!(self.node(),Node::Object{..})
+    }
+    pub fn as_bool(&self)->Option<bool> {
+        match self.node() {
+            Node::Bool(b)=>Some(b),
+            _=>None
+        }
+    }
+    pub fn as_number(&self)->Option<&'t str> {
+        match self.node() {
+            Node::Number(start,end)=>Some(self.text(start, end)),
+            _=>None
+        }
+    }
+    pub fn as_string(&self)->Option<&'t str> {
+        match self.node() {
+            Node::String(start,end)=>Some(self.text(start, end)),
+            _=>None
+        }
+    }
+
+    // elements of an array or members of an object
+    pub fn count(&self)->Option<usize> {
+        match self.node() {
+            Node::Array{len,..} | Node::Object{len,..}=>Some(len),
+            _=>None
+        }
+    }
+
+    pub fn at(&self,position:usize)->Option<Cursor<'t>> {
+        self.items().nth(position)
+    }
+
+    // like Json, the last of several members with the same key wins
+    pub fn get(&self,key:&str)->Option<Cursor<'t>> {
+        self.members().filter(|(k,_)|*k == key).last().map(|(_,value)|value)
+    }
+
+    // empty unless this is an array
+    pub fn items(&self)->Items<'t> {
+        let (index,end) = match self.node() {
+            Node::Array{end,..}=>(self.index + 1,end),
+            _=>(0,0)
+        };
+        Items{
+            cursor:self.at_index(index),
+            end
+        }
+    }
+
+    // empty unless this is an object
+    pub fn members(&self)->Members<'t> {
+        let (index,end) = match self.node() {
+            Node::Object{end,..}=>(self.index + 1,end),
+            _=>(0,0)
+        };
+        Members{
+            cursor:self.at_index(index),
+            end
+        }
+    }
+
+    pub fn to_json(&self)->Json {
+        match self.node() {
+            Node::Null=>Json::Null,
+            Node::Bool(b)=>Json::Bool(b),
+            Node::Number(start,end)=>Json::Number(String::from(self.text(start, end))),
+            Node::String(start,end)=>Json::String(String::from(self.text(start, end))),
+            Node::Array{len,..}=>{
+                let mut vec = Vec::with_capacity(len);
+                vec.extend(self.items().map(|item|item.to_json()));
+                Json::Array(vec)
+            },
+            Node::Object{len,..}=>{
+                let mut map = HashMap::with_capacity(len);
+                for (key,value) in self.members() {
+                    map.insert(String::from(key), value.to_json());
+                }
+                Json::Object(map)
+            }
+        }
+    }
+}
+
+pub struct Items<'t> {
+    cursor:Cursor<'t>,
+    end:usize
+}
+
+impl<'t> Iterator for Items<'t> {
+    type Item = Cursor<'t>;
+    fn next(&mut self)->Option<Cursor<'t>> {
+        if self.cursor.index >= self.end {
+            return None;
+        }
+        let item = self.cursor;
+        self.cursor.index = item.skip();
+        Some(item)
+    }
+}
+
+pub struct Members<'t> {
+    cursor:Cursor<'t>,
+    end:usize
+}
+
+impl<'t> Iterator for Members<'t> {
+    type Item = (&'t str,Cursor<'t>);
+    fn next(&mut self)->Option<(&'t str,Cursor<'t>)> {
+        if self.cursor.index >= self.end {
+            return None;
+        }
+        let key = self.cursor.as_string().unwrap_or_default();
+        let value = self.cursor.at_index(self.cursor.index + 1);
+        self.cursor.index = value.skip();
+        Some((key,value))
+    }
+}
@@ -0,0 +1,169 @@
+use std::io::{self, BufWriter};
+use std::fmt::Write;
+
+use crate::Json;
+use crate::serializer::{IoWriter, Style, write_string, write_value};
+
+#[derive(Debug)]
+pub enum WriterErr {
+    Io(io::Error),
+    // a key outside of an object
+    UnexpectedKey,
+    // a value or container inside an object without a key first
+    ExpectedKey,
+    // a key, or the end of the object, right after a key
+    ExpectedValue,
+    // an end that does not match the innermost open container
+    UnbalancedEnd,
+    // a second value after the top level one
+    DocumentComplete,
+    // finish with open containers, or before any value
+    Unfinished
+}
+
+impl From<io::Error> for WriterErr {
+    fn from(err:io::Error)->WriterErr {
+        WriterErr::Io(err)
+    }
+}
+
+// the same states the parsers go through, less those for the separators
+// the writer adds itself
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+enum ObjectWriterState {
+    Label,
+    Value,
+    Comma
+}
+
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+enum ArrayWriterState {
+    Beginning,
+    Comma
+}
+
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+enum Frame {
+    Object(ObjectWriterState),
+    Array(ArrayWriterState)
+}
+
+// writes a document piece by piece, without building it as a Json first.
+// calls that would make the output malformed fail and write nothing
+pub struct JsonWriter<W:io::Write> {
+    out:IoWriter<BufWriter<W>>,
+    stack:Vec<Frame>,
+    complete:bool
+}
+
+impl<W:io::Write> JsonWriter<W> {
+    pub fn new(writer:W)->JsonWriter<W> {
+        JsonWriter{
+            out:IoWriter::new(BufWriter::new(writer)),
+            stack:vec![],
+            complete:false
+        }
+    }
+
+    fn write<F:FnOnce(&mut IoWriter<BufWriter<W>>)->std::fmt::Result>(&mut self,f:F)->Result<(),WriterErr> {
+        match f(&mut self.out) {
+            Ok(())=>Ok(()),
+            Err(_)=>Err(WriterErr::Io(self.out.take_error()))
+        }
+    }
+
+    // checks a value may come next and writes the comma before it
+    fn before_value(&mut self)->Result<(),WriterErr> {
+        match self.stack.last_mut() {
+            None=>{
+                if self.complete {
+                    return Err(WriterErr::DocumentComplete);
+                }
+                self.complete = true;
+                Ok(())
+            },
+            Some(frame @ Frame::Object(ObjectWriterState::Value))=>{
+                *frame = Frame::Object(ObjectWriterState::Comma);
+                Ok(())
+            },
+            Some(Frame::Object(_))=>Err(WriterErr::ExpectedKey),
+            Some(Frame::Array(state))=>{
+                let comma = *state == ArrayWriterState::Comma;
+                *state = ArrayWriterState::Comma;
+                if comma {
+                    self.write(|out|out.write_char(','))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn key(&mut self,key:&str)->Result<(),WriterErr> {
+        let comma = match self.stack.last_mut() {
+            Some(Frame::Object(ObjectWriterState::Value))=>return Err(WriterErr::ExpectedValue),
+            Some(Frame::Object(state))=>{
+                let comma = *state == ObjectWriterState::Comma;
+                *state = ObjectWriterState::Value;
+                comma
+            },
+            _=>return Err(WriterErr::UnexpectedKey)
+        };
+        self.write(|out|{
+            if comma {
+                out.write_char(',')?;
+            }
+            write_string(out, key)?;
+            out.write_char(':')
+        })
+    }
+
+    pub fn value(&mut self,json:&Json)->Result<(),WriterErr> {
+        self.before_value()?;
+        self.write(|out|write_value(json, out, Style::Json))
+    }
+
+    pub fn begin_object(&mut self)->Result<(),WriterErr> {
+        self.before_value()?;
+        self.stack.push(Frame::Object(ObjectWriterState::Label));
+        self.write(|out|out.write_char('{'))
+    }
+
+    pub fn end_object(&mut self)->Result<(),WriterErr> {
+        match self.stack.last() {
+            Some(Frame::Object(ObjectWriterState::Value))=>return Err(WriterErr::ExpectedValue),
+            Some(Frame::Object(_))=>{},
+            _=>return Err(WriterErr::UnbalancedEnd)
+        }
+        self.stack.pop();
+        self.write(|out|out.write_char('}'))
+    }
+
+    pub fn begin_array(&mut self)->Result<(),WriterErr> {
+        self.before_value()?;
+        self.stack.push(Frame::Array(ArrayWriterState::Beginning));
+        self.write(|out|out.write_char('['))
+    }
+
+    pub fn end_array(&mut self)->Result<(),WriterErr> {
+        match self.stack.last() {
+            Some(Frame::Array(_))=>{},
+            _=>return Err(WriterErr::UnbalancedEnd)
+        }
+        self.stack.pop();
+        self.write(|out|out.write_char(']'))
+    }
+
+    // nesting depth of the containers still open
+    pub fn depth(&self)->usize {
+        self.stack.len()
+    }
+
+    // flushes the output and gives the underlying writer back, once the
+    // document is complete
+    pub fn finish(self)->Result<W,WriterErr> {
+        if !self.complete || !self.stack.is_empty() {
+            return Err(WriterErr::Unfinished);
+        }
+        self.out.inner.into_inner().map_err(|err|WriterErr::Io(err.into_error()))
+    }
+}
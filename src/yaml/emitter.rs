@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::yaml::reader::resolve;
+
+const INDENT : usize = 2;
+
+// words YAML 1.1 readers take as booleans
+const BOOLEAN_WORDS : &[&str] = &["yes","Yes","YES","no","No","NO","on","On","ON","off","Off","OFF"];
+
+// looks like a YAML 1.1 number, such as 1_000, 0b101 or 1:30
+fn looks_numeric(str:&str)->bool {
+    let digits = str.trim_start_matches(['-','+']);
+    digits.starts_with(|c:char|c.is_ascii_digit() || c == '.')
+        && digits.chars().any(|c|c.is_ascii_digit())
+        && digits.chars().all(|c|c.is_ascii_hexdigit() || "xXoObB_.:+-".contains(c))
+}
+
+fn printable(c:char)->bool {
+    !c.is_control() && !matches!(c,'\u{2028}' | '\u{2029}' | '\u{feff}')
+}
+
+fn needs_quotes(str:&str)->bool {
+    let first = match str.chars().next() {
+        Some(first)=>first,
+        None=>return true
+    };
+    !matches!(resolve(str),Ok(Json::String(_)))
+        || BOOLEAN_WORDS.contains(&str)
+        || looks_numeric(str)
+        || "-?:,[]{}#&*!|>'\"%@` \t".contains(first)
+        || str.ends_with([' ','\t',':'])
+        || str.starts_with("...")
+        || str.contains(": ")
+        || str.contains(" #")
+        || str.chars().any(|c|!printable(c))
+}
+
+fn write_quoted(out:&mut String,str:&str) {
+    out.push('"');
+    for c in str.chars() {
+        match c {
+            '"'=>out.push_str("\\\""),
+            '\\'=>out.push_str("\\\\"),
+            '\n'=>out.push_str("\\n"),
+            '\t'=>out.push_str("\\t"),
+            '\r'=>out.push_str("\\r"),
+            '\0'=>out.push_str("\\0"),
+            '\u{85}'=>out.push_str("\\N"),
+            '\u{2028}'=>out.push_str("\\L"),
+            '\u{2029}'=>out.push_str("\\P"),
+            c if (c as u32) < 0x100 && !printable(c)=>out.push_str(&format!("\\x{:02x}",c as u32)),
+            c if !printable(c)=>out.push_str(&format!("\\u{:04x}",c as u32)),
+            c=>out.push(c)
+        }
+    }
+    out.push('"');
+}
+
+// multi-line strings read best as literal block scalars. the content has
+// to start without a space, or the reader could not tell its indentation
+fn literal_block(str:&str)->bool {
+    let first_line = str.trim_start_matches('\n');
+    str.contains('\n')
+        && !first_line.is_empty()
+        && !first_line.starts_with([' ','\t'])
+        && str.chars().all(|c|c == '\n' || c == '\t' || printable(c))
+}
+
+fn write_literal(out:&mut String,str:&str,indent:usize) {
+    let content = str.trim_end_matches('\n');
+    let trailing = str.len() - content.len();
+    out.push_str(match trailing {
+        0=>"|-",
+        1=>"|",
+        _=>"|+"
+    });
+    for line in content.split('\n') {
+        out.push('\n');
+        if !line.is_empty() {
+            out.push_str(&" ".repeat(indent));
+            out.push_str(line);
+        }
+    }
+    out.push('\n');
+    for _ in 1..trailing {
+        out.push('\n');
+    }
+}
+
+fn write_string(out:&mut String,str:&str) {
+    if needs_quotes(str) {
+        write_quoted(out, str);
+    }
+    else {
+        out.push_str(str);
+    }
+}
+
+// writes a value that follows a key or a dash on the same line, and the
+// line break that ends it
+fn write_scalar(out:&mut String,json:&Json,indent:usize) {
+    match json {
+        Json::Null=>out.push_str("null"),
+        Json::Bool(b)=>out.push_str(if *b {"true"} else {"false"}),
+        Json::Number(num)=>out.push_str(num),
+        Json::String(str) if literal_block(str)=>return write_literal(out, str, indent),
+        Json::String(str)=>write_string(out, str),
+        Json::Array(_)=>out.push_str("[]"),
+        Json::Object(_)=>out.push_str("{}")
+    }
+    out.push('\n');
+}
+
+fn is_block(json:&Json)->bool {
+    match json {
+        Json::Array(vec)=>!vec.is_empty(),
+        Json::Object(map)=>!map.is_empty(),
+        _=>false
+    }
+}
+
+// the first line of a collection may already hold a dash, so it starts
+// without indentation
+fn write_mapping(out:&mut String,map:&HashMap<String,Json>,indent:usize,inline:bool) {
+    let mut keys : Vec<&String> = map.keys().collect();
+    keys.sort();
+    for (i,key) in keys.into_iter().enumerate() {
+        if i > 0 || !inline {
+            out.push_str(&" ".repeat(indent));
+        }
+        write_string(out, key);
+        out.push(':');
+        let value = &map[key];
+        if is_block(value) {
+            out.push('\n');
+            write_block(out, value, indent + INDENT, false);
+        }
+        else {
+            out.push(' ');
+            write_scalar(out, value, indent + INDENT);
+        }
+    }
+}
+
+fn write_sequence(out:&mut String,vec:&[Json],indent:usize,inline:bool) {
+    for (i,item) in vec.iter().enumerate() {
+        if i > 0 || !inline {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str("- ");
+        if is_block(item) {
+            write_block(out, item, indent + INDENT, true);
+        }
+        else {
+            write_scalar(out, item, indent + INDENT);
+        }
+    }
+}
+
+fn write_block(out:&mut String,json:&Json,indent:usize,inline:bool) {
+    match json {
+        Json::Object(map)=>write_mapping(out, map, indent, inline),
+        Json::Array(vec)=>write_sequence(out, vec, indent, inline),
+        _=>write_scalar(out, json, indent)
+    }
+}
+
+pub fn emit(json:&Json)->String {
+    let mut out = String::new();
+    if is_block(json) {
+        write_block(&mut out, json, 0, false);
+    }
+    else {
+        write_scalar(&mut out, json, INDENT);
+    }
+    out
+}
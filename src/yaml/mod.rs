@@ -0,0 +1,47 @@
+mod emitter;
+mod reader;
+
+use crate::Json;
+
+// every error carries the line and column it was found at, counted from 1
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub enum YamlErr {
+    UnexpectedChar(usize,usize),
+    UnexpectedEnd(usize,usize),
+    InvalidIndentation(usize,usize),
+    InvalidEscape(usize,usize),
+    DuplicateKey(usize,usize),
+    // anchors, aliases, tags, complex keys, directives, several documents
+    // and the non-finite floats have no place in the json data model
+    Unsupported(usize,usize),
+    // nodes nested more than 256 levels deep
+    TooDeep(usize,usize)
+}
+
+impl YamlErr {
+    pub fn line_col(&self)->(usize,usize) {
+        match self {
+            YamlErr::UnexpectedChar(line,col) |
+            YamlErr::UnexpectedEnd(line,col) |
+            YamlErr::InvalidIndentation(line,col) |
+            YamlErr::InvalidEscape(line,col) |
+            YamlErr::DuplicateKey(line,col) |
+            YamlErr::Unsupported(line,col) |
+            YamlErr::TooDeep(line,col)=>(*line,*col)
+        }
+    }
+}
+
+impl Json {
+    // block style YAML 1.2 with keys in sorted order. strings are quoted
+    // only when they would read back as something else, and numbers keep
+    // their text
+    pub fn to_yaml(&self)->String {
+        emitter::emit(self)
+    }
+    // reads one YAML document made of block and flow collections and
+    // scalars resolved with the core schema
+    pub fn from_yaml(text:&str)->Result<Json,YamlErr> {
+        reader::YamlReader::new(text).document()
+    }
+}
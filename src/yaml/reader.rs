@@ -0,0 +1,778 @@
+use std::collections::HashMap;
+
+use crate::Json;
+use crate::yaml::YamlErr;
+
+// the end of the input, or whitespace separating an indicator
+fn blank(c:Option<char>)->bool {
+    matches!(c,None | Some(' ') | Some('\t') | Some('\n'))
+}
+
+fn flow_indicator(c:Option<char>)->bool {
+    matches!(c,Some(',') | Some('[') | Some(']') | Some('{') | Some('}'))
+}
+
+// digits in base 8 or 16 as decimal text
+fn radix(digits:&str,base:u32)->Option<String> {
+    if digits.is_empty() {
+        return None;
+    }
+    // little endian decimal digits
+    let mut decimal : Vec<u32> = vec![0];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(base)?;
+        for d in decimal.iter_mut() {
+            let v = *d * base + carry;
+            *d = v % 10;
+            carry = v / 10;
+        }
+        while carry > 0 {
+            decimal.push(carry % 10);
+            carry /= 10;
+        }
+    }
+    Some(decimal.iter().rev().map(|d|char::from_digit(*d, 10).unwrap()).collect())
+}
+
+// the core schema ints and floats as json number text: the sign of
+// positive numbers and leading zeros go, as does a fraction point without
+// digits, and everything else is kept
+fn number(text:&str)->Option<String> {
+    let (sign,rest) = match text.as_bytes().first() {
+        Some(b'-')=>("-",&text[1..]),
+        Some(b'+')=>("",&text[1..]),
+        _=>("",text)
+    };
+    let (mantissa,exponent) = match rest.find(['e','E']) {
+        Some(i)=>(&rest[..i],Some(&rest[i..])),
+        None=>(rest,None)
+    };
+    let (int,frac) = match mantissa.find('.') {
+        Some(i)=>(&mantissa[..i],Some(&mantissa[i + 1..])),
+        None=>(mantissa,None)
+    };
+    let digits = |str:&str|str.bytes().all(|b|b.is_ascii_digit());
+    if !digits(int) || !frac.is_none_or(digits) || int.is_empty() && frac.is_none_or(str::is_empty) {
+        return None;
+    }
+    if let Some(exponent) = exponent {
+        let value = exponent[1..].trim_start_matches(['-','+']);
+        if value.is_empty() || !digits(value) || exponent[1..].len() > value.len() + 1 {
+            return None;
+        }
+    }
+    let int = int.trim_start_matches('0');
+    let mut out = format!("{}{}",sign,if int.is_empty() {"0"} else {int});
+    if let Some(frac) = frac.filter(|frac|!frac.is_empty()) {
+        out.push('.');
+        out.push_str(frac);
+    }
+    out.push_str(exponent.unwrap_or(""));
+    Some(out)
+}
+
+// the YAML 1.2 core schema for plain scalars. infinities and NaN have no
+// json value and give Err
+pub fn resolve(text:&str)->Result<Json,()> {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL"=>return Ok(Json::Null),
+        "true" | "True" | "TRUE"=>return Ok(Json::Bool(true)),
+        "false" | "False" | "FALSE"=>return Ok(Json::Bool(false)),
+        ".nan" | ".NaN" | ".NAN"=>return Err(()),
+        _=>{}
+    }
+    if matches!(text.strip_prefix(['-','+']).unwrap_or(text),".inf" | ".Inf" | ".INF") {
+        return Err(());
+    }
+    let num = match (text.strip_prefix("0o"),text.strip_prefix("0x")) {
+        (Some(digits),_)=>radix(digits, 8),
+        (_,Some(digits))=>radix(digits, 16),
+        _=>number(text)
+    };
+    Ok(match num {
+        Some(num)=>Json::Number(num),
+        None=>Json::String(String::from(text))
+    })
+}
+
+// nesting allowed, so hostile input cannot exhaust the stack
+const MAX_DEPTH : usize = 256;
+
+pub struct YamlReader {
+    chars:Vec<char>,
+    pos:usize,
+    // line counted from 1, column from 0 so it reads as the indentation
+    line:usize,
+    col:usize,
+    // blank lines passed by the last skip_empty_lines
+    skipped:usize,
+    // block and flow nodes being read
+    depth:usize
+}
+
+impl YamlReader {
+    pub fn new(text:&str)->YamlReader {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        YamlReader{chars:text.replace("\r\n", "\n").chars().collect(),pos:0,line:1,col:0,skipped:0,depth:0}
+    }
+    fn peek(&self)->Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    fn peek_at(&self,offset:usize)->Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+    fn advance(&mut self) {
+        if let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == '\n' {
+                self.line += 1;
+                self.col = 0;
+            }
+            else {
+                self.col += 1;
+            }
+        }
+    }
+    fn err(&self,kind:fn(usize,usize)->YamlErr)->YamlErr {
+        kind(self.line, self.col + 1)
+    }
+    // reads a node one level deeper
+    fn nested(&mut self,read:impl FnOnce(&mut YamlReader)->Result<Json,YamlErr>)->Result<Json,YamlErr> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.err(YamlErr::TooDeep));
+        }
+        self.depth += 1;
+        let result = read(self);
+        self.depth -= 1;
+        result
+    }
+    fn unexpected(&self)->YamlErr {
+        match self.peek() {
+            None=>self.err(YamlErr::UnexpectedEnd),
+            Some(_)=>self.err(YamlErr::UnexpectedChar)
+        }
+    }
+    fn starts_with(&self,str:&str)->bool {
+        str.chars().enumerate().all(|(i,c)|self.peek_at(i) == Some(c))
+    }
+    fn at_document_end(&self)->bool {
+        self.col == 0 && (self.starts_with("---") || self.starts_with("...")) && blank(self.peek_at(3))
+    }
+    fn at_line_end(&self)->bool {
+        matches!(self.peek(),None | Some('\n') | Some('#'))
+    }
+    fn at_entry(&self)->bool {
+        self.peek() == Some('-') && blank(self.peek_at(1))
+    }
+    fn skip_spaces(&mut self) {
+        while let Some(' ') | Some('\t') = self.peek() {
+            self.advance();
+        }
+    }
+    fn skip_to_line_end(&mut self) {
+        while !matches!(self.peek(),None | Some('\n')) {
+            self.advance();
+        }
+    }
+    // whitespace, line breaks and comments inside flow collections
+    fn skip_flow_space(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' ') | Some('\t') | Some('\n')=>self.advance(),
+                Some('#')=>self.skip_to_line_end(),
+                _=>break
+            }
+        }
+    }
+    // only a comment may follow on the rest of the line
+    fn end_line(&mut self)->Result<(),YamlErr> {
+        self.skip_spaces();
+        if self.peek() == Some('#') {
+            self.skip_to_line_end();
+        }
+        match self.peek() {
+            None=>Ok(()),
+            Some('\n')=>{
+                self.advance();
+                Ok(())
+            },
+            Some(_)=>Err(self.unexpected())
+        }
+    }
+    // from the start of a line, passes blank and comment lines and the
+    // indentation of the next one. false at the end of the input
+    fn skip_empty_lines(&mut self)->Result<bool,YamlErr> {
+        self.skipped = 0;
+        loop {
+            let mut tab = None;
+            while let Some(c @ (' ' | '\t')) = self.peek() {
+                if c == '\t' && tab.is_none() {
+                    tab = Some(self.err(YamlErr::InvalidIndentation));
+                }
+                self.advance();
+            }
+            match self.peek() {
+                None=>return Ok(false),
+                Some('\n')=>{
+                    self.skipped += 1;
+                    self.advance();
+                },
+                Some('#')=>{
+                    self.skip_to_line_end();
+                    self.advance();
+                },
+                Some(_)=>return match tab {
+                    Some(err)=>Err(err),
+                    None=>Ok(true)
+                }
+            }
+        }
+    }
+    fn finish_line(&mut self)->Result<bool,YamlErr> {
+        self.end_line()?;
+        self.skip_empty_lines()
+    }
+    pub fn document(mut self)->Result<Json,YamlErr> {
+        if !self.skip_empty_lines()? {
+            return Ok(Json::Null);
+        }
+        if self.peek() == Some('%') {
+            return Err(self.err(YamlErr::Unsupported));
+        }
+        let mut content = true;
+        if self.at_document_end() && self.starts_with("---") {
+            for _ in 0..3 {
+                self.advance();
+            }
+            self.skip_spaces();
+            if self.at_line_end() {
+                content = self.finish_line()?;
+            }
+        }
+        let mut json = Json::Null;
+        if content && !self.at_document_end() {
+            json = self.block_node(-1)?;
+        }
+        if self.at_document_end() && self.starts_with("...") {
+            for _ in 0..3 {
+                self.advance();
+            }
+            self.finish_line()?;
+        }
+        match self.peek() {
+            None=>Ok(json),
+            // a stream of several documents
+            Some(_) if self.at_document_end()=>Err(self.err(YamlErr::Unsupported)),
+            Some(_)=>Err(self.unexpected())
+        }
+    }
+    // a node starting here, its column being its indentation. nested
+    // nodes must be indented deeper than parent
+    fn block_node(&mut self,parent:isize)->Result<Json,YamlErr> {
+        self.nested(|reader|reader.block_node_at(parent))
+    }
+    fn block_node_at(&mut self,parent:isize)->Result<Json,YamlErr> {
+        let indent = self.col;
+        if self.at_entry() {
+            self.block_sequence(indent)
+        }
+        else if self.looks_like_key() {
+            self.block_mapping(indent)
+        }
+        else {
+            self.value_node(parent)
+        }
+    }
+    // a scalar or flow collection, which may go on over following lines
+    fn value_node(&mut self,parent:isize)->Result<Json,YamlErr> {
+        match self.peek() {
+            Some('[') | Some('{')=>{
+                let json = self.flow_node()?;
+                self.finish_line()?;
+                Ok(json)
+            },
+            Some('"') | Some('\'')=>{
+                let str = self.quoted()?;
+                self.finish_line()?;
+                Ok(Json::String(str))
+            },
+            Some('|') | Some('>')=>self.block_scalar(parent),
+            Some('&') | Some('*') | Some('!')=>Err(self.err(YamlErr::Unsupported)),
+            Some('?') if blank(self.peek_at(1))=>Err(self.err(YamlErr::Unsupported)),
+            Some('-') | Some(':') if blank(self.peek_at(1))=>Err(self.unexpected()),
+            Some(c) if ",]}#%@`".contains(c)=>Err(self.unexpected()),
+            _=>self.plain_block(parent)
+        }
+    }
+    // scans the rest of the line for the colon of an implicit key
+    fn looks_like_key(&self)->bool {
+        let at = |i:usize|self.chars.get(i).copied();
+        let mut i = self.pos;
+        match at(i) {
+            Some(quote @ ('"' | '\''))=>{
+                i += 1;
+                loop {
+                    match at(i) {
+                        None | Some('\n')=>return false,
+                        Some('\\') if quote == '"'=>i += 2,
+                        Some('\'') if quote == '\'' && at(i + 1) == Some('\'')=>i += 2,
+                        Some(c) if c == quote=>break,
+                        _=>i += 1
+                    }
+                }
+                i += 1;
+                while let Some(' ') | Some('\t') = at(i) {
+                    i += 1;
+                }
+                at(i) == Some(':') && blank(at(i + 1))
+            },
+            Some('[') | Some('{')=>false,
+            _=>{
+                while let Some(c) = at(i) {
+                    match c {
+                        '\n'=>return false,
+                        ':' if blank(at(i + 1))=>return true,
+                        '#' if i > self.pos && matches!(at(i - 1),Some(' ') | Some('\t'))=>return false,
+                        _=>{}
+                    }
+                    i += 1;
+                }
+                false
+            }
+        }
+    }
+    fn block_sequence(&mut self,indent:usize)->Result<Json,YamlErr> {
+        let mut vec = vec![];
+        loop {
+            self.advance();
+            self.skip_spaces();
+            let item = if self.at_line_end() {
+                if self.finish_line()? && self.col > indent && !self.at_document_end() {
+                    self.block_node(indent as isize)?
+                }
+                else {
+                    Json::Null
+                }
+            }
+            else {
+                self.block_node(indent as isize)?
+            };
+            vec.push(item);
+            if self.peek().is_none() || self.col < indent || self.at_document_end() {
+                break;
+            }
+            if self.col > indent {
+                return Err(self.err(YamlErr::InvalidIndentation));
+            }
+            // otherwise the next key of a mapping holding this sequence
+            if !self.at_entry() {
+                break;
+            }
+        }
+        Ok(Json::Array(vec))
+    }
+    fn block_key(&mut self)->Result<String,YamlErr> {
+        let key = match self.peek() {
+            Some('"') | Some('\'')=>self.quoted()?,
+            Some('&') | Some('*') | Some('!')=>return Err(self.err(YamlErr::Unsupported)),
+            Some('?') if blank(self.peek_at(1))=>return Err(self.err(YamlErr::Unsupported)),
+            Some('-') if blank(self.peek_at(1))=>return Err(self.unexpected()),
+            Some(c) if ",[]{}#|>%@`".contains(c)=>return Err(self.unexpected()),
+            _=>self.plain_line(false)
+        };
+        self.skip_spaces();
+        if self.peek() != Some(':') || key.is_empty() && !blank(self.peek_at(1)) {
+            return Err(self.unexpected());
+        }
+        self.advance();
+        Ok(key)
+    }
+    fn block_mapping(&mut self,indent:usize)->Result<Json,YamlErr> {
+        let mut map = HashMap::new();
+        loop {
+            let duplicate = self.err(YamlErr::DuplicateKey);
+            let key = self.block_key()?;
+            if map.contains_key(&key) {
+                return Err(duplicate);
+            }
+            self.skip_spaces();
+            let value = if self.at_line_end() {
+                if !self.finish_line()? || self.at_document_end() {
+                    Json::Null
+                }
+                else if self.col > indent {
+                    self.block_node(indent as isize)?
+                }
+                // sequences may sit at the indentation of their key
+                else if self.col == indent && self.at_entry() {
+                    self.block_sequence(indent)?
+                }
+                else {
+                    Json::Null
+                }
+            }
+            else {
+                self.value_node(indent as isize)?
+            };
+            map.insert(key, value);
+            if self.peek().is_none() || self.col < indent || self.at_document_end() {
+                break;
+            }
+            if self.col > indent {
+                return Err(self.err(YamlErr::InvalidIndentation));
+            }
+        }
+        Ok(Json::Object(map))
+    }
+    // a plain scalar up to the end of the line, a comment or the colon of
+    // a key. flow collections also end them at their indicators
+    fn plain_line(&mut self,flow:bool)->String {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            let stop = match c {
+                '\n'=>true,
+                ':'=>blank(self.peek_at(1)) || flow && flow_indicator(self.peek_at(1)),
+                '#'=>text.ends_with([' ','\t']),
+                ',' | '[' | ']' | '{' | '}'=>flow,
+                _=>false
+            };
+            if stop {
+                break;
+            }
+            text.push(c);
+            self.advance();
+        }
+        let len = text.trim_end_matches([' ','\t']).len();
+        text.truncate(len);
+        text
+    }
+    // lines indented deeper than parent continue a plain scalar, folded
+    // into a space, or into line feeds for blank lines between them
+    fn plain_block(&mut self,parent:isize)->Result<Json,YamlErr> {
+        let unsupported = self.err(YamlErr::Unsupported);
+        let mut text = self.plain_line(false);
+        let mut multiline = false;
+        while self.finish_line()? && self.col as isize > parent && !self.at_document_end() {
+            if self.skipped == 0 {
+                text.push(' ');
+            }
+            for _ in 0..self.skipped {
+                text.push('\n');
+            }
+            text.push_str(&self.plain_line(false));
+            multiline = true;
+        }
+        if multiline {
+            return Ok(Json::String(text));
+        }
+        resolve(&text).map_err(|_|unsupported)
+    }
+    fn flow_node(&mut self)->Result<Json,YamlErr> {
+        self.nested(YamlReader::flow_node_at)
+    }
+    fn flow_node_at(&mut self)->Result<Json,YamlErr> {
+        match self.peek() {
+            Some('[')=>self.flow_sequence(),
+            Some('{')=>self.flow_mapping(),
+            Some('"') | Some('\'')=>Ok(Json::String(self.quoted()?)),
+            Some('&') | Some('*') | Some('!')=>Err(self.err(YamlErr::Unsupported)),
+            Some('?') if blank(self.peek_at(1))=>Err(self.err(YamlErr::Unsupported)),
+            Some('-') | Some(':') if blank(self.peek_at(1))=>Err(self.unexpected()),
+            Some(c) if ",]}#|>%@`".contains(c)=>Err(self.unexpected()),
+            None=>Err(self.unexpected()),
+            _=>self.flow_plain()
+        }
+    }
+    fn flow_plain(&mut self)->Result<Json,YamlErr> {
+        let unsupported = self.err(YamlErr::Unsupported);
+        let mut text = self.plain_line(true);
+        let mut multiline = false;
+        while self.peek() == Some('\n') {
+            let mut breaks = 0;
+            while let Some(c @ (' ' | '\t' | '\n')) = self.peek() {
+                if c == '\n' {
+                    breaks += 1;
+                }
+                self.advance();
+            }
+            if matches!(self.peek(),None | Some(':') | Some('#')) || flow_indicator(self.peek()) {
+                break;
+            }
+            if breaks == 1 {
+                text.push(' ');
+            }
+            for _ in 1..breaks {
+                text.push('\n');
+            }
+            text.push_str(&self.plain_line(true));
+            multiline = true;
+        }
+        if multiline {
+            return Ok(Json::String(text));
+        }
+        resolve(&text).map_err(|_|unsupported)
+    }
+    fn flow_sequence(&mut self)->Result<Json,YamlErr> {
+        self.advance();
+        let mut vec = vec![];
+        loop {
+            self.skip_flow_space();
+            if self.peek() == Some(']') {
+                self.advance();
+                return Ok(Json::Array(vec));
+            }
+            vec.push(self.flow_node()?);
+            self.skip_flow_space();
+            match self.peek() {
+                Some(',')=>self.advance(),
+                Some(']')=>{},
+                _=>return Err(self.unexpected())
+            }
+        }
+    }
+    fn flow_mapping(&mut self)->Result<Json,YamlErr> {
+        self.advance();
+        let mut map = HashMap::new();
+        loop {
+            self.skip_flow_space();
+            if self.peek() == Some('}') {
+                self.advance();
+                return Ok(Json::Object(map));
+            }
+            let duplicate = self.err(YamlErr::DuplicateKey);
+            let key = match self.peek() {
+                Some('"') | Some('\'')=>self.quoted()?,
+                Some('[') | Some('{') | Some('&') | Some('*') | Some('!')=>return Err(self.err(YamlErr::Unsupported)),
+                Some('?') if blank(self.peek_at(1))=>return Err(self.err(YamlErr::Unsupported)),
+                Some(c) if ",]:#|>%@`".contains(c)=>return Err(self.unexpected()),
+                None=>return Err(self.unexpected()),
+                _=>self.plain_line(true)
+            };
+            if map.contains_key(&key) {
+                return Err(duplicate);
+            }
+            self.skip_flow_space();
+            // a key without a colon has a null value
+            let mut value = Json::Null;
+            if self.peek() == Some(':') {
+                self.advance();
+                self.skip_flow_space();
+                if !matches!(self.peek(),Some(',') | Some('}')) {
+                    value = self.flow_node()?;
+                }
+            }
+            map.insert(key, value);
+            self.skip_flow_space();
+            match self.peek() {
+                Some(',')=>self.advance(),
+                Some('}')=>{},
+                _=>return Err(self.unexpected())
+            }
+        }
+    }
+    // a line break inside a quoted scalar folds into a space, or into
+    // line feeds for the blank lines that follow it
+    fn fold(&mut self,out:&mut String) {
+        let len = out.trim_end_matches([' ','\t']).len();
+        out.truncate(len);
+        self.advance();
+        let mut breaks = 0;
+        loop {
+            self.skip_spaces();
+            if self.peek() != Some('\n') {
+                break;
+            }
+            breaks += 1;
+            self.advance();
+        }
+        if breaks == 0 {
+            out.push(' ');
+        }
+        for _ in 0..breaks {
+            out.push('\n');
+        }
+    }
+    fn hex(&mut self,len:usize)->Option<u32> {
+        let mut code = 0;
+        for _ in 0..len {
+            code = code * 16 + self.peek()?.to_digit(16)?;
+            self.advance();
+        }
+        Some(code)
+    }
+    fn escape(&mut self,out:&mut String)->Result<(),YamlErr> {
+        let invalid = self.err(YamlErr::InvalidEscape);
+        self.advance();
+        let c = match self.peek() {
+            Some(c)=>c,
+            None=>return Err(self.unexpected())
+        };
+        self.advance();
+        let decoded = match c {
+            '0'=>'\0',
+            'a'=>'\u{7}',
+            'b'=>'\u{8}',
+            't' | '\t'=>'\t',
+            'n'=>'\n',
+            'v'=>'\u{b}',
+            'f'=>'\u{c}',
+            'r'=>'\r',
+            'e'=>'\u{1b}',
+            ' ' | '"' | '/' | '\\'=>c,
+            'N'=>'\u{85}',
+            '_'=>'\u{a0}',
+            'L'=>'\u{2028}',
+            'P'=>'\u{2029}',
+            // an escaped line break joins the lines without a space
+            '\n'=>{
+                self.skip_spaces();
+                return Ok(());
+            },
+            'x' | 'u' | 'U'=>{
+                let len = match c {
+                    'x'=>2,
+                    'u'=>4,
+                    _=>8
+                };
+                let mut code = self.hex(len).ok_or_else(||invalid.clone())?;
+                // json style surrogate pairs
+                if (0xd800..0xdc00).contains(&code) && self.starts_with("\\u") {
+                    self.advance();
+                    self.advance();
+                    let low = self.hex(4).filter(|low|(0xdc00..0xe000).contains(low)).ok_or_else(||invalid.clone())?;
+                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                }
+                char::from_u32(code).ok_or(invalid)?
+            },
+            _=>return Err(invalid)
+        };
+        out.push(decoded);
+        Ok(())
+    }
+    fn quoted(&mut self)->Result<String,YamlErr> {
+        let quote = self.peek();
+        self.advance();
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None=>return Err(self.unexpected()),
+                Some('\'') if quote == Some('\'') && self.peek_at(1) == Some('\'')=>{
+                    self.advance();
+                    self.advance();
+                    out.push('\'');
+                },
+                c if c == quote=>{
+                    self.advance();
+                    return Ok(out);
+                },
+                Some('\\') if quote == Some('"')=>self.escape(&mut out)?,
+                Some('\n')=>self.fold(&mut out),
+                Some(c)=>{
+                    out.push(c);
+                    self.advance();
+                }
+            }
+        }
+    }
+    // literal (|) and folded (>) scalars, with an optional chomping
+    // indicator and indentation indicator in their header
+    fn block_scalar(&mut self,parent:isize)->Result<Json,YamlErr> {
+        let folded = self.peek() == Some('>');
+        self.advance();
+        let mut chomp = None;
+        let mut explicit = None;
+        for _ in 0..2 {
+            match self.peek() {
+                Some(c @ ('-' | '+')) if chomp.is_none()=>chomp = Some(c),
+                Some(c @ '1'..='9') if explicit.is_none()=>explicit = c.to_digit(10),
+                _=>break
+            }
+            self.advance();
+        }
+        self.end_line()?;
+        let mut indent = explicit.map(|m|parent.max(0) as usize + m as usize);
+        // the text of each line past the indentation, empty for blank ones
+        let mut lines : Vec<String> = vec![];
+        let mut last_break = false;
+        loop {
+            let mut spaces = 0;
+            while self.peek_at(spaces) == Some(' ') {
+                spaces += 1;
+            }
+            let after = self.peek_at(spaces);
+            if after.is_none() && spaces == 0 {
+                break;
+            }
+            if after.is_none() || after == Some('\n') {
+                lines.push(match indent {
+                    Some(indent) if spaces > indent=>" ".repeat(spaces - indent),
+                    _=>String::new()
+                });
+                for _ in 0..=spaces {
+                    self.advance();
+                }
+                continue;
+            }
+            let indent = match indent {
+                Some(indent)=>indent,
+                None if spaces as isize > parent=>*indent.insert(spaces),
+                None=>break
+            };
+            if spaces < indent || self.at_document_end() {
+                break;
+            }
+            for _ in 0..indent {
+                self.advance();
+            }
+            let mut line = String::new();
+            while let Some(c) = self.peek().filter(|c|*c != '\n') {
+                line.push(c);
+                self.advance();
+            }
+            last_break = self.peek() == Some('\n');
+            self.advance();
+            lines.push(line);
+        }
+        let trailing = lines.iter().rev().take_while(|line|line.is_empty()).count();
+        lines.truncate(lines.len() - trailing);
+        let mut text = String::new();
+        if !folded {
+            text = lines.join("\n");
+        }
+        else {
+            let mut breaks = 0;
+            let mut more_indented = false;
+            for line in lines.iter() {
+                if line.is_empty() {
+                    breaks += 1;
+                    continue;
+                }
+                let more = line.starts_with([' ','\t']);
+                if text.is_empty() {
+                    text.push_str(&"\n".repeat(breaks));
+                }
+                else if more || more_indented {
+                    text.push_str(&"\n".repeat(breaks + 1));
+                }
+                else if breaks == 0 {
+                    text.push(' ');
+                }
+                else {
+                    text.push_str(&"\n".repeat(breaks));
+                }
+                text.push_str(line);
+                more_indented = more;
+                breaks = 0;
+            }
+        }
+        match chomp {
+            Some('-')=>{},
+            Some(_)=>text.push_str(&"\n".repeat(trailing + usize::from(!lines.is_empty()))),
+            None=>if !lines.is_empty() && (last_break || trailing > 0) {
+                text.push('\n');
+            }
+        }
+        self.skip_empty_lines()?;
+        Ok(Json::String(text))
+    }
+}
@@ -0,0 +1,89 @@
+use std::borrow::Cow;
+use ryson::{Json,JsonRef,Jerr};
+
+#[test]
+fn borrows_unescaped_strings_and_numbers(){
+    let text = String::from("{\"name\":\"ryson\",\"size\":12.5}");
+    let json = JsonRef::parse(&text).unwrap();
+    match json.get("name").unwrap() {
+        JsonRef::String(Cow::Borrowed(str))=>assert_eq!(*str,"ryson"),
+        other=>panic!("expected a borrowed string, got {:?}",other)
+    }
+    match &json.as_object().unwrap()[0].0 {
+        Cow::Borrowed(key)=>assert_eq!(*key,"name"),
+        other=>panic!("expected a borrowed key, got {:?}",other)
+    }
+    assert_eq!(json.get("size").unwrap().as_number(),Some("12.5"));
+}
+
+#[test]
+fn copies_strings_with_escapes(){
+    let text = String::from("[\"a\\\"b\\u00e9\\n\",\"\\ud83d\\ude00\"]");
+    let json = JsonRef::parse(&text).unwrap();
+    let vec = json.as_array().unwrap();
+    match &vec[0] {
+        JsonRef::String(Cow::Owned(str))=>assert_eq!(str,"a\"bé\n"),
+        other=>panic!("expected an owned string, got {:?}",other)
+    }
+    assert_eq!(vec[1].as_string(),Some("😀"));
+}
+
+#[test]
+fn keeps_member_order_and_last_duplicate_wins(){
+    let json = JsonRef::parse("{\"b\":1,\"a\":2,\"b\":3}").unwrap();
+    let keys : Vec<&str> = json.as_object().unwrap().iter().map(|(k,_)|k.as_ref()).collect();
+    assert_eq!(keys,vec!["b","a","b"]);
+    assert_eq!(json.get("b"),Some(&JsonRef::Number("3")));
+    assert_eq!(json.to_owned(),Json::parse("{\"a\":2,\"b\":3}").unwrap());
+}
+
+#[test]
+fn to_owned_matches_parse(){
+    let texts = [
+        "null",
+        "true",
+        "0",
+        "  [ 1, \"two\",\n\t{\"three\" :[false,null]} ]  ",
+        "{\"a\":{\"b\":{\"c\":[[],{}]}},\"d\":\"\\t\\\\\"}",
+        "\"caf\\u00e9\"",
+        "\"a\\/b\"",
+        "{ \"a\" : -1.5e-3 }",
+        "[1E5,-0]\r\n"
+    ];
+    for text in texts.iter() {
+        assert_eq!(JsonRef::parse(text).unwrap().to_owned(),Json::parse(text).unwrap(),"{}",text);
+    }
+}
+
+#[test]
+fn errors_match_parse(){
+    let texts = [
+        "tru",
+        "01",
+        "1.",
+        "1.2.3",
+        "-",
+        "1e+",
+        "\"abc",
+        "\"\\x\"",
+        "\"\\u00zz\"",
+        "[1,,2]",
+        "[1,]",
+        "[1 2]",
+        "[1",
+        "{\"a\" 1}",
+        "{\"a\":}",
+        "{1:2}",
+        "{\"a\":1 \"b\":2}",
+        "{\"a\":1",
+        "null x"
+    ];
+    for text in texts.iter() {
+        assert_eq!(JsonRef::parse(text).unwrap_err(),Json::parse(text).unwrap_err(),"{}",text);
+    }
+}
+
+#[test]
+fn rejects_lone_surrogates(){
+    assert_eq!(JsonRef::parse("\"\\ud83d\"").unwrap_err(),Jerr::InvalidUnicodeSequence(String::from("d83d"),1));
+}
@@ -0,0 +1,160 @@
+use ryson::{BsonErr, Json, json};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn encode(json:&Json)->String {
+    hex::encode_upper(json.to_bson().unwrap())
+}
+
+fn decode(text:&str)->Json {
+    Json::from_bson(&hex::decode(text).unwrap()).unwrap()
+}
+
+fn decode_err(text:&str)->BsonErr {
+    Json::from_bson(&hex::decode(text).unwrap()).unwrap_err()
+}
+
+// levels documents, each holding the next under the key "a"
+fn nested(levels:usize)->Vec<u8> {
+    let mut bytes = vec![];
+    for level in 0..levels {
+        bytes.extend_from_slice(&(5 + 8 * (levels - 1 - level) as i32).to_le_bytes());
+        if level + 1 < levels {
+            bytes.extend_from_slice(&[0x03,b'a',0]);
+        }
+    }
+    bytes.extend(vec![0;levels]);
+    bytes
+}
+
+// extended json documents that read back unchanged, with their BSON
+const ROUND_TRIPS : &[(&str,&str)] = &[
+    ("{\"hello\":\"world\"}","160000000268656C6C6F0006000000776F726C640000"),
+    ("{\"BSON\":[\"awesome\",5.05,1986]}","310000000442534F4E002600000002300008000000617765736F6D65000131003333333333331440103200C20700000000"),
+    ("{\"a\":{\"$oid\":\"56e1fc72e0c917e9c4714161\"}}","1400000007610056E1FC72E0C917E9C471416100"),
+    ("{\"a\":{\"$date\":\"1970-01-01T00:00:00Z\"}}","10000000096100000000000000000000"),
+    ("{\"a\":{\"$date\":\"2012-12-24T12:15:30.501Z\"}}","10000000096100C5D8D6CC3B01000000"),
+    ("{\"a\":{\"$date\":{\"$numberLong\":\"-284643869501\"}}}","10000000096100C33CE7B9BDFFFFFF00"),
+    ("{\"x\":{\"$binary\":{\"base64\":\"//8=\",\"subType\":\"00\"}}}","0F0000000578000200000000FFFF00"),
+    ("{\"x\":{\"$binary\":{\"base64\":\"c//SZESzTGmQ6OfR38A11A==\",\"subType\":\"04\"}}}","1D000000057800100000000473FFD26444B34C6990E8E7D1DFC035D400"),
+    ("{\"a\":{\"$timestamp\":{\"t\":123456789,\"i\":42}}}","100000001161002A00000015CD5B0700"),
+    ("{\"a\":{\"$regularExpression\":{\"pattern\":\"abc\",\"options\":\"im\"}}}","0F0000000B610061626300696D0000"),
+    ("{\"a\":{\"$dbPointer\":{\"$ref\":\"b\",\"$id\":{\"$oid\":\"56e1fc72e0c917e9c4714161\"}}}}","1A0000000C610002000000620056E1FC72E0C917E9C471416100"),
+    ("{\"a\":{\"$code\":\"\"}}","0D0000000D6100010000000000"),
+    ("{\"a\":{\"$code\":\"\",\"$scope\":{}}}","160000000F61000E0000000100000000050000000000"),
+    ("{\"a\":{\"$symbol\":\"x\"}}","0E0000000E610002000000780000"),
+    ("{\"a\":{\"$undefined\":true}}","0800000006610000"),
+    ("{\"a\":{\"$minKey\":1}}","08000000FF610000"),
+    ("{\"a\":{\"$maxKey\":1}}","080000007F610000"),
+    ("{\"d\":1.0}","10000000016400000000000000F03F00"),
+    ("{\"d\":{\"$numberDouble\":\"Infinity\"}}","10000000016400000000000000F07F00"),
+    ("{\"d\":{\"$numberDouble\":\"-Infinity\"}}","10000000016400000000000000F0FF00"),
+    ("{\"d\":{\"$numberDouble\":\"NaN\"}}","10000000016400000000000000F87F00"),
+    ("{\"i\":1,\"l\":2147483648,\"n\":null,\"t\":true,\"f\":false}","220000000866000010690001000000126C0000000080000000000A6E000874000100")
+];
+
+#[test]
+fn encodes_documents(){
+    for (json,bson) in ROUND_TRIPS {
+        assert_eq!(encode(&parse(json)),*bson,"{}",json);
+    }
+}
+
+#[test]
+fn decodes_documents(){
+    for (json,bson) in ROUND_TRIPS {
+        assert_eq!(decode(bson),parse(json),"{}",bson);
+    }
+}
+
+#[test]
+fn relaxed_numbers(){
+    assert_eq!(decode("10000000126100010000000000000000"),json!({"a":1}));
+    assert_eq!(decode("0C0000001069002A00000000"),json!({"i":42}));
+    assert_eq!(decode("10000000016400000000000000008000"),json!({"d":Json::Number(String::from("-0.0"))}));
+    assert_eq!(decode("10000000016400000000000000F43F00"),parse("{\"d\":1.25}"));
+    assert_eq!(encode(&json!({"a":{"$numberLong":"1"}})),"10000000126100010000000000000000");
+    assert_eq!(encode(&json!({"a":{"$numberInt":"-1"}})),"0C000000106100FFFFFFFF00");
+    assert_eq!(encode(&json!({"a":{"$numberDouble":"1"}})),"10000000016100000000000000F03F00");
+    assert_eq!(encode(&json!({"a":-1})),"0C000000106100FFFFFFFF00");
+    assert_eq!(encode(&json!({"a":Json::Number(String::from("2e3"))})),"100000000161000000000000409F4000");
+    let json = json!({"a":{"b":[Json::Number(String::from("1e400"))]}});
+    assert_eq!(json.to_bson().unwrap_err(),BsonErr::InvalidNumber(String::from("/a/b/0")));
+}
+
+#[test]
+fn dates(){
+    assert_eq!(encode(&json!({"a":{"$date":{"$numberLong":"0"}}})),"10000000096100000000000000000000");
+    assert_eq!(encode(&json!({"a":{"$date":"2012-12-24T14:15:30.501+02:00"}})),"10000000096100C5D8D6CC3B01000000");
+    assert_eq!(encode(&json!({"a":{"$date":"2012-12-24T12:15:30.5012Z"}})),"10000000096100C5D8D6CC3B01000000");
+    assert_eq!(decode("10000000096100FFFFFFFFFFFFFFFF00"),json!({"a":{"$date":{"$numberLong":"-1"}}}));
+    assert_eq!(decode("1000000009610000DC1FD277E6000000"),json!({"a":{"$date":{"$numberLong":"253402300800000"}}}));
+    assert_eq!(decode("10000000096100FFDB1FD277E6000000"),json!({"a":{"$date":"9999-12-31T23:59:59.999Z"}}));
+    assert_eq!(decode("1000000009610000E0A69ADD00000000"),json!({"a":{"$date":"2000-02-29T00:00:00Z"}}));
+}
+
+#[test]
+fn decimal128(){
+    let cases = [
+        ("0","180000001364000000000000000000000000000000403000"),
+        ("-0","18000000136400000000000000000000000000000040B000"),
+        ("1","180000001364000100000000000000000000000000403000"),
+        ("-1","18000000136400010000000000000000000000000040B000"),
+        ("0.1","1800000013640001000000000000000000000000003E3000"),
+        ("0.001234","18000000136400D204000000000000000000000000343000"),
+        ("1.234E-7","18000000136400D2040000000000000000000000002C3000"),
+        ("1E+3","180000001364000100000000000000000000000000463000"),
+        ("0E+3","180000001364000000000000000000000000000000463000"),
+        ("Infinity","180000001364000000000000000000000000000000007800"),
+        ("-Infinity","18000000136400000000000000000000000000000000F800"),
+        ("NaN","180000001364000000000000000000000000000000007C00"),
+        ("9.999999999999999999999999999999999E+6144","18000000136400FFFFFFFF638E8D37C087ADBE09EDFF5F00"),
+        ("1E-6176","180000001364000100000000000000000000000000000000")
+    ];
+    for (text,bson) in cases.iter() {
+        let json = json!({"d":{"$numberDecimal":*text}});
+        assert_eq!(encode(&json),*bson,"{}",text);
+        assert_eq!(decode(bson),json,"{}",bson);
+    }
+    assert_eq!(encode(&json!({"d":{"$numberDecimal":"12.50"}})),encode(&json!({"d":{"$numberDecimal":"1250E-2"}})));
+    assert_eq!(decode(&encode(&json!({"d":{"$numberDecimal":"1E+6144"}}))),json!({"d":{"$numberDecimal":"1.000000000000000000000000000000000E+6144"}}));
+    let json = json!({"d":{"$numberDecimal":"12345678901234567890123456789012345"}});
+    assert_eq!(json.to_bson().unwrap_err(),BsonErr::InvalidExtendedJson(String::from("/d")));
+}
+
+#[test]
+fn round_trips_plain_json(){
+    let json = parse("{\"name\":\"sensor\",\"readings\":[1,2.5,3.0,70000],\"ok\":true,\"last\":null,\"tags\":{\"x\":\"\u{6c34}\",\"$y\":[]}}");
+    assert_eq!(Json::from_bson(&json.to_bson().unwrap()).unwrap(),json);
+}
+
+#[test]
+fn encoding_errors(){
+    assert_eq!(json!([1]).to_bson().unwrap_err(),BsonErr::NotADocument);
+    assert_eq!(json!({"a":{"$oid":"xyz"}}).to_bson().unwrap_err(),BsonErr::InvalidExtendedJson(String::from("/a")));
+    assert_eq!(json!({"a":[{"$date":"yesterday"}]}).to_bson().unwrap_err(),BsonErr::InvalidExtendedJson(String::from("/a/0")));
+    assert_eq!(json!({"a":{"$binary":{"base64":"!!","subType":"00"}}}).to_bson().unwrap_err(),BsonErr::InvalidExtendedJson(String::from("/a")));
+    assert_eq!(json!({"a":{"b\u{0}":1}}).to_bson().unwrap_err(),BsonErr::InvalidKey(String::from("/a/b\u{0}")));
+    // objects that only look like extended json stay documents
+    assert_eq!(decode(&encode(&json!({"a":{"$oid":1,"b":2}}))),json!({"a":{"$oid":1,"b":2}}));
+    assert_eq!(decode(&encode(&json!({"a":{"$other":1}}))),json!({"a":{"$other":1}}));
+}
+
+#[test]
+fn decoding_errors(){
+    assert_eq!(decode_err(""),BsonErr::UnexpectedEnd);
+    assert_eq!(decode_err("0500000000FF"),BsonErr::TrailingBytes(5));
+    assert_eq!(decode_err("0600000000"),BsonErr::InvalidLength(0));
+    assert_eq!(decode_err("0400000000"),BsonErr::InvalidLength(0));
+    assert_eq!(decode_err("0900000008620002"),BsonErr::InvalidLength(0));
+    assert_eq!(decode_err("090000000862000200"),BsonErr::InvalidValue(4));
+    assert_eq!(decode_err("080000002061000000"),BsonErr::InvalidType(4));
+    assert_eq!(decode_err("0E00000002610002000000FF0000"),BsonErr::InvalidUtf8(7));
+    assert_eq!(decode_err("0E00000002610002000000780100"),BsonErr::InvalidLength(7));
+    assert_eq!(decode_err("0E000000026100FFFFFFFF780000"),BsonErr::InvalidLength(7));
+    assert_eq!(Json::from_bson(&nested(100)).unwrap().to_bson().unwrap(),nested(100));
+    assert_eq!(Json::from_bson(&nested(101)).unwrap_err(),BsonErr::TooDeep(7 * 100));
+    assert_eq!(Json::from_bson(&nested(200000)).unwrap_err(),BsonErr::TooDeep(7 * 100));
+}
@@ -0,0 +1,101 @@
+use ryson::{Json,CanonicalErr,json};
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+fn double(bits:u64)->Json {
+    num(&format!("{:e}",f64::from_bits(bits)))
+}
+
+// RFC 8785 appendix B
+#[test]
+fn number_vectors(){
+    let vectors : [(u64,&str);24] = [
+        (0x0000000000000000,"0"),
+        (0x8000000000000000,"0"),
+        (0x0000000000000001,"5e-324"),
+        (0x8000000000000001,"-5e-324"),
+        (0x7fefffffffffffff,"1.7976931348623157e+308"),
+        (0xffefffffffffffff,"-1.7976931348623157e+308"),
+        (0x4340000000000000,"9007199254740992"),
+        (0xc340000000000000,"-9007199254740992"),
+        (0x4430000000000000,"295147905179352830000"),
+        (0x44b52d02c7e14af5,"9.999999999999997e+22"),
+        (0x44b52d02c7e14af6,"1e+23"),
+        (0x44b52d02c7e14af7,"1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e,"999999999999999700000"),
+        (0x444b1ae4d6e2ef4f,"999999999999999900000"),
+        (0x444b1ae4d6e2ef50,"1e+21"),
+        (0x3eb0c6f7a0b5ed8c,"9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d,"0.000001"),
+        (0x41b3de4355555553,"333333333.3333332"),
+        (0x41b3de4355555554,"333333333.33333325"),
+        (0x41b3de4355555555,"333333333.3333333"),
+        (0x41b3de4355555556,"333333333.3333334"),
+        (0x41b3de4355555557,"333333333.33333343"),
+        (0xbecbf647612f3696,"-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959,"1424953923781206.2")
+    ];
+    for (bits,expected) in vectors.iter() {
+        assert_eq!(double(*bits).to_canonical_string().unwrap(),*expected,"{:016x}",bits);
+    }
+}
+
+#[test]
+fn numbers_are_read_as_doubles(){
+    assert_eq!(num("1E30").to_canonical_string().unwrap(),"1e+30");
+    assert_eq!(num("4.50").to_canonical_string().unwrap(),"4.5");
+    assert_eq!(num("-0.0").to_canonical_string().unwrap(),"0");
+    assert_eq!(num("333333333.33333329").to_canonical_string().unwrap(),"333333333.3333333");
+    assert_eq!(num("9007199254740993").to_canonical_string().unwrap(),"9007199254740992");
+}
+
+#[test]
+fn rejects_numbers_outside_doubles(){
+    let json = json!({"a":[1,num("1e400")]});
+    assert_eq!(json.to_canonical_string().unwrap_err(),CanonicalErr::InvalidNumber(String::from("/a/1")));
+    assert_eq!(num("NaN").to_canonical_string().unwrap_err(),CanonicalErr::InvalidNumber(String::new()));
+}
+
+// RFC 8785 section 3.2.2
+#[test]
+fn structured_example(){
+    let json = json!({
+        "numbers":[num("333333333.33333329"),num("1E30"),num("4.50"),num("2e-3"),num("0.000000000000000000000000001")],
+        "string":"\u{20ac}$\u{000F}\u{000a}A'\u{0042}\u{0022}\u{005c}\\\"/",
+        "literals":[null,true,false]
+    });
+    let expected = "{\"literals\":[null,true,false],\"numbers\":[333333333.3333333,1e+30,4.5,0.002,1e-27],\"string\":\"€$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"}";
+    assert_eq!(json.to_canonical_string().unwrap(),expected);
+}
+
+// RFC 8785 section 3.2.3
+#[test]
+fn keys_sorted_by_utf16_code_units(){
+    let json = json!({
+        "\u{20ac}":"Euro Sign",
+        "\r":"Carriage Return",
+        "\u{fb33}":"Hebrew Letter Dalet With Dagesh",
+        "1":"One",
+        "\u{1f600}":"Emoji: Grinning Face",
+        "\u{0080}":"Control",
+        "\u{00f6}":"Latin Small Letter O With Diaeresis"
+    });
+    let expected = "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{0080}\":\"Control\",\"\u{00f6}\":\"Latin Small Letter O With Diaeresis\",\"\u{20ac}\":\"Euro Sign\",\"\u{1f600}\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}";
+    assert_eq!(json.to_canonical_string().unwrap(),expected);
+}
+
+#[test]
+fn escapes_only_what_is_needed(){
+    let json = json!(["\u{0}\u{8}\u{9}\u{a}\u{c}\u{d}\u{1f}","\u{7f}\u{2028}/<>"]);
+    assert_eq!(json.to_canonical_string().unwrap(),"[\"\\u0000\\b\\t\\n\\f\\r\\u001f\",\"\u{7f}\u{2028}/<>\"]");
+}
+
+#[test]
+fn equal_documents_serialize_identically(){
+    let a = Json::parse("{\"b\":[1,2.50],\"a\":{\"y\":null,\"x\":\"s\"}}").unwrap();
+    let b = Json::parse("{ \"a\" : {\"x\":\"s\",\"y\":null}, \"b\" : [1, 2.5] }").unwrap();
+    assert_eq!(a.to_canonical_string().unwrap(),b.to_canonical_string().unwrap());
+    assert_eq!(a.to_canonical_string().unwrap(),"{\"a\":{\"x\":\"s\",\"y\":null},\"b\":[1,2.5]}");
+}
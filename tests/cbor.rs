@@ -0,0 +1,183 @@
+use ryson::{CborErr, Json, json};
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+fn encode(json:&Json)->String {
+    hex::encode(json.to_cbor().unwrap())
+}
+
+fn decode(text:&str)->Json {
+    Json::from_cbor(&hex::decode(text).unwrap()).unwrap()
+}
+
+fn decode_err(text:&str)->CborErr {
+    Json::from_cbor(&hex::decode(text).unwrap()).unwrap_err()
+}
+
+// integers and floats of RFC 8949 appendix A
+const NUMBERS : &[(&str,&str)] = &[
+    ("0","00"),
+    ("1","01"),
+    ("10","0a"),
+    ("23","17"),
+    ("24","1818"),
+    ("25","1819"),
+    ("100","1864"),
+    ("1000","1903e8"),
+    ("1000000","1a000f4240"),
+    ("1000000000000","1b000000e8d4a51000"),
+    ("18446744073709551615","1bffffffffffffffff"),
+    ("18446744073709551616","c249010000000000000000"),
+    ("-18446744073709551616","3bffffffffffffffff"),
+    ("-18446744073709551617","c349010000000000000000"),
+    ("-1","20"),
+    ("-10","29"),
+    ("-100","3863"),
+    ("-1000","3903e7"),
+    ("-0","f98000"),
+    ("1.1","fb3ff199999999999a"),
+    ("1.5","f93e00"),
+    ("3.4028234663852886e+38","fa7f7fffff"),
+    ("1e+300","fb7e37e43c8800759c"),
+    ("5.960464477539063e-8","f90001"),
+    ("0.00006103515625","f90400"),
+    ("-4.1","fbc010666666666666")
+];
+
+#[test]
+fn encodes_numbers(){
+    for (text,cbor) in NUMBERS {
+        assert_eq!(encode(&num(text)),*cbor,"{}",text);
+    }
+}
+
+#[test]
+fn decodes_numbers(){
+    for (text,cbor) in NUMBERS {
+        assert_eq!(decode(cbor),num(text),"{}",cbor);
+    }
+    assert_eq!(decode("f90000"),num("0"));
+    assert_eq!(decode("f93c00"),num("1"));
+    assert_eq!(decode("f97bff"),num("65504"));
+    assert_eq!(decode("fa47c35000"),num("100000"));
+    assert_eq!(decode("f9c400"),num("-4"));
+    assert_eq!(decode("fbc010666666666666"),num("-4.1"));
+    assert_eq!(decode("c11a514b67b0"),num("1363896240"));
+    assert_eq!(decode("c1fb41d452d9ec200000"),num("1363896240.5"));
+}
+
+#[test]
+fn non_finite_and_simple_values_become_null(){
+    for cbor in ["f97c00","f97e00","f9fc00","fa7f800000","fa7fc00000","fb7ff0000000000000","f7","f0","f8ff"].iter() {
+        assert_eq!(decode(cbor),Json::Null,"{}",cbor);
+    }
+    assert_eq!(decode("f4"),Json::Bool(false));
+    assert_eq!(decode("f5"),Json::Bool(true));
+    assert_eq!(decode("f6"),Json::Null);
+}
+
+#[test]
+fn decimal_fractions_keep_the_exact_value(){
+    assert_eq!(decode("c48221196ab3"),num("273.15"));
+    assert_eq!(decode("c4822203"),num("0.003"));
+    assert_eq!(decode("c482023863"),num("-100e2"));
+    for text in ["1.50","0.1000000000000000000001","-0.050","123456789012345678901234567890.5","1.0"].iter() {
+        let cbor = num(text).to_cbor().unwrap();
+        assert_eq!(cbor[0],0xc4,"{}",text);
+        assert_eq!(Json::from_cbor(&cbor).unwrap(),num(text));
+    }
+    let cbor = num("1.5e-3000").to_cbor().unwrap();
+    assert_eq!(Json::from_cbor(&cbor).unwrap(),num("15e-3001"));
+}
+
+#[test]
+fn strings(){
+    assert_eq!(decode("60"),Json::from(""));
+    assert_eq!(decode("6161"),Json::from("a"));
+    assert_eq!(decode("6449455446"),Json::from("IETF"));
+    assert_eq!(decode("62225c"),Json::from("\"\\"));
+    assert_eq!(decode("62c3bc"),Json::from("\u{fc}"));
+    assert_eq!(decode("63e6b0b4"),Json::from("\u{6c34}"));
+    assert_eq!(decode("64f0908591"),Json::from("\u{10151}"));
+    assert_eq!(decode("7f657374726561646d696e67ff"),Json::from("streaming"));
+    for text in ["","a","IETF","\"\\","\u{fc}","\u{6c34}","\u{10151}"].iter() {
+        assert_eq!(decode(&encode(&Json::from(*text))),Json::from(*text));
+    }
+    assert_eq!(encode(&Json::from("IETF")),"6449455446");
+}
+
+#[test]
+fn byte_strings_become_text(){
+    assert_eq!(decode("40"),Json::from(""));
+    assert_eq!(decode("4401020304"),Json::from("AQIDBA"));
+    assert_eq!(decode("5f42010243030405ff"),Json::from("AQIDBAU"));
+    assert_eq!(decode("d74401020304"),Json::from("01020304"));
+    assert_eq!(decode("d6434dfbff"),Json::from("Tfv/"));
+    assert_eq!(decode("d5434dfbff"),Json::from("Tfv_"));
+    assert_eq!(decode("d818456449455446"),Json::from("ZElFVEY"));
+    assert_eq!(decode("d6824101d7814102"),json!(["AQ==",["02"]]));
+}
+
+#[test]
+fn containers(){
+    assert_eq!(decode("80"),json!([]));
+    assert_eq!(decode("83010203"),json!([1,2,3]));
+    assert_eq!(decode("8301820203820405"),json!([1,[2,3],[4,5]]));
+    assert_eq!(decode("98190102030405060708090a0b0c0d0e0f101112131415161718181819"),Json::Array((1..=25).map(Json::from).collect()));
+    assert_eq!(decode("a0"),json!({}));
+    assert_eq!(decode("a201020304"),json!({"1":2,"3":4}));
+    assert_eq!(decode("a26161016162820203"),json!({"a":1,"b":[2,3]}));
+    assert_eq!(decode("826161a161626163"),json!(["a",{"b":"c"}]));
+    assert_eq!(decode("a56161614161626142616361436164614461656145"),json!({"a":"A","b":"B","c":"C","d":"D","e":"E"}));
+    assert_eq!(decode("9fff"),json!([]));
+    assert_eq!(decode("9f018202039f0405ffff"),json!([1,[2,3],[4,5]]));
+    assert_eq!(decode("9f01820203820405ff"),json!([1,[2,3],[4,5]]));
+    assert_eq!(decode("83018202039f0405ff"),json!([1,[2,3],[4,5]]));
+    assert_eq!(decode("bf61610161629f0203ffff"),json!({"a":1,"b":[2,3]}));
+    assert_eq!(decode("826161bf61626163ff"),json!(["a",{"b":"c"}]));
+    assert_eq!(decode("bf6346756ef563416d7421ff"),json!({"Fun":true,"Amt":-2}));
+    assert_eq!(decode("c074323031332d30332d32315432303a30343a30305a"),Json::from("2013-03-21T20:04:00Z"));
+    assert_eq!(decode("a2f5f4f6f5"),json!({"true":false,"null":true}));
+    assert_eq!(decode("a2a1616b01f5820161610a"),json!({"{\"k\":1}":true,"[1,\"a\"]":10}));
+}
+
+#[test]
+fn encodes_maps_in_deterministic_order(){
+    assert_eq!(encode(&json!({"b":[2,3],"a":1})),"a26161016162820203");
+    assert_eq!(encode(&json!({"bb":null,"c":true,"a":false})),"a36161f46163f5626262f6");
+    assert_eq!(encode(&json!([1,[2,3],[4,5]])),"8301820203820405");
+}
+
+#[test]
+fn round_trips_documents(){
+    let json = Json::parse("{\"name\":\"sensor\",\"readings\":[1,2.5,18446744073709551616,12.125,1.50],\"ok\":true,\"last\":null,\"tags\":{\"x\":\"\u{6c34}\"}}").unwrap();
+    assert_eq!(Json::from_cbor(&json.to_cbor().unwrap()).unwrap(),json);
+}
+
+#[test]
+fn errors(){
+    assert_eq!(decode_err(""),CborErr::UnexpectedEnd);
+    assert_eq!(decode_err("1a0001"),CborErr::UnexpectedEnd);
+    assert_eq!(decode_err("8301"),CborErr::UnexpectedEnd);
+    assert_eq!(decode_err("0102"),CborErr::TrailingBytes(1));
+    assert_eq!(decode_err("1c"),CborErr::InvalidHeader(0));
+    assert_eq!(decode_err("8101ff"),CborErr::TrailingBytes(2));
+    assert_eq!(decode_err("82ff"),CborErr::InvalidHeader(1));
+    assert_eq!(decode_err("1f"),CborErr::InvalidHeader(0));
+    assert_eq!(decode_err("f818"),CborErr::InvalidHeader(0));
+    assert_eq!(decode_err("8162c328"),CborErr::InvalidUtf8(1));
+    assert_eq!(decode_err("5f6161ff"),CborErr::InvalidHeader(1));
+    assert_eq!(decode_err("c26161"),CborErr::InvalidTag(0));
+    assert_eq!(decode_err("81c48101"),CborErr::InvalidTag(1));
+    assert_eq!(decode_err("c4823b7fffffffffffffff01"),CborErr::InvalidTag(0));
+    assert_eq!(decode_err("5bffffffffffffffff"),CborErr::UnexpectedEnd);
+    assert_eq!(Json::from_cbor(&vec![0x81;200000]).unwrap_err(),CborErr::TooDeep(256));
+    assert_eq!(Json::from_cbor(&vec![0xc6;200000]).unwrap_err(),CborErr::TooDeep(256));
+    let mut bytes = vec![0x81;255];
+    bytes.push(0x01);
+    assert_eq!(Json::from_cbor(&bytes).unwrap().to_cbor().unwrap(),bytes);
+    let json = json!({"a":[1,Json::Number(String::from("1."))]});
+    assert_eq!(json.to_cbor().unwrap_err(),CborErr::InvalidNumber(String::from("/a/1")));
+}
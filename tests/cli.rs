@@ -0,0 +1,144 @@
+use std::{env, fs, io::Write, path::PathBuf, process::{Command, Output, Stdio}};
+
+fn ryson(args:&[&str],stdin:&str)->Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ryson"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn temp_file(name:&str,content:&str)->PathBuf {
+    let path = env::temp_dir().join(format!("ryson-cli-{}-{}",std::process::id(),name));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn stdout(output:&Output)->String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output:&Output)->String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn validate_reports_line_and_column(){
+    let output = ryson(&["validate"],"{\n  \"a\": 1\n  \"b\": 2\n}");
+    assert_eq!(output.status.code(),Some(1));
+    assert_eq!(stderr(&output),"<stdin>:3:3: expected ',' or the end of the container\n");
+    let output = ryson(&["validate"],"  [1,2");
+    assert_eq!(stderr(&output),"<stdin>:1:7: unexpected end of input\n");
+    let output = ryson(&["validate"],"[1,\n \"a\\x\"]");
+    assert_eq!(stderr(&output),"<stdin>:2:4: unknown escape '\\x'\n");
+    let output = ryson(&["validate"],"[1,\n 01]");
+    assert_eq!(stderr(&output),"<stdin>:2:2: invalid token '01'\n");
+    let output = ryson(&["validate"],"\n\"\\u00e9\\uzzzz\"");
+    assert_eq!(stderr(&output),"<stdin>:2:8: invalid unicode escape '\\uzzzz'\n");
+    let output = ryson(&["validate"],"{\"a\":[true]}");
+    assert_eq!(output.status.code(),Some(0));
+    assert_eq!(stdout(&output),"");
+}
+
+#[test]
+fn validate_rejects_empty_input(){
+    let output = ryson(&["validate"],"");
+    assert_eq!(output.status.code(),Some(1));
+    assert_eq!(stderr(&output),"<stdin>:1:1: unexpected end of input\n");
+    let output = ryson(&["validate"]," \n  ");
+    assert_eq!(output.status.code(),Some(1));
+    assert_eq!(stderr(&output),"<stdin>:2:3: unexpected end of input\n");
+}
+
+#[test]
+fn validate_accepts_the_full_number_and_white_space_grammar(){
+    for text in ["{\"a\":-1}","1e5","{\r\n  \"a\": 2.5E-3\r\n}\r\n","{ }"].iter() {
+        let output = ryson(&["validate"],text);
+        assert_eq!(output.status.code(),Some(0),"{}",text);
+        assert_eq!(stderr(&output),"");
+    }
+}
+
+#[test]
+fn validate_decodes_surrogate_pairs(){
+    let output = ryson(&["validate"],"[\"\\ud83d\\ude00\"]");
+    assert_eq!(output.status.code(),Some(0));
+    let output = ryson(&["minify"],"[\"\\ud83d\\ude00\"]");
+    assert_eq!(stdout(&output),"[\"\u{1F600}\"]\n");
+    let output = ryson(&["validate"],"[\"\\ud83d\\u0041\"]");
+    assert_eq!(output.status.code(),Some(1));
+    assert_eq!(stderr(&output),"<stdin>:1:3: invalid unicode escape '\\ud83d0041'\n");
+}
+
+#[test]
+fn validate_checks_every_file(){
+    let good = temp_file("good.json","[1]");
+    let bad = temp_file("bad.json","[1,]");
+    let output = ryson(&["validate",good.to_str().unwrap(),bad.to_str().unwrap()],"");
+    assert_eq!(output.status.code(),Some(1));
+    assert_eq!(stderr(&output),format!("{}:1:4: expected a value\n",bad.to_str().unwrap()));
+    let output = ryson(&["validate","/nonexistent/ryson.json"],"");
+    assert_eq!(output.status.code(),Some(2));
+}
+
+#[test]
+fn format_pretty_prints(){
+    let output = ryson(&["format","--sort-keys"],"{\"b\":[1,{}],\"a\":{\"c\":\"x\\ny\"}}");
+    assert_eq!(output.status.code(),Some(0));
+    assert_eq!(stdout(&output),"{\n  \"a\": {\n    \"c\": \"x\\ny\"\n  },\n  \"b\": [\n    1,\n    {}\n  ]\n}\n");
+    let output = ryson(&["format","--indent","4"],"[[]]");
+    assert_eq!(stdout(&output),"[\n    []\n]\n");
+}
+
+#[test]
+fn minify_writes_one_line(){
+    let output = ryson(&["minify","--sort-keys","-"],"{ \"b\" : [ 1 , 2 ],\n \"a\" : null }");
+    assert_eq!(stdout(&output),"{\"a\":null,\"b\":[1,2]}\n");
+}
+
+#[test]
+fn rewrites_files_in_place(){
+    let path = temp_file("in-place.json","{ \"z\" : 1 , \"y\" : [ ] }");
+    let output = ryson(&["minify","--sort-keys","-i",path.to_str().unwrap()],"");
+    assert_eq!(output.status.code(),Some(0));
+    assert_eq!(stdout(&output),"");
+    assert_eq!(fs::read_to_string(&path).unwrap(),"{\"y\":[],\"z\":1}\n");
+}
+
+#[test]
+fn rejects_bad_usage(){
+    assert_eq!(ryson(&[],"").status.code(),Some(2));
+    assert_eq!(ryson(&["frob"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["format","--bogus"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["validate","-i","x.json"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["format","--in-place"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["minify","--indent","2"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["--help"],"").status.code(),Some(0));
+}
+
+#[test]
+fn query_prints_each_output(){
+    let output = ryson(&["query",".items[] | {name}"],"{\"items\":[{\"name\":\"a\",\"n\":1},{\"name\":\"b\"}]}");
+    assert_eq!(output.status.code(),Some(0));
+    assert_eq!(stdout(&output),"{\n  \"name\": \"a\"\n}\n{\n  \"name\": \"b\"\n}\n");
+    let output = ryson(&["query","map(.n * 2)","-c"],"[{\"n\":1},{\"n\":1.5}]");
+    assert_eq!(stdout(&output),"[2,3]\n");
+    let output = ryson(&["query","--compact",".[] | select(. > 1)"],"[1,2,3]");
+    assert_eq!(stdout(&output),"2\n3\n");
+}
+
+#[test]
+fn query_reports_errors(){
+    let output = ryson(&["query",".a | keys"],"{\"a\":1}");
+    assert_eq!(output.status.code(),Some(1));
+    assert_eq!(stderr(&output),"<stdin>: keys cannot be applied to number\n");
+    let output = ryson(&["query",".a |"],"{}");
+    assert_eq!(output.status.code(),Some(2));
+    assert_eq!(ryson(&["query"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["query",".","-i","x.json"],"").status.code(),Some(2));
+    assert_eq!(ryson(&["format","-c"],"").status.code(),Some(2));
+}
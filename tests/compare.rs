@@ -0,0 +1,64 @@
+use ryson::{Json,Difference,compare,render_diff,assert_json_eq};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+#[test]
+fn equal_documents_have_no_differences(){
+    let a = parse("{\"a\":[1,2,{\"b\":null}]}");
+    assert!(compare(&a, &a.clone()).is_empty());
+}
+
+#[test]
+fn numbers_compared_by_value(){
+    assert!(compare(&parse("[1.0,2.50]"), &parse("[1,2.5]")).is_empty());
+}
+
+#[test]
+fn lists_changes_by_path(){
+    let a = parse("{\"port\":80,\"old\":true,\"hosts\":[\"a\",\"b\"]}");
+    let b = parse("{\"port\":443,\"new\":\"x\",\"hosts\":[\"a\"]}");
+    assert_eq!(compare(&a, &b),vec![
+        Difference::Removed{path:String::from("/hosts/1"),value:Json::String(String::from("b"))},
+        Difference::Added{path:String::from("/new"),value:Json::String(String::from("x"))},
+        Difference::Removed{path:String::from("/old"),value:Json::Bool(true)},
+        Difference::Changed{path:String::from("/port"),left:num("80"),right:num("443")}
+    ]);
+}
+
+#[test]
+fn type_change_is_a_single_difference(){
+    assert_eq!(compare(&parse("{\"a\":[1]}"), &parse("{\"a\":{}}")),vec![
+        Difference::Changed{path:String::from("/a"),left:parse("[1]"),right:parse("{}")}
+    ]);
+}
+
+#[test]
+fn renders_unified_lines(){
+    let a = parse("{\"port\":80,\"a/b\":true}");
+    let b = parse("{\"port\":443}");
+    let text = render_diff(&compare(&a, &b), false);
+    assert_eq!(text,"- /a~1b: true\n- /port: 80\n+ /port: 443\n");
+}
+
+#[test]
+fn renders_root_and_colors(){
+    let text = render_diff(&compare(&Json::Null, &Json::Bool(true)), true);
+    assert_eq!(text,"\x1b[31m- (root): null\x1b[0m\n\x1b[32m+ (root): true\x1b[0m\n");
+}
+
+#[test]
+fn assert_json_eq_passes_on_equal_values(){
+    assert_json_eq!(parse("{\"a\":1.0}"),parse("{\"a\":1}"));
+}
+
+#[test]
+#[should_panic(expected = "- /a: 1\n+ /a: 2")]
+fn assert_json_eq_panics_with_diff(){
+    assert_json_eq!(parse("{\"a\":1}"),parse("{\"a\":2}"));
+}
@@ -0,0 +1,113 @@
+use ryson::{CsvArrays, CsvErr, CsvOptions, Json};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn inferring()->CsvOptions {
+    CsvOptions{infer_types:true,..CsvOptions::default()}
+}
+
+#[test]
+fn writes_header_and_rows(){
+    let json = parse("[{\"name\":\"ann\",\"age\":31},{\"name\":\"bob\",\"age\":17,\"admin\":true}]");
+    assert_eq!(json.to_csv().unwrap(),"age,name,admin\r\n31,ann,\r\n17,bob,true\r\n");
+}
+
+#[test]
+fn flattens_nested_keys(){
+    let json = parse("[{\"id\":1,\"user\":{\"name\":\"ann\",\"address\":{\"city\":\"Oslo\"}}}]");
+    assert_eq!(json.to_csv().unwrap(),"id,user.address.city,user.name\r\n1,Oslo,ann\r\n");
+}
+
+#[test]
+fn quotes_fields(){
+    let json = parse("[{\"a\":\"x,y\",\"b\":\"say \\\"hi\\\"\",\"c\":\"two\\nlines\",\"d\":\" plain \"}]");
+    assert_eq!(json.to_csv().unwrap(),"a,b,c,d\r\n\"x,y\",\"say \"\"hi\"\"\",\"two\nlines\", plain \r\n");
+    let options = CsvOptions{delimiter:';',..CsvOptions::default()};
+    assert_eq!(json.to_csv_with(&options).unwrap(),"a;b;c;d\r\nx,y;\"say \"\"hi\"\"\";\"two\nlines\"; plain \r\n");
+    assert_eq!(Json::from_csv(&json.to_csv().unwrap()).unwrap(),json);
+    assert_eq!(Json::from_csv_with(&json.to_csv_with(&options).unwrap(), &options).unwrap(),json);
+}
+
+#[test]
+fn array_handling(){
+    let json = parse("[{\"id\":1,\"tags\":[\"a\",\"b\"]},{\"id\":2,\"tags\":[\"c\"]}]");
+    assert_eq!(json.to_csv().unwrap(),"id,tags.0,tags.1\r\n1,a,b\r\n2,c,\r\n");
+    let options = CsvOptions{arrays:CsvArrays::Json,..CsvOptions::default()};
+    assert_eq!(json.to_csv_with(&options).unwrap(),"id,tags\r\n1,\"[\"\"a\"\",\"\"b\"\"]\"\r\n2,\"[\"\"c\"\"]\"\r\n");
+    let options = CsvOptions{arrays:CsvArrays::Join(String::from("|")),..CsvOptions::default()};
+    assert_eq!(json.to_csv_with(&options).unwrap(),"id,tags\r\n1,a|b\r\n2,c\r\n");
+    let json = parse("[{\"tags\":[\"a\",{\"k\":\"v\"},[1]]}]");
+    assert_eq!(json.to_csv_with(&options).unwrap(),"tags\r\n\"a|{\"\"k\"\":\"\"v\"\"}|[1]\"\r\n");
+    let json = parse("[{\"points\":[{\"x\":1},{\"x\":2}],\"empty\":[],\"none\":{}}]");
+    assert_eq!(json.to_csv().unwrap(),"empty,none,points.0.x,points.1.x\r\n[],{},1,2\r\n");
+}
+
+#[test]
+fn missing_values_are_empty(){
+    let json = parse("[{\"a\":1},{\"b\":null},{\"a\":2,\"c\":false}]");
+    assert_eq!(json.to_csv().unwrap(),"a,b,c\r\n1,,\r\n,,\r\n2,,false\r\n");
+    assert_eq!(Json::Array(vec![]).to_csv().unwrap(),"\r\n");
+}
+
+#[test]
+fn reads_rows_as_strings(){
+    let text = "name,age,address.city\r\nann,31,Oslo\nbob,,\"New\r\nYork\"\n";
+    assert_eq!(Json::from_csv(text).unwrap(),parse("[{\"name\":\"ann\",\"age\":\"31\",\"address\":{\"city\":\"Oslo\"}},{\"name\":\"bob\",\"age\":\"\",\"address\":{\"city\":\"New\\r\\nYork\"}}]"));
+    assert_eq!(Json::from_csv("").unwrap(),Json::Array(vec![]));
+    assert_eq!(Json::from_csv("a,b\n\n1,2").unwrap(),parse("[{\"a\":\"1\",\"b\":\"2\"}]"));
+}
+
+#[test]
+fn infers_types(){
+    let text = "n,f,t,z,s,e,x\n1,2.5,true,null,01,,false";
+    let expected = parse("[{\"n\":1,\"f\":2.5,\"t\":true,\"z\":null,\"s\":\"01\",\"e\":null,\"x\":false}]");
+    assert_eq!(Json::from_csv_with(text, &inferring()).unwrap(),expected);
+    let result = Json::from_csv_with("n,s\n-3,1e5x", &inferring()).unwrap();
+    assert_eq!(result[0]["n"],Json::Number(String::from("-3")));
+    assert_eq!(result[0]["s"],Json::from("1e5x"));
+}
+
+#[test]
+fn round_trips_arrays_of_objects(){
+    let json = parse("[{\"id\":1,\"user\":{\"name\":\"ann\",\"tags\":[\"a\",\"b\"]},\"ok\":true},{\"id\":2,\"user\":{\"name\":\"bob\",\"tags\":[\"c\",\"d\"]},\"ok\":false}]");
+    assert_eq!(Json::from_csv_with(&json.to_csv().unwrap(), &inferring()).unwrap(),json);
+    let options = CsvOptions{arrays:CsvArrays::Json,infer_types:true,..CsvOptions::default()};
+    let json = parse("[{\"id\":1,\"tags\":[\"a\",[2]],\"empty\":{}}]");
+    assert_eq!(Json::from_csv_with(&json.to_csv_with(&options).unwrap(), &options).unwrap(),json);
+    let json = parse("[{\"t\":[\"c\\\"d\",{\"k\":1}]}]");
+    let text = json.to_csv_with(&options).unwrap();
+    assert_eq!(text,"t\r\n\"[\"\"c\\\"\"d\"\",{\"\"k\"\":1}]\"\r\n");
+    assert_eq!(Json::from_csv_with(&text, &options).unwrap(),json);
+}
+
+#[test]
+fn escapes_keys_like_flatten(){
+    let json = parse("[{\"a.b\":\"1\",\"0\":\"x\",\"c\":{\"d\\\\e\":\"2\"}}]");
+    let text = json.to_csv().unwrap();
+    assert_eq!(text,"\\0,a\\.b,c.d\\\\e\r\nx,1,2\r\n");
+    assert_eq!(Json::from_csv(&text).unwrap(),json);
+    let json = parse("[{\"k\":{\"1\":[\"a\"]}}]");
+    let options = CsvOptions{arrays:CsvArrays::Json,..CsvOptions::default()};
+    let text = json.to_csv_with(&options).unwrap();
+    assert_eq!(text,"k.\\1\r\n\"[\"\"a\"\"]\"\r\n");
+    assert_eq!(Json::from_csv_with(&text, &options).unwrap(),json);
+}
+
+#[test]
+fn errors(){
+    assert_eq!(parse("{}").to_csv().unwrap_err(),CsvErr::NotAnArray);
+    assert_eq!(parse("[{},1]").to_csv().unwrap_err(),CsvErr::NotAnObject(1));
+    assert_eq!(Json::from_csv("a,b\n1,2\n3").unwrap_err(),CsvErr::FieldCount(3));
+    assert_eq!(Json::from_csv("a\n\"x\ny").unwrap_err(),CsvErr::UnterminatedQuote(2));
+    assert_eq!(Json::from_csv("a\n\"x\"y").unwrap_err(),CsvErr::UnexpectedQuote(2));
+    assert_eq!(Json::from_csv("a\nx\"y\"").unwrap_err(),CsvErr::UnexpectedQuote(2));
+    assert_eq!(Json::from_csv("a,a.b\n1,2").unwrap_err(),CsvErr::ConflictingColumn(String::from("a.b")));
+    assert_eq!(Json::from_csv("a,a\n1,2").unwrap_err(),CsvErr::ConflictingColumn(String::from("a")));
+    assert_eq!(Json::from_csv("a,a.b\n1,").unwrap(),parse("[{\"a\":\"1\"}]"));
+    // indices stay below the number of columns
+    assert_eq!(Json::from_csv("a.99999999999\n1").unwrap_err(),CsvErr::ConflictingColumn(String::from("a.99999999999")));
+    assert_eq!(Json::from_csv("a.1,b\n1,2").unwrap(),parse("[{\"a\":[null,\"1\"],\"b\":\"2\"}]"));
+    assert_eq!(Json::from_csv("a.0,a.99999999999\n1,").unwrap(),parse("[{\"a\":[\"1\"]}]"));
+}
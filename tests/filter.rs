@@ -0,0 +1,101 @@
+use ryson::{Filter, FilterErr, Json};
+
+fn run(filter:&str,input:&str)->Vec<Json> {
+    Json::parse(input).unwrap().filter(filter).unwrap()
+}
+
+fn parse_all(texts:&[&str])->Vec<Json> {
+    texts.iter().map(|text|Json::parse(text).unwrap()).collect()
+}
+
+#[test]
+fn paths(){
+    let input = "{\"a\":{\"b\":[10,20,30]},\"c d\":true}";
+    assert_eq!(run(".",input),parse_all(&[input]));
+    assert_eq!(run(".a.b",input),parse_all(&["[10,20,30]"]));
+    assert_eq!(run(".a.b[1]",input),parse_all(&["20"]));
+    assert_eq!(run(".a.b[-1]",input),parse_all(&["30"]));
+    assert_eq!(run(".a.b[5]",input),parse_all(&["null"]));
+    assert_eq!(run(".[\"c d\"]",input),parse_all(&["true"]));
+    assert_eq!(run(".\"c d\"",input),parse_all(&["true"]));
+    assert_eq!(run(".missing.deeper",input),parse_all(&["null"]));
+    assert_eq!(run(".a.b[1:]",input),parse_all(&["[20,30]"]));
+    assert_eq!(run(".a.b[:-1]",input),parse_all(&["[10,20]"]));
+}
+
+#[test]
+fn iteration_and_pipes(){
+    let input = "[{\"name\":\"x\",\"n\":1},{\"name\":\"y\",\"n\":2}]";
+    assert_eq!(run(".[].name",input),parse_all(&["\"x\"","\"y\""]));
+    assert_eq!(run(".[] | .n",input),parse_all(&["1","2"]));
+    assert_eq!(run("map(.n)",input),parse_all(&["[1,2]"]));
+    assert_eq!(run(".[0].name, .[1].n",input),parse_all(&["\"x\"","2"]));
+    assert_eq!(run("[.[] | select(.n > 1) | .name]",input),parse_all(&["[\"y\"]"]));
+    assert_eq!(run("{\"b\":2,\"a\":1} | .[]","null"),parse_all(&["1","2"]));
+    assert_eq!(run("[..]","[[1]]"),parse_all(&["[[[1]],[1],1]"]));
+}
+
+#[test]
+fn construction(){
+    let input = "{\"user\":\"ann\",\"titles\":[\"a\",\"b\"]}";
+    assert_eq!(run("{user, title: .titles[]}",input),parse_all(&[
+        "{\"user\":\"ann\",\"title\":\"a\"}",
+        "{\"user\":\"ann\",\"title\":\"b\"}"
+    ]));
+    assert_eq!(run("{(.user): 1}",input),parse_all(&["{\"ann\":1}"]));
+    assert_eq!(run("[.titles[], .user]",input),parse_all(&["[\"a\",\"b\",\"ann\"]"]));
+    assert_eq!(run("[]",input),parse_all(&["[]"]));
+}
+
+#[test]
+fn arithmetic_and_comparison(){
+    assert_eq!(run(".a + .b * 2","{\"a\":1,\"b\":2.5}"),parse_all(&["6"]));
+    assert_eq!(run("(.a - 4) / 2, -.a, .a % 2","{\"a\":7}"),vec![Json::Number(String::from("1.5")),Json::Number(String::from("-7")),Json::Number(String::from("1"))]);
+    assert_eq!(run(".a + .b","{\"a\":\"x\",\"b\":\"y\"}"),parse_all(&["\"xy\""]));
+    assert_eq!(run(". + [3]","[1,2]"),parse_all(&["[1,2,3]"]));
+    assert_eq!(run(". - [2]","[1,2,3,2]"),parse_all(&["[1,3]"]));
+    assert_eq!(run(". + null","4"),parse_all(&["4"]));
+    assert_eq!(run(".a == 1.0, .a != 1, .a < 2, .a >= 2","{\"a\":1}"),parse_all(&["true","false","true","false"]));
+    assert_eq!(run("\"a\" < \"b\", [1] < [1,0], null < false","null"),parse_all(&["true","true","true"]));
+    assert_eq!(run(".a and .b, .a or .b, (.b | not)","{\"a\":true,\"b\":null}"),parse_all(&["false","true","true"]));
+}
+
+#[test]
+fn builtins(){
+    assert_eq!(run("length","\"héllo\""),parse_all(&["5"]));
+    assert_eq!(run("map(length)","[[1,2],{\"a\":1},null,\"\"]"),parse_all(&["[2,1,0,0]"]));
+    assert_eq!(run("keys","{\"b\":1,\"a\":2}"),parse_all(&["[\"a\",\"b\"]"]));
+    assert_eq!(run("keys","[true,false]"),parse_all(&["[0,1]"]));
+    assert_eq!(run("map(type)","[null,true,1,\"s\",[],{}]"),parse_all(&["[\"null\",\"boolean\",\"number\",\"string\",\"array\",\"object\"]"]));
+    assert_eq!(run("add","[1,2,3]"),parse_all(&["6"]));
+    assert_eq!(run("add","[]"),parse_all(&["null"]));
+    assert_eq!(run("sort","[3,\"a\",null,1]"),parse_all(&["[null,1,3,\"a\"]"]));
+    assert_eq!(run("empty","1"),vec![]);
+}
+
+#[test]
+fn errors(){
+    assert_eq!(Filter::parse(".a |").unwrap_err(),FilterErr::UnexpectedEnd);
+    assert_eq!(Filter::parse(".a ]").unwrap_err(),FilterErr::UnexpectedChar(3));
+    assert_eq!(Filter::parse("frob").unwrap_err(),FilterErr::UnknownFunction(String::from("frob/0")));
+    assert_eq!(Filter::parse("map").unwrap_err(),FilterErr::UnknownFunction(String::from("map/0")));
+    assert_eq!(Filter::parse("\"a\\q\"").unwrap_err(),FilterErr::InvalidEscape(2));
+    assert_eq!(Filter::parse("\"\\ud83d\"").unwrap_err(),FilterErr::InvalidEscape(1));
+    assert_eq!(Filter::parse("\"\\u12\"").unwrap_err(),FilterErr::InvalidEscape(5));
+    assert_eq!(Filter::parse("\"ab").unwrap_err(),FilterErr::UnexpectedEnd);
+    let json = Json::parse("{\"a\":[1],\"s\":\"x\"}").unwrap();
+    assert_eq!(json.filter(".a.b").unwrap_err(),FilterErr::CannotIndex(String::from("array"),String::from("string")));
+    assert_eq!(json.filter(".s[]").unwrap_err(),FilterErr::CannotIterate(String::from("string")));
+    assert_eq!(json.filter(".s + 1").unwrap_err(),FilterErr::InvalidOperands(String::from("+"),String::from("string"),String::from("number")));
+    assert_eq!(json.filter(".a[0] / 0").unwrap_err(),FilterErr::DivisionByZero);
+    assert_eq!(json.filter("{(.a): 1}").unwrap_err(),FilterErr::InvalidKey(String::from("array")));
+    assert_eq!(json.filter(".a.b?").unwrap(),vec![]);
+    assert_eq!(json.filter("[.[] | .[0]?]").unwrap(),parse_all(&["[1]"]));
+}
+
+#[test]
+fn reusable_filter(){
+    let filter = Filter::parse(".n * 2").unwrap();
+    assert_eq!(filter.apply(&Json::parse("{\"n\":2}").unwrap()).unwrap(),parse_all(&["4"]));
+    assert_eq!(filter.apply(&Json::parse("{\"n\":1.5}").unwrap()).unwrap(),parse_all(&["3"]));
+}
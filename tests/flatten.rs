@@ -0,0 +1,71 @@
+use ryson::{FlattenErr, FlattenOptions, IndexNotation, Json, json};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn brackets()->FlattenOptions {
+    FlattenOptions{index:IndexNotation::Bracket,..FlattenOptions::default()}
+}
+
+#[test]
+fn flattens_nested_objects(){
+    let json = parse("{\"db\":{\"host\":\"x\",\"port\":5432},\"debug\":true,\"none\":null}");
+    assert_eq!(json.flatten(),parse("{\"db.host\":\"x\",\"db.port\":5432,\"debug\":true,\"none\":null}"));
+    let json = parse("{\"a\":[1,{\"b\":2},[3]],\"e\":{},\"f\":[]}");
+    assert_eq!(json.flatten(),parse("{\"a.0\":1,\"a.1.b\":2,\"a.2.0\":3,\"e\":{},\"f\":[]}"));
+    assert_eq!(json!([{"a":1},2]).flatten(),json!({"0.a":1,"1":2}));
+    assert_eq!(json!(1).flatten(),json!(1));
+    assert_eq!(json!({}).flatten(),json!({}));
+}
+
+#[test]
+fn bracket_notation(){
+    let json = parse("{\"a\":[1,{\"b\":2},[3,4]],\"c\":{\"0\":true}}");
+    assert_eq!(json.flatten_with(&brackets()),parse("{\"a[0]\":1,\"a[1].b\":2,\"a[2][0]\":3,\"a[2][1]\":4,\"c.0\":true}"));
+    assert_eq!(json!([{"a":1},[2]]).flatten_with(&brackets()),json!({"[0].a":1,"[1][0]":2}));
+    assert_eq!(json!({"a":{"":[1]}}).flatten_with(&brackets()),json!({"a.[0]":1}));
+    assert_eq!(json.flatten_with(&brackets()).unflatten_with(&brackets()).unwrap(),json);
+}
+
+#[test]
+fn separators_and_escaping(){
+    let options = FlattenOptions{separator:String::from("__"),..FlattenOptions::default()};
+    let json = json!({"DB":{"HOST":"x","MAX_CONN":10}});
+    assert_eq!(json.flatten_with(&options),json!({"DB__HOST":"x","DB__MAX\\_CONN":10}));
+    assert_eq!(json!({"a.b":{"c\\d":1,"7":2}}).flatten(),json!({"a\\.b.c\\\\d":1,"a\\.b.\\7":2}));
+    assert_eq!(json!({"a[0]":1}).flatten_with(&brackets()),json!({"a\\[0\\]":1}));
+    for json in [json!({"a.b":{"c\\d":1,"7":[2]}}),json!({"a_":{"_b__":[{"":1}]}}),json!({"":{"":2}})].iter() {
+        for options in [FlattenOptions::default(),brackets(),options.clone()].iter() {
+            assert_eq!(json.flatten_with(options).unflatten_with(options).unwrap(),*json,"{}",json);
+        }
+    }
+}
+
+#[test]
+fn unflattens(){
+    let flat = parse("{\"db.host\":\"x\",\"db.ports.0\":5432,\"db.ports.1\":5433,\"debug\":true}");
+    assert_eq!(flat.unflatten().unwrap(),parse("{\"db\":{\"host\":\"x\",\"ports\":[5432,5433]},\"debug\":true}"));
+    assert_eq!(json!({"1":"b","0":"a"}).unflatten().unwrap(),json!(["a","b"]));
+    assert_eq!(json!({"a.2":"c","a.0":"a"}).unflatten().unwrap(),json!({"a":["a",null,"c"]}));
+    assert_eq!(json!({"a[1]":"b","b":{}}).unflatten_with(&brackets()).unwrap(),json!({"a":[null,"b"],"b":{}}));
+    assert_eq!(json!({}).unflatten().unwrap(),json!({}));
+    let json = parse("{\"name\":\"app\",\"tags\":[\"a\",[\"b\",{\"c\":null}]],\"empty\":[],\"obj\":{}}");
+    assert_eq!(json.flatten().unflatten().unwrap(),json);
+}
+
+#[test]
+fn errors(){
+    assert_eq!(json!([1]).unflatten().unwrap_err(),FlattenErr::NotAnObject);
+    assert_eq!(json!({"a":1,"a.b":2}).unflatten().unwrap_err(),FlattenErr::Conflict(String::from("a.b")));
+    assert_eq!(json!({"a.0":1,"a.x":2}).unflatten().unwrap_err(),FlattenErr::Conflict(String::from("a.x")));
+    assert_eq!(json!({"0":1,"x":2}).unflatten().unwrap_err(),FlattenErr::Conflict(String::from("x")));
+    assert_eq!(json!({"a.b":{},"a.b.c":1}).unflatten().unwrap_err(),FlattenErr::Conflict(String::from("a.b.c")));
+    assert_eq!(json!({"a.5":1}).unflatten().unwrap_err(),FlattenErr::InvalidKey(String::from("a.5")));
+    assert_eq!(json!({"a\\":1}).unflatten().unwrap_err(),FlattenErr::InvalidKey(String::from("a\\")));
+    assert_eq!(json!({"a.99999999999":1}).unflatten().unwrap_err(),FlattenErr::InvalidKey(String::from("a.99999999999")));
+    assert_eq!(json!({"a.0":1,"a.3":2}).unflatten().unwrap_err(),FlattenErr::InvalidKey(String::from("a.3")));
+    for key in ["a[x]","a[01]","a[0]b","a[0","a[99999999999]","a[99999999999999999999999]"].iter() {
+        assert_eq!(json!({*key:1}).unflatten_with(&brackets()).unwrap_err(),FlattenErr::InvalidKey(String::from(*key)));
+    }
+}
@@ -0,0 +1,71 @@
+use ryson::Json;
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+#[test]
+fn index_by_key_and_position(){
+    let json = parse("{\"server\":{\"ports\":[80,443]}}");
+    assert_eq!(json["server"]["ports"][1],num("443"));
+    assert_eq!(json[&String::from("server")]["ports"][0],num("80"));
+}
+
+#[test]
+fn missing_values_read_as_null(){
+    let json = parse("{\"a\":[1]}");
+    assert_eq!(json["b"],Json::Null);
+    assert_eq!(json["a"][5],Json::Null);
+    assert_eq!(json["a"]["x"],Json::Null);
+    assert_eq!(json[0],Json::Null);
+    assert_eq!(json["b"]["c"]["d"],Json::Null);
+}
+
+#[test]
+fn get_returns_option(){
+    let json = parse("{\"a\":[1]}");
+    assert_eq!(json.get("a").and_then(|a|a.get(0)),Some(&num("1")));
+    assert_eq!(json.get("b"),None);
+    assert_eq!(json.get(0),None);
+}
+
+#[test]
+fn get_mut_changes_value(){
+    let mut json = parse("{\"a\":[1]}");
+    *json.get_mut("a").unwrap().get_mut(0).unwrap() = Json::Bool(true);
+    assert_eq!(json,parse("{\"a\":[true]}"));
+    assert!(json.get_mut("b").is_none());
+}
+
+#[test]
+fn index_mut_assigns_existing_values(){
+    let mut json = parse("{\"a\":[1,2]}");
+    json["a"][1] = num("3");
+    assert_eq!(json,parse("{\"a\":[1,3]}"));
+}
+
+#[test]
+fn index_mut_vivifies_objects(){
+    let mut json = Json::Null;
+    json["db"]["host"] = Json::String(String::from("localhost"));
+    json["db"]["port"] = num("5432");
+    assert_eq!(json,parse("{\"db\":{\"host\":\"localhost\",\"port\":5432}}"));
+}
+
+#[test]
+#[should_panic(expected = "index 2 is out of bounds for array of length 2")]
+fn index_mut_out_of_bounds_panics(){
+    let mut json = parse("[1,2]");
+    json[2] = Json::Null;
+}
+
+#[test]
+#[should_panic(expected = "cannot index into number with a key")]
+fn index_mut_key_on_scalar_panics(){
+    let mut json = parse("{\"a\":1}");
+    json["a"]["b"] = Json::Null;
+}
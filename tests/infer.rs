@@ -0,0 +1,105 @@
+use ryson::{Json,Schema,SchemaInferrer,InferOptions};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn infer(samples:&[&str])->Json {
+    let mut inferrer = SchemaInferrer::default();
+    for sample in samples {
+        inferrer.add(&parse(sample));
+    }
+    inferrer.infer()
+}
+
+fn at<'a>(schema:&'a Json,pointer:&str)->&'a Json {
+    schema.pointer(pointer).unwrap()
+}
+
+fn strings(values:&[&str])->Json {
+    Json::Array(values.iter().map(|v|Json::String(String::from(*v))).collect())
+}
+
+#[test]
+fn infers_scalar_types(){
+    assert_eq!(at(&infer(&["1","2"]), "/type"),&Json::String(String::from("integer")));
+    assert_eq!(at(&infer(&["1","2.5"]), "/type"),&Json::String(String::from("number")));
+    assert_eq!(at(&infer(&["true"]), "/type"),&Json::String(String::from("boolean")));
+    assert_eq!(at(&infer(&["\"a\"","null"]), "/type"),&strings(&["string","null"]));
+}
+
+#[test]
+fn declares_dialect(){
+    assert_eq!(at(&infer(&["{}"]), "/$schema"),&Json::String(String::from("https://json-schema.org/draft/2020-12/schema")));
+}
+
+#[test]
+fn required_and_optional_keys(){
+    let schema = infer(&[
+        "{\"id\":1,\"name\":\"a\",\"email\":\"x@y\"}",
+        "{\"id\":2,\"name\":\"b\"}"
+    ]);
+    assert_eq!(at(&schema, "/type"),&Json::String(String::from("object")));
+    assert_eq!(at(&schema, "/required"),&strings(&["id","name"]));
+    assert_eq!(at(&schema, "/properties/email/type"),&Json::String(String::from("string")));
+}
+
+#[test]
+fn nullable_fields(){
+    let schema = infer(&["{\"parent\":null}","{\"parent\":3}"]);
+    assert_eq!(at(&schema, "/properties/parent/type"),&strings(&["integer","null"]));
+}
+
+#[test]
+fn array_items_are_merged(){
+    let schema = infer(&["{\"tags\":[\"a\",1]}","{\"tags\":[]}"]);
+    assert_eq!(at(&schema, "/properties/tags/type"),&Json::String(String::from("array")));
+    assert_eq!(at(&schema, "/properties/tags/items/type"),&strings(&["string","integer"]));
+    assert!(infer(&["[]"]).pointer("/items").is_err());
+}
+
+#[test]
+fn nested_objects_in_arrays(){
+    let schema = infer(&["{\"items\":[{\"id\":1,\"price\":2},{\"id\":2}]}"]);
+    assert_eq!(at(&schema, "/properties/items/items/required"),&strings(&["id"]));
+}
+
+#[test]
+fn repeated_strings_become_enum(){
+    let schema = infer(&[
+        "{\"status\":\"open\",\"title\":\"a\"}",
+        "{\"status\":\"closed\",\"title\":\"b\"}",
+        "{\"status\":\"open\",\"title\":\"c\"}",
+        "{\"status\":\"closed\",\"title\":\"d\"}"
+    ]);
+    assert_eq!(at(&schema, "/properties/status/enum"),&strings(&["closed","open"]));
+    assert!(schema.pointer("/properties/title/enum").is_err());
+}
+
+#[test]
+fn enum_limit_is_configurable(){
+    let mut inferrer = SchemaInferrer::new(InferOptions{max_enum_values:1,min_enum_repeats:1});
+    inferrer.add(&parse("\"a\""));
+    inferrer.add(&parse("\"b\""));
+    assert!(inferrer.infer().pointer("/enum").is_err());
+    let mut inferrer = SchemaInferrer::new(InferOptions{max_enum_values:2,min_enum_repeats:1});
+    inferrer.add(&parse("\"a\""));
+    inferrer.add(&parse("\"b\""));
+    assert_eq!(at(&inferrer.infer(), "/enum"),&strings(&["a","b"]));
+}
+
+#[test]
+fn samples_validate_against_inferred_schema(){
+    let samples = [
+        "{\"id\":1,\"kind\":\"user\",\"tags\":[\"x\"],\"meta\":{\"age\":30}}",
+        "{\"id\":2,\"kind\":\"bot\",\"tags\":[],\"meta\":null}",
+        "{\"id\":3,\"kind\":\"user\",\"tags\":[\"y\",\"z\"],\"meta\":{\"age\":41.5,\"vip\":true}}",
+        "{\"id\":4,\"kind\":\"bot\",\"tags\":[\"x\"]}"
+    ];
+    let schema = Schema::compile(&infer(&samples)).unwrap();
+    for sample in samples.iter() {
+        assert!(schema.is_valid(&parse(sample)));
+    }
+    assert!(!schema.is_valid(&parse("{\"id\":\"5\",\"kind\":\"user\",\"tags\":[]}")));
+    assert!(!schema.is_valid(&parse("{\"id\":5,\"kind\":\"admin\",\"tags\":[]}")));
+}
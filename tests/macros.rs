@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap,HashMap};
+use ryson::{Json,json};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+#[test]
+fn literals(){
+    assert_eq!(json!(null),Json::Null);
+    assert_eq!(json!(true),Json::Bool(true));
+    assert_eq!(json!(false),Json::Bool(false));
+    assert_eq!(json!(12),num("12"));
+    assert_eq!(json!(-3),num("-3"));
+    assert_eq!(json!(1.5),num("1.5"));
+    assert_eq!(json!("text"),Json::String(String::from("text")));
+    assert_eq!(json!([]),Json::Array(vec![]));
+    assert_eq!(json!({}),Json::Object(HashMap::new()));
+}
+
+#[test]
+fn nested_values_match_parsed_text(){
+    let built = json!({
+        "name":"ryson",
+        "tags":["json",null,true,false],
+        "nested":{"empty":[],"deep":[[1],{"a":{}}]},
+        "count":3,
+    });
+    let parsed = parse("{\"name\":\"ryson\",\"tags\":[\"json\",null,true,false],\"nested\":{\"empty\":[],\"deep\":[[1],{\"a\":{}}]},\"count\":3}");
+    assert_eq!(built,parsed);
+}
+
+#[test]
+fn interpolated_expressions(){
+    let port = 8080;
+    let host = String::from("localhost");
+    let inner = json!([1,2]);
+    let key = "dynamic";
+    let built = json!({
+        "host":host,
+        "port":port + 1,
+        "inner":inner,
+        (key):Some("set"),
+        "missing":None::<i32>,
+        "list":[port,-port,"x"]
+    });
+    assert_eq!(built["host"],Json::String(String::from("localhost")));
+    assert_eq!(built["port"],num("8081"));
+    assert_eq!(built["inner"],parse("[1,2]"));
+    assert_eq!(built["dynamic"],Json::String(String::from("set")));
+    assert_eq!(built["missing"],Json::Null);
+    assert_eq!(built["list"],Json::Array(vec![num("8080"),num("-8080"),Json::String(String::from("x"))]));
+}
+
+#[test]
+fn from_primitives(){
+    assert_eq!(Json::from(true),Json::Bool(true));
+    assert_eq!(Json::from(u64::MAX),num("18446744073709551615"));
+    assert_eq!(Json::from(i128::MIN),num("-170141183460469231731687303715884105728"));
+    assert_eq!(Json::from(0.25f32),num("0.25"));
+    assert_eq!(Json::from(f64::NAN),Json::Null);
+    assert_eq!(Json::from(f64::INFINITY),Json::Null);
+    assert_eq!(Json::from("a"),Json::String(String::from("a")));
+    assert_eq!(Json::from(&String::from("b")),Json::String(String::from("b")));
+}
+
+#[test]
+fn from_collections(){
+    assert_eq!(Json::from(vec![1,2,3]),parse("[1,2,3]"));
+    assert_eq!(Json::from(&["a","b"][..]),parse("[\"a\",\"b\"]"));
+    assert_eq!(Json::from(vec![Some(1),None]),parse("[1,null]"));
+    let mut map = HashMap::new();
+    map.insert("a",vec![true]);
+    assert_eq!(Json::from(map),parse("{\"a\":[true]}"));
+    let mut map = BTreeMap::new();
+    map.insert(String::from("b"),1u8);
+    assert_eq!(Json::from(map),parse("{\"b\":1}"));
+}
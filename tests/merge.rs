@@ -0,0 +1,99 @@
+use ryson::{Json,MergeOptions,ArrayMerge,ConflictPolicy,MergeErr};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn merge_patch(target:&str,patch:&str)->Json {
+    let mut json = parse(target);
+    json.merge_patch(&parse(patch));
+    json
+}
+
+fn options(arrays:ArrayMerge,conflicts:ConflictPolicy)->MergeOptions {
+    MergeOptions{arrays,conflicts}
+}
+
+#[test]
+fn merge_patch_rfc_examples(){
+    let cases = [
+        ("{\"a\":\"b\"}","{\"a\":\"c\"}","{\"a\":\"c\"}"),
+        ("{\"a\":\"b\"}","{\"b\":\"c\"}","{\"a\":\"b\",\"b\":\"c\"}"),
+        ("{\"a\":\"b\"}","{\"a\":null}","{}"),
+        ("{\"a\":\"b\",\"b\":\"c\"}","{\"a\":null}","{\"b\":\"c\"}"),
+        ("{\"a\":[\"b\"]}","{\"a\":\"c\"}","{\"a\":\"c\"}"),
+        ("{\"a\":\"c\"}","{\"a\":[\"b\"]}","{\"a\":[\"b\"]}"),
+        ("{\"a\":{\"b\":\"c\"}}","{\"a\":{\"b\":\"d\",\"c\":null}}","{\"a\":{\"b\":\"d\"}}"),
+        ("{\"a\":[{\"b\":\"c\"}]}","{\"a\":[1]}","{\"a\":[1]}"),
+        ("[\"a\",\"b\"]","[\"c\",\"d\"]","[\"c\",\"d\"]"),
+        ("{\"a\":\"b\"}","[\"c\"]","[\"c\"]"),
+        ("{\"a\":\"foo\"}","null","null"),
+        ("{\"a\":\"foo\"}","\"bar\"","\"bar\""),
+        ("{\"e\":null}","{\"a\":1}","{\"e\":null,\"a\":1}"),
+        ("[1,2]","{\"a\":\"b\",\"c\":null}","{\"a\":\"b\"}"),
+        ("{}","{\"a\":{\"bb\":{\"ccc\":null}}}","{\"a\":{\"bb\":{}}}")
+    ];
+    for (target,patch,result) in cases.iter() {
+        assert_eq!(merge_patch(target, patch),parse(result));
+    }
+}
+
+#[test]
+fn deep_merge_layers_objects(){
+    let mut config = parse("{\"db\":{\"host\":\"localhost\",\"port\":5432},\"debug\":false}");
+    config.deep_merge(&parse("{\"db\":{\"host\":\"db.internal\"},\"debug\":true}"), &MergeOptions::default()).unwrap();
+    assert_eq!(config,parse("{\"db\":{\"host\":\"db.internal\",\"port\":5432},\"debug\":true}"));
+}
+
+#[test]
+fn left_wins_keeps_existing_values(){
+    let mut config = parse("{\"db\":{\"host\":\"localhost\"}}");
+    let opts = options(ArrayMerge::Replace, ConflictPolicy::LeftWins);
+    config.deep_merge(&parse("{\"db\":{\"host\":\"remote\",\"user\":\"app\"}}"), &opts).unwrap();
+    assert_eq!(config,parse("{\"db\":{\"host\":\"localhost\",\"user\":\"app\"}}"));
+}
+
+#[test]
+fn conflict_error_reports_path_and_keeps_value(){
+    let mut config = parse("{\"db\":{\"port\":5432,\"host\":\"a\"}}");
+    let opts = options(ArrayMerge::Replace, ConflictPolicy::Error);
+    let err = config.deep_merge(&parse("{\"db\":{\"host\":\"a\",\"port\":\"5433\"}}"), &opts).unwrap_err();
+    assert_eq!(err,MergeErr::Conflict(String::from("/db/port")));
+    assert_eq!(config,parse("{\"db\":{\"port\":5432,\"host\":\"a\"}}"));
+}
+
+#[test]
+fn equal_values_are_not_conflicts(){
+    let mut config = parse("{\"port\":80,\"tags\":[1]}");
+    let opts = options(ArrayMerge::Replace, ConflictPolicy::Error);
+    config.deep_merge(&parse("{\"port\":80.0,\"tags\":[1]}"), &opts).unwrap();
+    assert_eq!(config,parse("{\"port\":80,\"tags\":[1]}"));
+}
+
+#[test]
+fn arrays_replace_or_append(){
+    let mut replaced = parse("{\"hosts\":[\"a\",\"b\"]}");
+    replaced.deep_merge(&parse("{\"hosts\":[\"c\"]}"), &MergeOptions::default()).unwrap();
+    assert_eq!(replaced,parse("{\"hosts\":[\"c\"]}"));
+
+    let mut appended = parse("{\"hosts\":[\"a\",\"b\"]}");
+    let opts = options(ArrayMerge::Append, ConflictPolicy::Error);
+    appended.deep_merge(&parse("{\"hosts\":[\"c\"]}"), &opts).unwrap();
+    assert_eq!(appended,parse("{\"hosts\":[\"a\",\"b\",\"c\"]}"));
+}
+
+#[test]
+fn arrays_merge_by_key(){
+    let mut services = parse("[{\"name\":\"api\",\"port\":80},{\"name\":\"web\",\"port\":8080}]");
+    let opts = options(ArrayMerge::MergeByKey(String::from("name")), ConflictPolicy::RightWins);
+    services.deep_merge(&parse("[{\"name\":\"web\",\"port\":9090,\"tls\":true},{\"name\":\"db\"}]"), &opts).unwrap();
+    assert_eq!(services,parse("[{\"name\":\"api\",\"port\":80},{\"name\":\"web\",\"port\":9090,\"tls\":true},{\"name\":\"db\"}]"));
+}
+
+#[test]
+fn merge_by_key_conflict_path_uses_left_index(){
+    let mut services = parse("[{\"name\":\"api\",\"port\":80},{\"name\":\"web\",\"port\":8080}]");
+    let opts = options(ArrayMerge::MergeByKey(String::from("name")), ConflictPolicy::Error);
+    let err = services.deep_merge(&parse("[{\"name\":\"web\",\"port\":9090}]"), &opts).unwrap_err();
+    assert_eq!(err,MergeErr::Conflict(String::from("/1/port")));
+}
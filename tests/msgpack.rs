@@ -0,0 +1,130 @@
+use ryson::{ExtPolicy, Json, MsgPackErr, MsgPackOptions, json};
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+fn encode(json:&Json)->String {
+    hex::encode(json.to_msgpack().unwrap())
+}
+
+fn decode(text:&str)->Json {
+    Json::from_msgpack(&hex::decode(text).unwrap()).unwrap()
+}
+
+fn decode_err(text:&str)->MsgPackErr {
+    Json::from_msgpack(&hex::decode(text).unwrap()).unwrap_err()
+}
+
+const NUMBERS : &[(&str,&str)] = &[
+    ("0","00"),
+    ("127","7f"),
+    ("128","cc80"),
+    ("255","ccff"),
+    ("256","cd0100"),
+    ("65535","cdffff"),
+    ("65536","ce00010000"),
+    ("4294967295","ceffffffff"),
+    ("4294967296","cf0000000100000000"),
+    ("18446744073709551615","cfffffffffffffffff"),
+    ("-1","ff"),
+    ("-32","e0"),
+    ("-33","d0df"),
+    ("-128","d080"),
+    ("-129","d1ff7f"),
+    ("-32768","d18000"),
+    ("-32769","d2ffff7fff"),
+    ("-2147483648","d280000000"),
+    ("-2147483649","d3ffffffff7fffffff"),
+    ("-9223372036854775808","d38000000000000000"),
+    ("1.5","ca3fc00000"),
+    ("-0.25","cabe800000"),
+    ("-0","ca80000000"),
+    ("1.1","cb3ff199999999999a"),
+    ("1e+300","cb7e37e43c8800759c")
+];
+
+#[test]
+fn encodes_numbers_in_the_smallest_form(){
+    for (text,msgpack) in NUMBERS {
+        assert_eq!(encode(&num(text)),*msgpack,"{}",text);
+    }
+}
+
+#[test]
+fn decodes_numbers(){
+    for (text,msgpack) in NUMBERS {
+        assert_eq!(decode(msgpack),num(text),"{}",msgpack);
+    }
+    assert_eq!(decode("cc05"),num("5"));
+    assert_eq!(decode("d3fffffffffffffffe"),num("-2"));
+    assert_eq!(decode("cb4004000000000000"),num("2.5"));
+    assert_eq!(decode("ca7f800000"),Json::Null);
+    assert_eq!(decode("cb7ff8000000000000"),Json::Null);
+}
+
+#[test]
+fn numbers_without_an_exact_form_get_the_nearest_double(){
+    assert_eq!(encode(&num("1.50")),"ca3fc00000");
+    assert_eq!(encode(&num("18446744073709551616")),"ca5f800000");
+    assert_eq!(encode(&num("2e3")),"ca44fa0000");
+    let json = json!({"a":[1,num("1e400")]});
+    assert_eq!(json.to_msgpack().unwrap_err(),MsgPackErr::InvalidNumber(String::from("/a/1")));
+    assert_eq!(num("1.").to_msgpack().unwrap_err(),MsgPackErr::InvalidNumber(String::new()));
+}
+
+#[test]
+fn strings_and_containers(){
+    assert_eq!(encode(&Json::from("")),"a0");
+    assert_eq!(encode(&Json::from("hello")),"a568656c6c6f");
+    assert_eq!(encode(&Json::from("a".repeat(32))),format!("d920{}","61".repeat(32)));
+    assert_eq!(encode(&Json::from("a".repeat(256))),format!("da0100{}","61".repeat(256)));
+    assert_eq!(encode(&json!([1,true,null,false])),"9401c3c0c2");
+    assert_eq!(encode(&Json::Array(vec![Json::Null;16])),format!("dc0010{}","c0".repeat(16)));
+    assert_eq!(encode(&json!({"b":2,"a":1})),"82a16101a16202");
+    assert_eq!(encode(&json!({"compact":true,"schema":0})),"82a7636f6d70616374c3a6736368656d6100");
+    assert_eq!(decode("82a7636f6d70616374c3a6736368656d6100"),json!({"compact":true,"schema":0}));
+    assert_eq!(decode("d90568656c6c6f"),Json::from("hello"));
+    assert_eq!(decode("db0000000161"),Json::from("a"));
+    assert_eq!(decode("dd000000020102"),json!([1,2]));
+    assert_eq!(decode("de0001a16190"),json!({"a":[]}));
+    assert_eq!(decode("df00000001a161c0"),json!({"a":null}));
+    assert_eq!(decode("8201c3c3c0"),json!({"1":true,"true":null}));
+    assert_eq!(decode("8281a16ba3612262019201a16102"),json!({"{\"k\":\"a\\\"b\"}":1,"[1,\"a\"]":2}));
+    assert_eq!(decode("c40401020304"),Json::from("AQIDBA=="));
+    assert_eq!(decode("c5000100"),Json::from("AA=="));
+}
+
+#[test]
+fn round_trips_documents(){
+    let json = Json::parse("{\"name\":\"sensor\",\"readings\":[1,2.5,300,70000],\"ok\":true,\"last\":null,\"tags\":{\"x\":\"\u{6c34}\"}}").unwrap();
+    assert_eq!(Json::from_msgpack(&json.to_msgpack().unwrap()).unwrap(),json);
+}
+
+#[test]
+fn ext_policy(){
+    let bytes = hex::decode("92d6ffd4e70c0001").unwrap();
+    assert_eq!(Json::from_msgpack(&bytes).unwrap_err(),MsgPackErr::UnsupportedExt(1));
+    let options = MsgPackOptions{ext:ExtPolicy::Null};
+    assert_eq!(Json::from_msgpack_with(&bytes, &options).unwrap(),json!([null,1]));
+    let options = MsgPackOptions{ext:ExtPolicy::Object};
+    assert_eq!(Json::from_msgpack_with(&bytes, &options).unwrap(),json!([{"type":-1,"data":"1OcMAA=="},1]));
+    let bytes = hex::decode("c70305010203").unwrap();
+    assert_eq!(Json::from_msgpack_with(&bytes, &options).unwrap(),json!({"type":5,"data":"AQID"}));
+}
+
+#[test]
+fn errors(){
+    assert_eq!(decode_err(""),MsgPackErr::UnexpectedEnd);
+    assert_eq!(decode_err("cd01"),MsgPackErr::UnexpectedEnd);
+    assert_eq!(decode_err("9201"),MsgPackErr::UnexpectedEnd);
+    assert_eq!(decode_err("dbffffffff61"),MsgPackErr::UnexpectedEnd);
+    assert_eq!(decode_err("0102"),MsgPackErr::TrailingBytes(1));
+    assert_eq!(decode_err("9201c1"),MsgPackErr::InvalidMarker(2));
+    assert_eq!(decode_err("91a2c328"),MsgPackErr::InvalidUtf8(1));
+    assert_eq!(Json::from_msgpack(&vec![0x91;200000]).unwrap_err(),MsgPackErr::TooDeep(256));
+    assert_eq!(Json::from_msgpack(&[0x81,0xc0].repeat(100000)).unwrap_err(),MsgPackErr::TooDeep(511));
+    let mut bytes = vec![0x91;255];
+    bytes.push(0x01);
+    assert_eq!(Json::from_msgpack(&bytes).unwrap().to_msgpack().unwrap(),bytes);
+}
@@ -0,0 +1,86 @@
+use std::collections::{BTreeMap,BTreeSet,HashMap,HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash,Hasher};
+use ryson::{Json,json};
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+fn hash(json:&Json)->u64 {
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn orders_across_types(){
+    let mut values = vec![
+        json!({"a":1}),
+        json!([1]),
+        json!("a"),
+        json!(1),
+        json!(true),
+        json!(false),
+        json!(null)
+    ];
+    values.sort();
+    assert_eq!(values,vec![
+        json!(null),
+        json!(false),
+        json!(true),
+        json!(1),
+        json!("a"),
+        json!([1]),
+        json!({"a":1})
+    ]);
+}
+
+#[test]
+fn numbers_compare_by_value(){
+    assert!(num("9") < num("10"));
+    assert!(num("-5") < num("0.5"));
+    assert!(num("1e3") > num("999.99"));
+    // equal values fall back to their text so that only == values compare equal
+    assert!(num("1") < num("1.0"));
+    assert!(num("1.0") < num("1.00"));
+    assert!(num("100") < num("not a number"));
+}
+
+#[test]
+fn arrays_and_objects_compare_element_wise(){
+    assert!(json!([1,2]) < json!([1,3]));
+    assert!(json!([1]) < json!([1,0]));
+    assert!(json!({"a":1}) < json!({"a":2}));
+    assert!(json!({"a":1}) < json!({"b":0}));
+    assert!(json!({"a":1}) < json!({"a":1,"b":0}));
+    assert_eq!(json!({"a":1,"b":[2]}).cmp(&json!({"b":[2],"a":1})),std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn object_hash_ignores_member_order(){
+    let mut a = HashMap::new();
+    let mut b = HashMap::new();
+    for i in 0..50 {
+        a.insert(i.to_string(),json!(i));
+    }
+    for i in (0..50).rev() {
+        b.insert(i.to_string(),json!(i));
+    }
+    assert_eq!(hash(&Json::Object(a)),hash(&Json::Object(b)));
+    assert_ne!(hash(&json!({"a":1})),hash(&json!({"a":2})));
+    assert_ne!(hash(&json!([1,2])),hash(&json!([2,1])));
+}
+
+#[test]
+fn works_in_sets_and_maps(){
+    let values = vec![json!({"x":[1,2]}),json!("a"),json!({"x":[1,2]}),json!(null),json!("a")];
+    let unique : HashSet<Json> = values.iter().cloned().collect();
+    assert_eq!(unique.len(),3);
+    let sorted : BTreeSet<Json> = values.into_iter().collect();
+    assert_eq!(sorted.into_iter().collect::<Vec<Json>>(),vec![json!(null),json!("a"),json!({"x":[1,2]})]);
+    let mut counts = BTreeMap::new();
+    *counts.entry(json!([1])).or_insert(0) += 1;
+    *counts.entry(json!([1])).or_insert(0) += 1;
+    assert_eq!(counts[&json!([1])],2);
+}
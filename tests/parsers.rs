@@ -0,0 +1,92 @@
+use ryson::{Json,JsonRef,Jerr,Tape};
+
+// the owned, borrowed and tape parsers must agree on every input: the same
+// value when it is valid and the same error when it is not
+fn parse_all(text:&str)->[Result<Json,Jerr>;3] {
+    [
+        Json::parse(text),
+        JsonRef::parse(text).map(|json|json.to_owned()),
+        Tape::parse(text).map(|tape|tape.to_json())
+    ]
+}
+
+#[test]
+fn parsers_agree_on_valid_input(){
+    let texts = [
+        "null",
+        "true",
+        "false",
+        "0",
+        "-0",
+        "1024",
+        "-12.5",
+        "1e5",
+        "2E+10",
+        "-0.5e-3",
+        "\"\"",
+        "\"caf\\u00e9\"",
+        "\"\\ud83d\\ude00\"",
+        "\"\\\"\\\\\\/\\b\\f\\n\\r\\t\"",
+        "[]",
+        "[ ]",
+        "{}",
+        "{ }",
+        "  [ 1, \"two\",\n\t{\"three\" :[false,null]} ]  ",
+        "{\"a\":{\"b\":{\"c\":[[],{}]}},\"d\":[-1,2.5]}",
+        "{\r\n  \"a\": -1,\r\n  \"b\": [1,\r\n2]\r\n}\r\n",
+        "{\"b\":1,\"a\":2,\"b\":3}"
+    ];
+    for text in texts.iter() {
+        let [owned,borrowed,tape] = parse_all(text);
+        assert!(owned.is_ok(),"{:?}: {:?}",text,owned);
+        assert_eq!(borrowed,owned,"{:?}",text);
+        assert_eq!(tape,owned,"{:?}",text);
+    }
+}
+
+#[test]
+fn parsers_agree_on_errors(){
+    let texts = [
+        "",
+        "  \n ",
+        "tru",
+        "nul",
+        "01",
+        "-01",
+        "-",
+        "1.",
+        ".5",
+        "1.2.3",
+        "1e",
+        "1e+",
+        "1.5E",
+        "4534h",
+        "\"abc",
+        "\"\\x\"",
+        "\"\\u00zz\"",
+        "\"\\ud83d\"",
+        "\"\\ud83d\\u0041\"",
+        "[1,,2]",
+        "[1,]",
+        "[,1]",
+        "[1 2]",
+        "[1",
+        "[1]]",
+        "{\"a\" 1}",
+        "{\"a\":}",
+        "{1:2}",
+        "{\"a\":1,}",
+        "{\"a\":1 \"b\":2}",
+        "{\"a\":1",
+        "{,}",
+        "null x",
+        "[-]",
+        "{\"a\":01}"
+    ];
+    for text in texts.iter() {
+        let [owned,borrowed,tape] = parse_all(text);
+        assert!(owned.is_err(),"{:?}: {:?}",text,owned);
+        assert_eq!(borrowed,owned,"{:?}",text);
+        assert_eq!(tape,owned,"{:?}",text);
+    }
+}
@@ -0,0 +1,151 @@
+use ryson::{Json,Patch,PatchOp,PatchErr,PointerErr,diff};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn apply(doc:&str,patch:&str)->Result<Json,PatchErr> {
+    let mut json = parse(doc);
+    let patch = Patch::from_json(&parse(patch))?;
+    json.apply_patch(&patch)?;
+    Ok(json)
+}
+
+#[test]
+fn adds_object_member(){
+    let json = apply("{\"foo\":\"bar\"}","[{\"op\":\"add\",\"path\":\"/baz\",\"value\":\"qux\"}]").unwrap();
+    assert_eq!(json,parse("{\"baz\":\"qux\",\"foo\":\"bar\"}"));
+}
+
+#[test]
+fn adds_array_element(){
+    let json = apply("{\"foo\":[\"bar\",\"baz\"]}","[{\"op\":\"add\",\"path\":\"/foo/1\",\"value\":\"qux\"}]").unwrap();
+    assert_eq!(json,parse("{\"foo\":[\"bar\",\"qux\",\"baz\"]}"));
+}
+
+#[test]
+fn removes_member_and_element(){
+    let json = apply("{\"baz\":\"qux\",\"foo\":[\"bar\",\"qux\",\"baz\"]}",
+        "[{\"op\":\"remove\",\"path\":\"/baz\"},{\"op\":\"remove\",\"path\":\"/foo/1\"}]").unwrap();
+    assert_eq!(json,parse("{\"foo\":[\"bar\",\"baz\"]}"));
+}
+
+#[test]
+fn replaces_value(){
+    let json = apply("{\"baz\":\"qux\",\"foo\":\"bar\"}","[{\"op\":\"replace\",\"path\":\"/baz\",\"value\":\"boo\"}]").unwrap();
+    assert_eq!(json,parse("{\"baz\":\"boo\",\"foo\":\"bar\"}"));
+}
+
+#[test]
+fn moves_value(){
+    let doc = "{\"foo\":{\"bar\":\"baz\",\"waldo\":\"fred\"},\"qux\":{\"corge\":\"grault\"}}";
+    let json = apply(doc,"[{\"op\":\"move\",\"from\":\"/foo/waldo\",\"path\":\"/qux/thud\"}]").unwrap();
+    assert_eq!(json,parse("{\"foo\":{\"bar\":\"baz\"},\"qux\":{\"corge\":\"grault\",\"thud\":\"fred\"}}"));
+    let json = apply("{\"foo\":[\"all\",\"grass\",\"cows\",\"eat\"]}",
+        "[{\"op\":\"move\",\"from\":\"/foo/1\",\"path\":\"/foo/3\"}]").unwrap();
+    assert_eq!(json,parse("{\"foo\":[\"all\",\"cows\",\"eat\",\"grass\"]}"));
+}
+
+#[test]
+fn copies_value(){
+    let json = apply("{\"a\":{\"b\":1}}","[{\"op\":\"copy\",\"from\":\"/a\",\"path\":\"/c\"}]").unwrap();
+    assert_eq!(json,parse("{\"a\":{\"b\":1},\"c\":{\"b\":1}}"));
+}
+
+#[test]
+fn test_operation_compares_numbers_by_value(){
+    let doc = "{\"baz\":\"qux\",\"foo\":[\"a\",2,\"c\"]}";
+    assert!(apply(doc,"[{\"op\":\"test\",\"path\":\"/baz\",\"value\":\"qux\"},\
+        {\"op\":\"test\",\"path\":\"/foo/1\",\"value\":2.0}]").is_ok());
+    assert_eq!(apply(doc,"[{\"op\":\"test\",\"path\":\"/baz\",\"value\":\"bar\"}]").unwrap_err(),
+        PatchErr::TestFailed(0));
+}
+
+#[test]
+fn failed_patch_rolls_back(){
+    let mut json = parse("{\"a\":1}");
+    let patch = Patch(vec![
+        PatchOp::Add{path:String::from("/b"),value:Json::Null},
+        PatchOp::Remove{path:String::from("/missing")}
+    ]);
+    assert_eq!(json.apply_patch(&patch).unwrap_err(),
+        PatchErr::PointerFailed(1,PointerErr::KeyNotFound(String::from("/missing"))));
+    assert_eq!(json,parse("{\"a\":1}"));
+}
+
+#[test]
+fn error_on_move_into_child(){
+    let err = apply("{\"a\":{\"b\":1}}","[{\"op\":\"move\",\"from\":\"/a\",\"path\":\"/a/b/c\"}]").unwrap_err();
+    assert_eq!(err,PatchErr::MoveIntoChild(0));
+}
+
+#[test]
+fn error_on_malformed_operations(){
+    assert_eq!(Patch::from_json(&parse("{}")).unwrap_err(),PatchErr::ExpectedArray);
+    assert_eq!(Patch::from_json(&parse("[{\"op\":\"add\",\"path\":\"/a\"}]")).unwrap_err(),
+        PatchErr::InvalidOperation(0));
+    assert_eq!(Patch::from_json(&parse("[{\"op\":\"remove\",\"path\":\"/a\"},{\"op\":\"jump\",\"path\":\"/a\"}]")).unwrap_err(),
+        PatchErr::UnknownOperation(1,String::from("jump")));
+}
+
+#[test]
+fn patch_round_trips_through_json(){
+    let text = "[{\"op\":\"move\",\"from\":\"/a\",\"path\":\"/b\"},{\"op\":\"test\",\"path\":\"/b\",\"value\":[1]}]";
+    let patch = Patch::from_json(&parse(text)).unwrap();
+    assert_eq!(patch.to_json(),parse(text));
+}
+
+#[test]
+fn diff_of_equal_documents_is_empty(){
+    let a = parse("{\"a\":[1,{\"b\":null}]}");
+    assert_eq!(diff(&a, &a.clone()),Patch::default());
+}
+
+#[test]
+fn diff_touches_only_changed_members(){
+    let a = parse("{\"name\":\"svc\",\"port\":80,\"tls\":{\"on\":false},\"old\":true}");
+    let b = parse("{\"name\":\"svc\",\"port\":443,\"tls\":{\"on\":true},\"new\":1}");
+    let patch = diff(&a, &b);
+    assert_eq!(patch,Patch(vec![
+        PatchOp::Add{path:String::from("/new"),value:Json::Number(String::from("1"))},
+        PatchOp::Remove{path:String::from("/old")},
+        PatchOp::Replace{path:String::from("/port"),value:Json::Number(String::from("443"))},
+        PatchOp::Replace{path:String::from("/tls/on"),value:Json::Bool(true)}
+    ]));
+}
+
+#[test]
+fn diff_of_arrays_uses_shortest_edits(){
+    let a = parse("[1,2,3,4,5]");
+    let b = parse("[0,1,2,4,5,6]");
+    let patch = diff(&a, &b);
+    assert_eq!(patch.0.len(),3);
+    let mut patched = a.clone();
+    patched.apply_patch(&patch).unwrap();
+    assert_eq!(patched,b);
+}
+
+#[test]
+fn diff_escapes_keys(){
+    let a = parse("{\"a/b\":1}");
+    let b = parse("{\"a/b\":2}");
+    assert_eq!(diff(&a, &b),Patch(vec![
+        PatchOp::Replace{path:String::from("/a~1b"),value:Json::Number(String::from("2"))}
+    ]));
+}
+
+#[test]
+fn diff_then_apply_reproduces_target(){
+    let a = parse("{\"items\":[{\"id\":1,\"tags\":[\"x\"]},{\"id\":2}],\"count\":2}");
+    let b = parse("{\"items\":[{\"id\":2,\"tags\":[]},{\"id\":1,\"tags\":[\"x\",\"y\"]},{\"id\":3}],\"total\":3}");
+    let mut patched = a.clone();
+    patched.apply_patch(&diff(&a, &b)).unwrap();
+    assert_eq!(patched,b);
+}
+
+#[test]
+fn diff_replaces_root_of_different_types(){
+    assert_eq!(diff(&parse("[]"), &parse("{}")),Patch(vec![
+        PatchOp::Replace{path:String::new(),value:parse("{}")}
+    ]));
+}
@@ -0,0 +1,159 @@
+use ryson::{Json,JsonPath,PathErr};
+
+fn store()->Json {
+    let text = "{ \"store\": {
+        \"book\": [
+          { \"category\": \"reference\",
+            \"author\": \"Nigel Rees\",
+            \"title\": \"Sayings of the Century\",
+            \"price\": 8.95
+          },
+          { \"category\": \"fiction\",
+            \"author\": \"Evelyn Waugh\",
+            \"title\": \"Sword of Honour\",
+            \"price\": 12.99
+          },
+          { \"category\": \"fiction\",
+            \"author\": \"Herman Melville\",
+            \"title\": \"Moby Dick\",
+            \"isbn\": \"0-553-21311-3\",
+            \"price\": 8.99
+          },
+          { \"category\": \"fiction\",
+            \"author\": \"J. R. R. Tolkien\",
+            \"title\": \"The Lord of the Rings\",
+            \"isbn\": \"0-395-19395-8\",
+            \"price\": 22.99
+          }
+        ],
+        \"bicycle\": {
+          \"color\": \"red\",
+          \"price\": 399
+        }
+      }
+    }";
+    Json::parse(text).unwrap()
+}
+
+fn paths(json:&Json,path:&str)->Vec<String> {
+    json.query(path).unwrap().into_iter().map(|m|m.path).collect()
+}
+
+fn strings(json:&Json,path:&str)->Vec<String> {
+    json.query(path).unwrap().into_iter().map(|m|m.value.as_string().unwrap().clone()).collect()
+}
+
+#[test]
+fn root_selects_document(){
+    let json = store();
+    let matches = json.query("$").unwrap();
+    assert_eq!(matches.len(),1);
+    assert_eq!(matches[0].path,"$");
+    assert_eq!(matches[0].value,&json);
+}
+
+#[test]
+fn child_and_wildcard_segments(){
+    let json = store();
+    assert_eq!(strings(&json, "$.store.book[*].author"),
+        vec!["Nigel Rees","Evelyn Waugh","Herman Melville","J. R. R. Tolkien"]);
+    assert_eq!(strings(&json, "$['store'][\"book\"][0]['title']"),vec!["Sayings of the Century"]);
+}
+
+#[test]
+fn descendant_segment(){
+    let json = store();
+    assert_eq!(json.query("$..author").unwrap().len(),4);
+    assert_eq!(json.query("$..price").unwrap().len(),5);
+    assert_eq!(paths(&json, "$.store..color"),vec!["$['store']['bicycle']['color']"]);
+}
+
+#[test]
+fn index_and_negative_index(){
+    let json = store();
+    assert_eq!(strings(&json, "$..book[2].title"),vec!["Moby Dick"]);
+    assert_eq!(strings(&json, "$..book[-1].title"),vec!["The Lord of the Rings"]);
+    assert!(json.query("$..book[4]").unwrap().is_empty());
+}
+
+#[test]
+fn slice_and_union(){
+    let json = store();
+    assert_eq!(paths(&json, "$.store.book[:2]"),vec!["$['store']['book'][0]","$['store']['book'][1]"]);
+    assert_eq!(paths(&json, "$.store.book[::-2]"),vec!["$['store']['book'][3]","$['store']['book'][1]"]);
+    assert_eq!(strings(&json, "$.store.book[0,3].author"),vec!["Nigel Rees","J. R. R. Tolkien"]);
+    assert_eq!(strings(&json, "$.store.book[0]['author','title']"),vec!["Nigel Rees","Sayings of the Century"]);
+}
+
+#[test]
+fn filter_on_existence(){
+    let json = store();
+    assert_eq!(strings(&json, "$..book[?@.isbn].title"),vec!["Moby Dick","The Lord of the Rings"]);
+    assert_eq!(strings(&json, "$..book[?!@.isbn].title"),vec!["Sayings of the Century","Sword of Honour"]);
+}
+
+#[test]
+fn filter_with_comparisons(){
+    let json = store();
+    assert_eq!(strings(&json, "$..book[?@.price < 10].title"),vec!["Sayings of the Century","Moby Dick"]);
+    assert_eq!(strings(&json, "$..book[?@.price >= 12.99].title"),vec!["Sword of Honour","The Lord of the Rings"]);
+    assert_eq!(strings(&json, "$..book[?@.author == 'Herman Melville'].title"),vec!["Moby Dick"]);
+    assert_eq!(strings(&json, "$..book[?@.price == 8.950].title"),vec!["Sayings of the Century"]);
+}
+
+#[test]
+fn filter_with_logical_operators(){
+    let json = store();
+    assert_eq!(strings(&json, "$..book[?@.category == 'fiction' && @.price < 10].title"),vec!["Moby Dick"]);
+    assert_eq!(strings(&json, "$..book[?@.price < 9 || @.price > 20].title"),
+        vec!["Sayings of the Century","Moby Dick","The Lord of the Rings"]);
+    assert_eq!(strings(&json, "$..book[?!(@.category == 'fiction')].title"),vec!["Sayings of the Century"]);
+}
+
+#[test]
+fn filter_against_root(){
+    let json = store();
+    assert_eq!(strings(&json, "$.store.book[?@.price > $.store.book[0].price].title").len(),3);
+}
+
+#[test]
+fn filter_with_functions(){
+    let json = Json::parse("[{\"tags\":[\"a\",\"b\"]},{\"tags\":[\"c\"]},{\"name\":\"xyz\"}]").unwrap();
+    assert_eq!(paths(&json, "$[?length(@.tags) > 1]"),vec!["$[0]"]);
+    assert_eq!(paths(&json, "$[?count(@.*) == 1 && length(@.name) == 3]"),vec!["$[2]"]);
+    assert_eq!(paths(&json, "$[?value(@.tags[0]) == 'c']"),vec!["$[1]"]);
+}
+
+#[test]
+fn missing_values_compare_equal(){
+    let json = Json::parse("[{\"a\":1},{\"b\":2}]").unwrap();
+    assert_eq!(paths(&json, "$[?@.a == @.c]"),vec!["$[1]"]);
+}
+
+#[test]
+fn normalized_path_escapes_names(){
+    let json = Json::parse("{\"it's\":{\"a\\\\b\":true}}").unwrap();
+    assert_eq!(paths(&json, "$..*"),vec!["$['it\\'s']","$['it\\'s']['a\\\\b']"]);
+}
+
+#[test]
+fn compiled_path_is_reusable(){
+    let path = JsonPath::parse("$.id").unwrap();
+    let a = Json::parse("{\"id\":1}").unwrap();
+    let b = Json::parse("{\"id\":2}").unwrap();
+    assert_eq!(path.query(&a)[0].value,&Json::Number(String::from("1")));
+    assert_eq!(path.query(&b)[0].value,&Json::Number(String::from("2")));
+}
+
+#[test]
+fn syntax_errors(){
+    assert_eq!(JsonPath::parse("store").unwrap_err(),PathErr::UnexpectedChar(0));
+    assert_eq!(JsonPath::parse("$.store[").unwrap_err(),PathErr::UnexpectedEnd);
+    assert_eq!(JsonPath::parse("$[01]").unwrap_err(),PathErr::InvalidNumber(2));
+    assert_eq!(JsonPath::parse("$[?@..a == 1]").unwrap_err(),PathErr::NonSingularQuery(3));
+    assert_eq!(JsonPath::parse("$[?1]").unwrap_err(),PathErr::ExpectedComparison(3));
+    assert_eq!(JsonPath::parse("$[?foo(@)]").unwrap_err(),PathErr::UnknownFunction(String::from("foo")));
+    assert_eq!(JsonPath::parse("$['\\q']").unwrap_err(),PathErr::InvalidEscape(3));
+    assert_eq!(JsonPath::parse("$['\\ud83d']").unwrap_err(),PathErr::InvalidEscape(3));
+    assert_eq!(JsonPath::parse("$['a\tb']").unwrap_err(),PathErr::UnexpectedChar(4));
+}
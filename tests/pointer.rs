@@ -0,0 +1,104 @@
+use ryson::{Json,PointerErr};
+
+fn rfc_document()->Json {
+    let text = "{\"foo\":[\"bar\",\"baz\"],\"\":0,\"a/b\":1,\"c%d\":2,\"e^f\":3,\"g|h\":4,\
+        \"i\\\\j\":5,\"k\\\"l\":6,\" \":7,\"m~n\":8}";
+    Json::parse(text).unwrap()
+}
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+#[test]
+fn resolves_rfc_examples(){
+    let json = rfc_document();
+    assert_eq!(json.pointer("").unwrap(),&json);
+    assert_eq!(json.pointer("/foo").unwrap(),&Json::Array(vec![
+        Json::String(String::from("bar")),
+        Json::String(String::from("baz"))
+    ]));
+    assert_eq!(json.pointer("/foo/0").unwrap(),&Json::String(String::from("bar")));
+    assert_eq!(json.pointer("/").unwrap(),&num("0"));
+    assert_eq!(json.pointer("/a~1b").unwrap(),&num("1"));
+    assert_eq!(json.pointer("/c%d").unwrap(),&num("2"));
+    assert_eq!(json.pointer("/e^f").unwrap(),&num("3"));
+    assert_eq!(json.pointer("/g|h").unwrap(),&num("4"));
+    assert_eq!(json.pointer("/i\\j").unwrap(),&num("5"));
+    assert_eq!(json.pointer("/k\"l").unwrap(),&num("6"));
+    assert_eq!(json.pointer("/ ").unwrap(),&num("7"));
+    assert_eq!(json.pointer("/m~0n").unwrap(),&num("8"));
+}
+
+#[test]
+fn error_on_missing_slash(){
+    let json = rfc_document();
+    assert_eq!(json.pointer("foo").unwrap_err(),PointerErr::MissingSlash(String::from("foo")));
+}
+
+#[test]
+fn error_names_failing_segment(){
+    let json = Json::parse("{\"a\":[{\"b\":true}]}").unwrap();
+    assert_eq!(json.pointer("/a/0/c").unwrap_err(),PointerErr::KeyNotFound(String::from("/a/0/c")));
+    assert_eq!(json.pointer("/a/1/b").unwrap_err(),PointerErr::IndexOutOfBounds(String::from("/a/1")));
+    assert_eq!(json.pointer("/a/01").unwrap_err(),PointerErr::InvalidIndex(String::from("/a/01")));
+    assert_eq!(json.pointer("/a/x").unwrap_err(),PointerErr::InvalidIndex(String::from("/a/x")));
+    assert_eq!(json.pointer("/a/0/b/c").unwrap_err(),PointerErr::NotAContainer(String::from("/a/0/b/c")));
+    assert_eq!(json.pointer("/a~2").unwrap_err(),PointerErr::InvalidEscape(String::from("/a~2")));
+}
+
+#[test]
+fn dash_does_not_resolve_for_reads(){
+    let json = Json::parse("[1,2]").unwrap();
+    assert_eq!(json.pointer("/-").unwrap_err(),PointerErr::IndexOutOfBounds(String::from("/-")));
+}
+
+#[test]
+fn pointer_mut_changes_value(){
+    let mut json = Json::parse("{\"server\":{\"port\":80}}").unwrap();
+    *json.pointer_mut("/server/port").unwrap() = num("8080");
+    assert_eq!(json,Json::parse("{\"server\":{\"port\":8080}}").unwrap());
+}
+
+#[test]
+fn insert_sets_object_member(){
+    let mut json = Json::parse("{\"a\":{\"b\":1}}").unwrap();
+    assert_eq!(json.insert_at_pointer("/a/c", Json::Null).unwrap(),None);
+    assert_eq!(json.insert_at_pointer("/a/b", num("2")).unwrap(),Some(num("1")));
+    assert_eq!(json,Json::parse("{\"a\":{\"b\":2,\"c\":null}}").unwrap());
+}
+
+#[test]
+fn insert_into_array(){
+    let mut json = Json::parse("[1,3]").unwrap();
+    json.insert_at_pointer("/1", num("2")).unwrap();
+    json.insert_at_pointer("/-", num("4")).unwrap();
+    json.insert_at_pointer("/4", num("5")).unwrap();
+    assert_eq!(json,Json::parse("[1,2,3,4,5]").unwrap());
+    assert_eq!(json.insert_at_pointer("/6", Json::Null).unwrap_err(),
+        PointerErr::IndexOutOfBounds(String::from("/6")));
+}
+
+#[test]
+fn insert_at_root_replaces_document(){
+    let mut json = Json::parse("[1]").unwrap();
+    assert_eq!(json.insert_at_pointer("", Json::Null).unwrap(),Some(Json::parse("[1]").unwrap()));
+    assert_eq!(json,Json::Null);
+}
+
+#[test]
+fn insert_requires_existing_parent(){
+    let mut json = Json::parse("{}").unwrap();
+    assert_eq!(json.insert_at_pointer("/a/b", Json::Null).unwrap_err(),
+        PointerErr::KeyNotFound(String::from("/a")));
+}
+
+#[test]
+fn remove_members_and_elements(){
+    let mut json = Json::parse("{\"a\":[1,2,3],\"b\":true}").unwrap();
+    assert_eq!(json.remove_at_pointer("/a/1").unwrap(),num("2"));
+    assert_eq!(json.remove_at_pointer("/b").unwrap(),Json::Bool(true));
+    assert_eq!(json,Json::parse("{\"a\":[1,3]}").unwrap());
+    assert_eq!(json.remove_at_pointer("/b").unwrap_err(),PointerErr::KeyNotFound(String::from("/b")));
+    assert_eq!(json.remove_at_pointer("").unwrap_err(),PointerErr::RemoveRoot);
+}
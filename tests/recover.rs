@@ -0,0 +1,84 @@
+use ryson::{Diagnostic, Jerr, Json, json};
+
+fn recover(text:&str)->(Json,Vec<Diagnostic>) {
+    Json::parse_recovering(text)
+}
+
+fn at(error:Jerr,position:usize)->Diagnostic {
+    Diagnostic{error,position}
+}
+
+#[test]
+fn valid_input_has_no_diagnostics(){
+    let text = "{\"a\":[1,2.5,\"x\"],\"b\":{\"c\":null,\"d\":true},\"e\":{}}";
+    assert_eq!(recover(text),(Json::parse(text).unwrap(),vec![]));
+}
+
+#[test]
+fn reports_every_error(){
+    assert_eq!(recover("[1, tru, 3]"),(json!([1,3]),vec![at(Jerr::UnexpectedChar(4),4)]));
+    let (json,diagnostics) = recover("{\"a\": 1, \"b\": , \"c\": [1,,2], \"d\" 4, \"e\": 5}");
+    assert_eq!(json,json!({"a":1,"c":[1,2],"e":5}));
+    assert_eq!(diagnostics,vec![at(Jerr::ExpectedValue(14),14),at(Jerr::ExpectedValue(24),24),at(Jerr::ExpectedColon(33),33)]);
+    let (json,diagnostics) = recover("[\"x\\q\", 01, 4., \"ok\"]");
+    assert_eq!(json,json!(["ok"]));
+    assert_eq!(diagnostics,vec![
+        at(Jerr::UnknownEscape('q',3),3),
+        at(Jerr::InvalidToken(String::from("01"),8),8),
+        at(Jerr::InvalidToken(String::from("4."),12),12)
+    ]);
+}
+
+#[test]
+fn records_invalid_surrogates(){
+    let (json,diagnostics) = recover("[\"\\ud800\", 1, \"\\ud83d\\ude00\", \"\\ude00x\", 2]");
+    assert_eq!(json,json!([1,"\u{1F600}",2]));
+    assert_eq!(diagnostics,vec![
+        at(Jerr::InvalidUnicodeSequence(String::from("d800"),2),2),
+        at(Jerr::InvalidUnicodeSequence(String::from("de00"),31),31)
+    ]);
+    let (json,diagnostics) = recover("[\"\\ud800\\u0041\",2]");
+    assert_eq!(json,json!([2]));
+    assert_eq!(diagnostics,vec![at(Jerr::InvalidUnicodeSequence(String::from("d8000041"),2),2)]);
+}
+
+#[test]
+fn resynchronizes_at_commas_and_brackets(){
+    assert_eq!(recover("[1 2, 3]"),(json!([1,3]),vec![at(Jerr::ExpectedCommaOrEnd(3),3)]));
+    assert_eq!(recover("{\"a\":1,}"),(json!({"a":1}),vec![at(Jerr::ExpectedProperty(7),7)]));
+    assert_eq!(recover("[,1,,2,]").0,json!([1,2]));
+    // commas inside nested values and strings of the skipped text
+    let (json,diagnostics) = recover("{1:2, \"b\":[x [3,4] \"a,b\"], \"c\":3}");
+    assert_eq!(json,json!({"b":[],"c":3}));
+    assert_eq!(diagnostics,vec![at(Jerr::ExpectedProperty(1),1),at(Jerr::UnexpectedChar(11),11)]);
+    // a closing bracket of the wrong kind ends the inner value
+    let (json,diagnostics) = recover("[1, {\"a\": 2]]");
+    assert_eq!(json,json!([1,{"a":2}]));
+    assert_eq!(diagnostics,vec![at(Jerr::ExpectedCommaOrEnd(11),11),at(Jerr::ExpectedEnd(12),12)]);
+    assert_eq!(recover("[{\"k\":[1,}, 2]").0,json!([{"k":[1]},2]));
+}
+
+#[test]
+fn unexpected_end_keeps_partial_tree(){
+    assert_eq!(recover("{\"a\": [1, 2"),(json!({"a":[1,2]}),vec![at(Jerr::UnexpectedEnd,11)]));
+    assert_eq!(recover("[[[\"x\""),(json!([[["x"]]]),vec![at(Jerr::UnexpectedEnd,6)]));
+    assert_eq!(recover(""),(Json::Null,vec![at(Jerr::UnexpectedEnd,0)]));
+    assert_eq!(recover("nul"),(Json::Null,vec![at(Jerr::UnexpectedChar(0),0)]));
+}
+
+#[test]
+fn diagnostics_carry_line_and_column(){
+    let text = "\n  {\n    \"a\": tru,\n    \"b\": [1,,2],\n    \"c\": 3\n";
+    let (json,diagnostics) = recover(text);
+    assert_eq!(json,json!({"b":[1,2],"c":3}));
+    let positions : Vec<(usize,usize)> = diagnostics.iter().map(|diagnostic|diagnostic.line_col(text)).collect();
+    assert_eq!(positions,vec![(3,10),(4,13),(5,11)]);
+}
+
+#[test]
+fn agrees_with_parse(){
+    for text in ["[1, tru]","{\"a\" 1}","{\"a\":1 \"b\":2}","[1,]","{\"a\":1} x","[\"\\q\"]","[01]","[\"\\ud800\"]"].iter() {
+        let (_,diagnostics) = recover(text);
+        assert_eq!(diagnostics[0].error,Json::parse(text).unwrap_err(),"{}",text);
+    }
+}
@@ -0,0 +1,218 @@
+use ryson::{Json,Schema,SchemaErr,ValidationError};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn schema(text:&str)->Schema {
+    Schema::compile(&parse(text)).unwrap()
+}
+
+fn error_paths(schema:&Schema,instance:&str)->Vec<(String,String)> {
+    let mut paths : Vec<(String,String)> = schema.validate(&parse(instance)).unwrap_err().into_iter()
+        .map(|e|(e.instance_path,e.schema_path))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn pair(instance:&str,schema:&str)->(String,String) {
+    (String::from(instance),String::from(schema))
+}
+
+#[test]
+fn boolean_schemas(){
+    assert!(schema("true").is_valid(&parse("[1]")));
+    assert!(!schema("false").is_valid(&parse("null")));
+}
+
+#[test]
+fn checks_types(){
+    let s = schema("{\"type\":\"integer\"}");
+    assert!(s.is_valid(&parse("42")));
+    assert!(s.is_valid(&parse("42.0")));
+    assert!(!s.is_valid(&parse("42.5")));
+    assert!(!s.is_valid(&parse("\"42\"")));
+    let s = schema("{\"type\":[\"string\",\"null\"]}");
+    assert!(s.is_valid(&parse("null")));
+    assert!(!s.is_valid(&parse("true")));
+}
+
+#[test]
+fn reports_message_and_paths(){
+    let s = schema("{\"type\":\"object\"}");
+    assert_eq!(s.validate(&parse("[]")).unwrap_err(),vec![ValidationError{
+        instance_path:String::new(),
+        schema_path:String::from("/type"),
+        message:String::from("expected object")
+    }]);
+}
+
+#[test]
+fn object_keywords(){
+    let s = schema("{\"type\":\"object\",\
+        \"properties\":{\"name\":{\"type\":\"string\"},\"port\":{\"type\":\"integer\",\"maximum\":65535}},\
+        \"required\":[\"name\",\"port\"],\"additionalProperties\":false}");
+    assert!(s.is_valid(&parse("{\"name\":\"api\",\"port\":80}")));
+    assert_eq!(error_paths(&s, "{\"name\":1,\"port\":70000,\"extra\":true}"),vec![
+        pair("/extra","/additionalProperties"),
+        pair("/name","/properties/name/type"),
+        pair("/port","/properties/port/maximum")
+    ]);
+    assert_eq!(error_paths(&s, "{}"),vec![pair("","/required"),pair("","/required")]);
+}
+
+#[test]
+fn pattern_properties_and_property_names(){
+    let s = schema("{\"patternProperties\":{\"^x-\":{\"type\":\"string\"}},\
+        \"propertyNames\":{\"maxLength\":5},\"additionalProperties\":{\"type\":\"number\"}}");
+    assert!(s.is_valid(&parse("{\"x-id\":\"a\",\"n\":1}")));
+    assert_eq!(error_paths(&s, "{\"x-id\":1,\"n\":\"a\",\"longname\":2}"),vec![
+        pair("/longname","/propertyNames/maxLength"),
+        pair("/n","/additionalProperties/type"),
+        pair("/x-id","/patternProperties/^x-/type")
+    ]);
+}
+
+#[test]
+fn property_counts_and_dependencies(){
+    let s = schema("{\"minProperties\":1,\"maxProperties\":2,\
+        \"dependentRequired\":{\"card\":[\"cvv\"]},\
+        \"dependentSchemas\":{\"tls\":{\"required\":[\"cert\"]}}}");
+    assert!(!s.is_valid(&parse("{}")));
+    assert!(!s.is_valid(&parse("{\"a\":1,\"b\":2,\"c\":3}")));
+    assert_eq!(error_paths(&s, "{\"card\":1}"),vec![pair("","/dependentRequired")]);
+    assert_eq!(error_paths(&s, "{\"tls\":true}"),vec![pair("","/dependentSchemas/tls/required")]);
+}
+
+#[test]
+fn array_keywords(){
+    let s = schema("{\"prefixItems\":[{\"type\":\"string\"}],\"items\":{\"type\":\"integer\"},\
+        \"minItems\":2,\"maxItems\":4,\"uniqueItems\":true}");
+    assert!(s.is_valid(&parse("[\"a\",1,2]")));
+    assert_eq!(error_paths(&s, "[1]"),vec![pair("","/minItems"),pair("/0","/prefixItems/0/type")]);
+    assert_eq!(error_paths(&s, "[\"a\",1,1.0,true,2]"),vec![
+        pair("","/maxItems"),pair("","/uniqueItems"),pair("/3","/items/type")
+    ]);
+}
+
+#[test]
+fn contains_with_bounds(){
+    let s = schema("{\"contains\":{\"const\":1},\"minContains\":2,\"maxContains\":3}");
+    assert!(s.is_valid(&parse("[1,2,1]")));
+    assert!(!s.is_valid(&parse("[1,2]")));
+    assert!(!s.is_valid(&parse("[1,1,1,1]")));
+    assert!(schema("{\"contains\":{\"const\":1},\"minContains\":0}").is_valid(&parse("[]")));
+}
+
+#[test]
+fn enum_and_const_compare_numbers_by_value(){
+    let s = schema("{\"enum\":[\"red\",1,[true]]}");
+    assert!(s.is_valid(&parse("1.0")));
+    assert!(s.is_valid(&parse("[true]")));
+    assert!(!s.is_valid(&parse("\"blue\"")));
+    assert!(schema("{\"const\":{\"a\":2}}").is_valid(&parse("{\"a\":2.00}")));
+}
+
+#[test]
+fn numeric_constraints(){
+    let mut json = parse("{\"minimum\":1.5,\"exclusiveMaximum\":10,\"multipleOf\":null}");
+    *json.pointer_mut("/multipleOf").unwrap() = Json::Number(String::from("0.5"));
+    let s = Schema::compile(&json).unwrap();
+    assert!(s.is_valid(&parse("1.5")));
+    assert!(s.is_valid(&parse("9.5")));
+    assert_eq!(error_paths(&s, "10"),vec![pair("","/exclusiveMaximum")]);
+    assert_eq!(error_paths(&s, "1"),vec![pair("","/minimum")]);
+    assert_eq!(error_paths(&s, "2.25"),vec![pair("","/multipleOf")]);
+    let mut json = parse("{\"multipleOf\":null}");
+    *json.pointer_mut("/multipleOf").unwrap() = Json::Number(String::from("0.01"));
+    assert!(Schema::compile(&json).unwrap().is_valid(&parse("19.99")));
+}
+
+#[test]
+fn string_constraints(){
+    let s = schema("{\"minLength\":2,\"maxLength\":4,\"pattern\":\"^[a-z]+$\"}");
+    assert!(s.is_valid(&parse("\"abc\"")));
+    assert!(!s.is_valid(&parse("\"ϴϴ\"")));
+    assert_eq!(error_paths(&s, "\"a\""),vec![pair("","/minLength")]);
+    assert_eq!(error_paths(&s, "\"ABCDE\""),vec![pair("","/maxLength"),pair("","/pattern")]);
+    assert!(schema("{\"minLength\":2,\"maxLength\":2}").is_valid(&parse("\"ϴϴ\"")));
+}
+
+#[test]
+fn combinators(){
+    let s = schema("{\"anyOf\":[{\"type\":\"string\"},{\"type\":\"integer\"}]}");
+    assert!(s.is_valid(&parse("1")));
+    assert_eq!(error_paths(&s, "true"),vec![pair("","/anyOf")]);
+
+    let s = schema("{\"oneOf\":[{\"multipleOf\":2},{\"multipleOf\":3}]}");
+    assert!(s.is_valid(&parse("4")));
+    assert!(!s.is_valid(&parse("6")));
+    assert!(!s.is_valid(&parse("5")));
+
+    let s = schema("{\"allOf\":[{\"minimum\":2},{\"maximum\":4}],\"not\":{\"const\":3}}");
+    assert!(s.is_valid(&parse("2")));
+    assert_eq!(error_paths(&s, "3"),vec![pair("","/not")]);
+    assert_eq!(error_paths(&s, "5"),vec![pair("","/allOf/1/maximum")]);
+}
+
+#[test]
+fn if_then_else(){
+    let s = schema("{\"if\":{\"properties\":{\"kind\":{\"const\":\"tcp\"}}},\
+        \"then\":{\"required\":[\"port\"]},\"else\":{\"required\":[\"path\"]}}");
+    assert!(s.is_valid(&parse("{\"kind\":\"tcp\",\"port\":1}")));
+    assert!(s.is_valid(&parse("{\"kind\":\"unix\",\"path\":\"/tmp/s\"}")));
+    assert_eq!(error_paths(&s, "{\"kind\":\"tcp\"}"),vec![pair("","/then/required")]);
+    assert_eq!(error_paths(&s, "{\"kind\":\"unix\"}"),vec![pair("","/else/required")]);
+}
+
+#[test]
+fn local_references(){
+    let s = schema("{\"$defs\":{\"port\":{\"type\":\"integer\",\"maximum\":65535},\
+        \"node\":{\"$anchor\":\"node\",\"properties\":{\"children\":{\"items\":{\"$ref\":\"#node\"}}},\"required\":[\"id\"]}},\
+        \"properties\":{\"port\":{\"$ref\":\"#/$defs/port\"},\"tree\":{\"$ref\":\"#node\"}}}");
+    assert!(s.is_valid(&parse("{\"port\":80,\"tree\":{\"id\":1,\"children\":[{\"id\":2}]}}")));
+    assert_eq!(error_paths(&s, "{\"port\":70000}"),vec![pair("/port","/properties/port/$ref/maximum")]);
+    assert_eq!(error_paths(&s, "{\"tree\":{\"id\":1,\"children\":[{}]}}"),vec![
+        pair("/tree/children/0","/properties/tree/$ref/properties/children/items/$ref/required")
+    ]);
+}
+
+#[test]
+fn references_outside_keywords(){
+    let s = schema("{\"definitions\":{\"s\":{\"pattern\":\"^a\",\"patternProperties\":{\"^x\":{\"type\":\"string\"}},\
+        \"dependentRequired\":{\"a\":[\"b\"]}}},\"$ref\":\"#/definitions/s\"}");
+    assert!(s.is_valid(&parse("\"abc\"")));
+    assert!(!s.is_valid(&parse("\"b\"")));
+    assert!(!s.is_valid(&parse("{\"x\":1}")));
+    assert!(!s.is_valid(&parse("{\"a\":1}")));
+    assert_eq!(Schema::compile(&parse("{\"definitions\":{\"s\":{\"pattern\":\"(\"}},\"$ref\":\"#/definitions/s\"}")).unwrap_err(),
+        SchemaErr::InvalidPattern(String::from("/definitions/s/pattern")));
+    assert_eq!(Schema::compile(&parse("{\"definitions\":{\"s\":{\"$ref\":\"#/nowhere\"}},\"$ref\":\"#/definitions/s\"}")).unwrap_err(),
+        SchemaErr::UnresolvedRef(String::from("/definitions/s/$ref")));
+}
+
+#[test]
+fn recursive_reference_to_root(){
+    let s = schema("{\"type\":\"array\",\"items\":{\"$ref\":\"#\"}}");
+    assert!(s.is_valid(&parse("[[],[[]]]")));
+    assert!(!s.is_valid(&parse("[[1]]")));
+}
+
+#[test]
+fn collects_all_errors(){
+    let s = schema("{\"items\":{\"type\":\"string\"}}");
+    assert_eq!(s.validate(&parse("[1,\"a\",true,null]")).unwrap_err().len(),3);
+}
+
+#[test]
+fn compile_errors(){
+    assert_eq!(Schema::compile(&parse("1")).unwrap_err(),SchemaErr::InvalidSchema(String::new()));
+    assert_eq!(Schema::compile(&parse("{\"type\":\"text\"}")).unwrap_err(),SchemaErr::InvalidKeyword(String::from("/type")));
+    assert_eq!(Schema::compile(&parse("{\"properties\":{\"a\":3}}")).unwrap_err(),
+        SchemaErr::InvalidSchema(String::from("/properties/a")));
+    assert_eq!(Schema::compile(&parse("{\"pattern\":\"(\"}")).unwrap_err(),SchemaErr::InvalidPattern(String::from("/pattern")));
+    assert_eq!(Schema::compile(&parse("{\"items\":{\"$ref\":\"#/$defs/missing\"}}")).unwrap_err(),
+        SchemaErr::UnresolvedRef(String::from("/items/$ref")));
+    assert_eq!(Schema::compile(&parse("{\"minLength\":1.5}")).unwrap_err(),SchemaErr::InvalidKeyword(String::from("/minLength")));
+}
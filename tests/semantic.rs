@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use ryson::Json;
+
+fn num(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+#[test]
+fn numbers_equal_by_value(){
+    assert!(num("1.0").semantic_eq(&num("1")));
+    assert!(num("1.50").semantic_eq(&num("1.5")));
+    assert!(num("100").semantic_eq(&num("1e2")));
+    assert!(num("-0").semantic_eq(&num("0.000")));
+}
+
+#[test]
+fn different_numbers_not_equal(){
+    assert!(!num("1.01").semantic_eq(&num("1.1")));
+    assert!(!num("-1").semantic_eq(&num("1")));
+}
+
+#[test]
+fn structural_eq_still_compares_text(){
+    assert_ne!(num("1.0"),num("1"));
+}
+
+#[test]
+fn semantic_eq_on_nested_values(){
+    let a = Json::parse("{\"price\":[1.50,2],\"tag\":\"x\"}").unwrap();
+    let b = Json::parse("{\"tag\":\"x\",\"price\":[1.5,2.000]}").unwrap();
+    assert!(a.semantic_eq(&b));
+    let c = Json::parse("{\"tag\":\"x\",\"price\":[1.5]}").unwrap();
+    assert!(!a.semantic_eq(&c));
+}
+
+#[test]
+fn semantic_eq_does_not_mix_types(){
+    assert!(!num("1").semantic_eq(&Json::String(String::from("1"))));
+}
+
+#[test]
+fn normalize_numbers_in_tree(){
+    let mut map = HashMap::new();
+    map.insert(String::from("a"), num("20.0"));
+    let mut json = Json::Array(vec![num("1.500"),Json::Object(map),num("1e-3"),num("100")]);
+    json.normalize();
+
+    let mut map = HashMap::new();
+    map.insert(String::from("a"), num("20"));
+    assert_eq!(json,Json::Array(vec![num("1.5"),Json::Object(map),num("0.001"),num("100")]));
+}
+
+#[test]
+fn normalize_uses_exponent_for_extreme_values(){
+    assert_eq!(num("1000000000000000000000000").normalized(),num("1e+24"));
+    assert_eq!(num("0.00000012").normalized(),num("1.2e-7"));
+    assert_eq!(num("-12E3").normalized(),num("-12000"));
+}
+
+#[test]
+fn normalize_keeps_invalid_numbers(){
+    assert_eq!(num("12..").normalized(),num("12.."));
+}
@@ -0,0 +1,63 @@
+use std::io::{self,Write};
+use ryson::{Json,json};
+
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self,_:&[u8])->io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe,"closed"))
+    }
+    fn flush(&mut self)->io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn to_vec_writes_standard_json(){
+    let json = json!({"say \"hi\"":["tab\there","back\\slash","\u{1}",null,1.5,true]});
+    let text = String::from_utf8(json.to_vec()).unwrap();
+    assert_eq!(text,"{\"say \\\"hi\\\"\":[\"tab\\there\",\"back\\\\slash\",\"\\u0001\",null,1.5,true]}");
+}
+
+#[test]
+fn output_parses_back(){
+    let json = Json::parse("{\"a\":[1,{\"b\":\"line\\nbreak \\u00e9\"}],\"c\":{}}").unwrap();
+    let text = String::from_utf8(json.to_vec()).unwrap();
+    assert_eq!(Json::parse(&text).unwrap(),json);
+}
+
+#[test]
+fn to_writer_writes_to_io_sinks(){
+    let json = json!([1,"two",{"three":3}]);
+    let mut out = vec![];
+    json.to_writer(&mut out).unwrap();
+    assert_eq!(out,json.to_vec());
+    let err = json.to_writer(FailingWriter).unwrap_err();
+    assert_eq!(err.kind(),io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn write_json_writes_to_fmt_sinks(){
+    let mut out = String::from("value=");
+    json!({"k":"v"}).write_json(&mut out).unwrap();
+    assert_eq!(out,"value={\"k\":\"v\"}");
+}
+
+#[test]
+fn display_keeps_its_format(){
+    let json = json!({"key":["a\"b"]});
+    assert_eq!(json.to_string(),"{key:[\"a\"b\"]}");
+    assert_eq!(format!("{:?}",json),"{key:[\"a\"b\"]}");
+}
+
+#[test]
+fn serializes_deep_and_wide_trees(){
+    let mut json = json!([]);
+    for i in 0..200 {
+        json = json!({"level":i,"items":[json,"x"]});
+    }
+    let text = String::from_utf8(json.to_vec()).unwrap();
+    assert_eq!(text.matches("level").count(),200);
+    let wide = Json::Array((0..10000).map(Json::from).collect());
+    assert_eq!(wide.to_vec().len(),wide.to_string().len());
+}
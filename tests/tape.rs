@@ -0,0 +1,68 @@
+use ryson::{Json,Tape};
+
+const TEXT : &str = "{\"id\":7,\"tags\":[\"a\",\"b\\n\"],\"meta\":{\"ok\":true,\"none\":null},\"after\":[[1],[]]}";
+
+#[test]
+fn navigates_with_cursors(){
+    let tape = Tape::parse(TEXT).unwrap();
+    let root = tape.root();
+    assert!(root.is_object());
+    assert_eq!(root.count(),Some(4));
+    assert_eq!(root.get("id").unwrap().as_number(),Some("7"));
+    let tags = root.get("tags").unwrap();
+    assert!(tags.is_array());
+    assert_eq!(tags.at(1).unwrap().as_string(),Some("b\n"));
+    assert!(tags.at(2).is_none());
+    let meta = root.get("meta").unwrap();
+    assert_eq!(meta.get("ok").unwrap().as_bool(),Some(true));
+    assert!(meta.get("none").unwrap().is_null());
+    assert!(root.get("missing").is_none());
+    // siblings after nested containers are found by skipping subtrees
+    let after = root.get("after").unwrap();
+    assert_eq!(after.at(0).unwrap().at(0).unwrap().as_number(),Some("1"));
+    assert_eq!(after.at(1).unwrap().count(),Some(0));
+}
+
+#[test]
+fn iterates_items_and_members(){
+    let tape = Tape::parse(TEXT).unwrap();
+    let keys : Vec<&str> = tape.root().members().map(|(key,_)|key).collect();
+    assert_eq!(keys,vec!["id","tags","meta","after"]);
+    let tags : Vec<&str> = tape.root().get("tags").unwrap().items().filter_map(|item|item.as_string()).collect();
+    assert_eq!(tags,vec!["a","b\n"]);
+    assert_eq!(tape.root().items().count(),0);
+    assert_eq!(tape.root().get("id").unwrap().members().count(),0);
+}
+
+#[test]
+fn converts_to_json(){
+    let texts = [
+        TEXT,
+        "null",
+        "\"text\"",
+        "[]",
+        "{}",
+        " [ {\"a\" : [ {} , [ ] ]} , 2.5 ] ",
+        "{\"k\":1,\"k\":2}"
+    ];
+    for text in texts.iter() {
+        let tape = Tape::parse(text).unwrap();
+        assert_eq!(tape.to_json(),Json::parse(text).unwrap(),"{}",text);
+    }
+    let tape = Tape::parse(TEXT).unwrap();
+    assert_eq!(tape.root().get("meta").unwrap().to_json(),Json::parse("{\"ok\":true,\"none\":null}").unwrap());
+}
+
+#[test]
+fn one_node_per_value_and_key(){
+    let tape = Tape::parse("{\"a\":[1,2],\"b\":\"c\"}").unwrap();
+    assert_eq!(tape.node_count(),7);
+}
+
+#[test]
+fn errors_match_parse(){
+    let texts = ["[1,,2]","{\"a\":}","{\"a\" 1}","[1 2]","{1:2}","\"abc","[1]]"];
+    for text in texts.iter() {
+        assert_eq!(Tape::parse(text).unwrap_err(),Json::parse(text).unwrap_err(),"{}",text);
+    }
+}
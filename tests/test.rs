@@ -47,7 +47,7 @@ fn error_on_non_digits_after_digits(){
 fn error_on_non_zero_starting_with_zero(){
     let text = String::from("0916");
     let jerr = Json::parse(&text).unwrap_err();
-    assert_eq!(jerr,Jerr::InvalidToken(String::from("0916")));
+    assert_eq!(jerr,Jerr::InvalidToken(String::from("0916"),0));
 }
 
 #[test]
@@ -61,10 +61,24 @@ fn accepts_rationals(){
 fn error_on_ending_dot(){
     let text = String::from("1624.");
     let jerr = Json::parse(&text).unwrap_err();
-    assert_eq!(jerr,Jerr::InvalidToken(String::from("1624.")));
+    assert_eq!(jerr,Jerr::InvalidToken(String::from("1624."),0));
 }
 
 
+#[test]
+fn accepts_negative_numbers_and_exponents(){
+    for text in ["-1","-0.5","1e5","2E+10","-0.5e-3"].iter() {
+        assert_eq!(Json::parse(text).unwrap(),Json::Number(String::from(*text)));
+    }
+}
+
+#[test]
+fn error_on_incomplete_sign_or_exponent(){
+    for text in ["-","-01","1e","1e+","1.5E"].iter() {
+        assert_eq!(Json::parse(text).unwrap_err(),Jerr::InvalidToken(String::from(*text),0));
+    }
+}
+
 #[test]
 fn error_on_beginning_dot(){
     let text = String::from(".234567");
@@ -121,6 +135,13 @@ fn escapes_criagereturn_tab_newline_formfeed_backspace(){
     assert_eq!(json,Json::String(String::from("escaped:\n\thello\x08\x0Ctext file\r")));
 }
 
+#[test]
+fn escapes_solidus(){
+    let text = String::from("\"a\\/b\"");
+    let json = Json::parse(&text).unwrap();
+    assert_eq!(json,Json::String(String::from("a/b")));
+}
+
 #[test]
 fn escapes_unicode(){
     let text = String::from("\"this is theta : \\u03F4\"");
@@ -132,14 +153,14 @@ fn escapes_unicode(){
 fn error_on_invalid_unicode(){
     let text = String::from("\"this is invalid : \\u93G4\"");
     let jerr = Json::parse(&text).unwrap_err();
-    assert_eq!(jerr,Jerr::InvalidUnicodeSequence(String::from("93G4")));
+    assert_eq!(jerr,Jerr::InvalidUnicodeSequence(String::from("93G4"),19));
 }
 
 #[test]
 fn error_on_unknown_escape(){
     let text = String::from("\"I don't know \\a\"");
     let jerr = Json::parse(&text).unwrap_err();
-    assert_eq!(jerr,Jerr::UnknownEscape('a'));
+    assert_eq!(jerr,Jerr::UnknownEscape('a',14));
 }
 
 #[test]
@@ -304,6 +325,21 @@ fn accepts_empty_object(){
     assert_eq!(json,Json::Object(HashMap::new()));
 }
 
+#[test]
+fn accepts_empty_object_with_white_space(){
+    let text = String::from("{ \r\n}");
+    let json = Json::parse(&text).unwrap();
+    assert_eq!(json,Json::Object(HashMap::new()));
+}
+
+#[test]
+fn accepts_carriage_returns(){
+    let text = String::from("{\"a\":-1,\r\n\"b\":[1,\r\n2]}\r\n");
+    let json = Json::parse(&text).unwrap();
+    assert_eq!(json["a"],Json::Number(String::from("-1")));
+    assert_eq!(json["b"],Json::Array(vec![Json::Number(String::from("1")),Json::Number(String::from("2"))]));
+}
+
 #[test]
 fn to_string_null(){
     let json = Json::Null;
@@ -0,0 +1,84 @@
+use ryson::{Json,JsonWriter,WriterErr,json};
+
+fn text(bytes:Vec<u8>)->String {
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn writes_nested_documents(){
+    let mut writer = JsonWriter::new(vec![]);
+    writer.begin_object().unwrap();
+    writer.key("rows").unwrap();
+    writer.begin_array().unwrap();
+    for i in 0..3 {
+        writer.begin_object().unwrap();
+        writer.key("id").unwrap();
+        writer.value(&json!(i)).unwrap();
+        writer.key("tags").unwrap();
+        writer.value(&json!(["a\"b"])).unwrap();
+        writer.end_object().unwrap();
+    }
+    writer.end_array().unwrap();
+    writer.key("empty").unwrap();
+    writer.begin_object().unwrap();
+    writer.end_object().unwrap();
+    writer.end_object().unwrap();
+    let out = text(writer.finish().unwrap());
+    assert_eq!(out,"{\"rows\":[{\"id\":0,\"tags\":[\"a\\\"b\"]},{\"id\":1,\"tags\":[\"a\\\"b\"]},{\"id\":2,\"tags\":[\"a\\\"b\"]}],\"empty\":{}}");
+    assert_eq!(Json::parse(&out).unwrap()["rows"][2]["id"],json!(2));
+}
+
+#[test]
+fn writes_a_single_value(){
+    let mut writer = JsonWriter::new(vec![]);
+    writer.value(&json!("only")).unwrap();
+    assert!(matches!(writer.value(&json!(1)),Err(WriterErr::DocumentComplete)));
+    assert!(matches!(writer.begin_array(),Err(WriterErr::DocumentComplete)));
+    assert_eq!(text(writer.finish().unwrap()),"\"only\"");
+}
+
+#[test]
+fn rejects_values_without_keys(){
+    let mut writer = JsonWriter::new(vec![]);
+    writer.begin_object().unwrap();
+    assert!(matches!(writer.value(&json!(1)),Err(WriterErr::ExpectedKey)));
+    assert!(matches!(writer.begin_array(),Err(WriterErr::ExpectedKey)));
+    writer.key("a").unwrap();
+    assert!(matches!(writer.key("b"),Err(WriterErr::ExpectedValue)));
+    assert!(matches!(writer.end_object(),Err(WriterErr::ExpectedValue)));
+    writer.value(&json!(1)).unwrap();
+    assert!(matches!(writer.value(&json!(2)),Err(WriterErr::ExpectedKey)));
+    writer.end_object().unwrap();
+    assert_eq!(text(writer.finish().unwrap()),"{\"a\":1}");
+}
+
+#[test]
+fn rejects_keys_outside_objects(){
+    let mut writer = JsonWriter::new(vec![]);
+    assert!(matches!(writer.key("a"),Err(WriterErr::UnexpectedKey)));
+    writer.begin_array().unwrap();
+    assert!(matches!(writer.key("a"),Err(WriterErr::UnexpectedKey)));
+}
+
+#[test]
+fn rejects_unbalanced_ends(){
+    let mut writer = JsonWriter::new(vec![]);
+    assert!(matches!(writer.end_array(),Err(WriterErr::UnbalancedEnd)));
+    writer.begin_array().unwrap();
+    writer.begin_object().unwrap();
+    assert!(matches!(writer.end_array(),Err(WriterErr::UnbalancedEnd)));
+    writer.end_object().unwrap();
+    assert!(matches!(writer.end_object(),Err(WriterErr::UnbalancedEnd)));
+    assert_eq!(writer.depth(),1);
+    writer.end_array().unwrap();
+    assert_eq!(text(writer.finish().unwrap()),"[{}]");
+}
+
+#[test]
+fn finish_requires_a_complete_document(){
+    assert!(matches!(JsonWriter::new(vec![]).finish(),Err(WriterErr::Unfinished)));
+    let mut writer = JsonWriter::new(vec![]);
+    writer.begin_array().unwrap();
+    writer.value(&json!(null)).unwrap();
+    assert!(matches!(writer.finish(),Err(WriterErr::Unfinished)));
+}
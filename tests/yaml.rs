@@ -0,0 +1,221 @@
+use ryson::{Json, YamlErr, json};
+
+fn parse(text:&str)->Json {
+    Json::parse(text).unwrap()
+}
+
+fn read(text:&str)->Json {
+    Json::from_yaml(text).unwrap()
+}
+
+fn number(text:&str)->Json {
+    Json::Number(String::from(text))
+}
+
+#[test]
+fn emits_block_style(){
+    let json = parse("{\"name\":\"web\",\"replicas\":3,\"ports\":[{\"port\":80,\"tls\":false},{\"port\":443,\"tls\":true}],\"labels\":{\"app\":\"web\",\"tier\":null},\"args\":[[\"a\",\"b\"],[]],\"extra\":{}}");
+    let expected = "\
+args:
+  - - a
+    - b
+  - []
+extra: {}
+labels:
+  app: web
+  tier: null
+name: web
+ports:
+  - port: 80
+    tls: false
+  - port: 443
+    tls: true
+replicas: 3
+";
+    assert_eq!(json.to_yaml(),expected);
+    assert_eq!(Json::Null.to_yaml(),"null\n");
+    assert_eq!(Json::from("text").to_yaml(),"text\n");
+    assert_eq!(json!([]).to_yaml(),"[]\n");
+    assert_eq!(json!({"n":number("1.50e+3")}).to_yaml(),"n: 1.50e+3\n");
+}
+
+#[test]
+fn quotes_strings_when_needed(){
+    let cases = [
+        ("plain text","plain text"),
+        ("a:b","a:b"),
+        ("http://example.com/x?y=1","http://example.com/x?y=1"),
+        ("",r#""""#),
+        ("true",r#""true""#),
+        ("Null",r#""Null""#),
+        ("~",r#""~""#),
+        ("yes",r#""yes""#),
+        ("off",r#""off""#),
+        ("12",r#""12""#),
+        ("0x1F",r#""0x1F""#),
+        ("1.2.3",r#""1.2.3""#),
+        (".inf",r#"".inf""#),
+        ("- item",r#""- item""#),
+        ("key: value",r#""key: value""#),
+        ("ends:",r#""ends:""#),
+        ("a #b",r#""a #b""#),
+        ("#tag","\"#tag\""),
+        ("*alias",r#""*alias""#),
+        (" padded",r#"" padded""#),
+        ("padded ",r#""padded ""#),
+        ("---",r#""---""#),
+        ("say \"hi\"\\","say \"hi\"\\"),
+        ("'single'",r#""'single'""#),
+        ("bell\u{7}\r",r#""bell\x07\r""#)
+    ];
+    for (str,yaml) in cases.iter() {
+        assert_eq!(Json::from(*str).to_yaml(),format!("{}\n",yaml),"{}",str);
+        assert_eq!(read(yaml),Json::from(*str),"{}",yaml);
+    }
+    assert_eq!(json!({"true":1,"a b":2}).to_yaml(),"a b: 2\n\"true\": 1\n");
+}
+
+#[test]
+fn multi_line_strings(){
+    let json = json!({"script":"set -e\nmake\n","note":"line one\n  indented\nlast","keep":"a\n\n","lead":" x\ny"});
+    let expected = "\
+keep: |+
+  a
+
+lead: \" x\\ny\"
+note: |-
+  line one
+    indented
+  last
+script: |
+  set -e
+  make
+";
+    assert_eq!(json.to_yaml(),expected);
+    assert_eq!(read(expected),json);
+    assert_eq!(json!(["a\nb"]).to_yaml(),"- |-\n  a\n  b\n");
+}
+
+#[test]
+fn reads_block_collections(){
+    let text = "\
+# deployment
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web   # the service
+  labels: {app: web, tier: frontend}
+spec:
+  replicas: 3
+  containers:
+  - name: nginx
+    image: \"nginx:1.25\"
+    ports:
+      - containerPort: 80
+      -   containerPort: 443
+    args: [--port, '8080']
+  -
+    name: sidecar
+    env:
+  empty:
+";
+    let expected = parse("{\"apiVersion\":\"apps/v1\",\"kind\":\"Deployment\",\"metadata\":{\"name\":\"web\",\"labels\":{\"app\":\"web\",\"tier\":\"frontend\"}},\"spec\":{\"replicas\":3,\"containers\":[{\"name\":\"nginx\",\"image\":\"nginx:1.25\",\"ports\":[{\"containerPort\":80},{\"containerPort\":443}],\"args\":[\"--port\",\"8080\"]},{\"name\":\"sidecar\",\"env\":null}],\"empty\":null}}");
+    assert_eq!(read(text),expected);
+    assert_eq!(read("- - a\n  - b\n- c\n-\n"),json!([["a","b"],"c",null]));
+    assert_eq!(read("---\na: 1\n...\n"),json!({"a":1}));
+    assert_eq!(read("--- text\n"),Json::from("text"));
+    assert_eq!(read(""),Json::Null);
+    assert_eq!(read("# nothing\n"),Json::Null);
+}
+
+#[test]
+fn reads_json(){
+    let text = "{\"a\": [1, 2.5, \"x\\u00e9\"], \"b\": {\"c\": null, \"d\": true}, \"e\":{}, \"f\":[]}";
+    assert_eq!(read(text),parse(text));
+    assert_eq!(read("\"\\ud83d\\ude00\""),Json::from("\u{1F600}"));
+    assert_eq!(read("{\"a\":1,\n \"b\":\n   [true,\n    false]\n}"),json!({"a":1,"b":[true,false]}));
+    assert_eq!(read("[a, b c, 'd,e', {f: 1, g}, ]"),json!(["a","b c","d,e",{"f":1,"g":null}]));
+}
+
+#[test]
+fn resolves_scalars(){
+    let text = "[~, null, NULL, True, FALSE, 12, +12, -3, 007, 0o17, 0x1F, 1.50, .5, -1., 1e3, 2.5E-2, 1_000, 1.2.3, yes, .inf]";
+    assert_eq!(Json::from_yaml(text).unwrap_err(),YamlErr::Unsupported(1,107));
+    let text = &text[..text.len() - 7];
+    let expected = Json::Array(vec![
+        Json::Null,Json::Null,Json::Null,Json::Bool(true),Json::Bool(false),
+        number("12"),number("12"),number("-3"),number("7"),number("15"),number("31"),
+        number("1.50"),number("0.5"),number("-1"),number("1e3"),number("2.5E-2"),
+        Json::from("1_000"),Json::from("1.2.3"),Json::from("yes")
+    ]);
+    assert_eq!(read(&format!("{}]",text)),expected);
+    assert_eq!(read("0x123456789abcdef0123"),number("5373003642731685151011"));
+    assert_eq!(read("key: \"12\"\n'x': '007'"),json!({"key":"12","x":"007"}));
+}
+
+#[test]
+fn quoted_and_block_scalars(){
+    assert_eq!(read(r#""tab\there \"q\" \x41\u00e9\U0001F600 \/""#),Json::from("tab\there \"q\" A\u{e9}\u{1F600} /"));
+    assert_eq!(read("'it''s'"),Json::from("it's"));
+    assert_eq!(read("\"one\n  two\n\n  three\\\n  four\""),Json::from("one two\nthreefour"));
+    assert_eq!(read("a: plain\n  continued\n\n  here\nb: 1"),json!({"a":"plain continued\nhere","b":1}));
+    let text = "\
+literal: |
+  line one
+    indented
+
+  last
+folded: >
+  some folded
+  text
+
+  new paragraph
+    kept
+  end
+strip: |-
+  x
+
+keep: |+
+  x
+
+indicator: |2
+   leading space
+";
+    assert_eq!(read(text),json!({
+        "literal":"line one\n  indented\n\nlast\n",
+        "folded":"some folded text\nnew paragraph\n  kept\nend\n",
+        "strip":"x",
+        "keep":"x\n\n",
+        "indicator":" leading space\n"
+    }));
+    assert_eq!(read("- >-\n  a\n  b\n- c"),json!(["a b","c"]));
+}
+
+#[test]
+fn round_trips(){
+    let json = parse("{\"name\":\"app\",\"version\":\"1.10\",\"count\":0,\"ratio\":12.125,\"tags\":[\"\",\"null\",\"- x\",\"multi\\nline\\n\"],\"nested\":[[{\"a\":[]}],{\"b\":{\"c\":{}}}],\"quote\":\"say \\\"hi\\\"\",\"unicode\":\"\u{6c34}\\u0001\"}");
+    assert_eq!(read(&json.to_yaml()),json);
+}
+
+#[test]
+fn errors(){
+    let err = |text:&str|Json::from_yaml(text).unwrap_err();
+    assert_eq!(err("a: 1\na: 2\n"),YamlErr::DuplicateKey(2,1));
+    assert_eq!(err("{a: 1, a: 2}"),YamlErr::DuplicateKey(1,8));
+    assert_eq!(err("a: [1]\n  b: 2\n"),YamlErr::InvalidIndentation(2,3));
+    assert_eq!(err("a: 1\n   b: 2\n"),YamlErr::UnexpectedChar(2,5));
+    assert_eq!(err("a:\n\t- b\n"),YamlErr::InvalidIndentation(2,1));
+    assert_eq!(err("a: b: c\n"),YamlErr::UnexpectedChar(1,5));
+    assert_eq!(err("a: [1, 2\n"),YamlErr::UnexpectedEnd(2,1));
+    assert_eq!(err("a: \"\\q\""),YamlErr::InvalidEscape(1,5));
+    assert_eq!(err("a: &anchor 1\n"),YamlErr::Unsupported(1,4));
+    assert_eq!(err("- *alias\n"),YamlErr::Unsupported(1,3));
+    assert_eq!(err("a: !!str 1\n"),YamlErr::Unsupported(1,4));
+    assert_eq!(err("a: 1\n---\nb: 2\n"),YamlErr::Unsupported(2,1));
+    assert_eq!(err("- a\nb: 1\n"),YamlErr::UnexpectedChar(2,1));
+    assert_eq!(err("x: .nan").line_col(),(1,4));
+    assert_eq!(err(&"[".repeat(200000)),YamlErr::TooDeep(1,256));
+    assert_eq!(err(&"{a: ".repeat(200000)),YamlErr::TooDeep(1,1021));
+    assert_eq!(err(&"- ".repeat(200000)),YamlErr::TooDeep(1,513));
+    assert!(Json::from_yaml(&format!("{}1{}","[".repeat(254),"]".repeat(254))).is_ok());
+}